@@ -17,4 +17,17 @@ pub enum Action {
     Refresh,
     Error(String),
     Help,
+    HotReload,
+    HotRestart,
+    RestartSession,
+    Bell,
+    EnterInputMode,
+    ExitInputMode,
+    /// Open a file in the user's editor at a one-based line and column, suspending the UI while a
+    /// terminal editor runs.
+    OpenInEditor {
+        path: String,
+        line: usize,
+        column: usize,
+    },
 }
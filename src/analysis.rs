@@ -0,0 +1,126 @@
+use std::{collections::BTreeMap, path::Path, process::Stdio};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use strum::Display;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Severity {
+    #[strum(to_string = "error")]
+    Error,
+    #[strum(to_string = "warning")]
+    Warning,
+    #[strum(to_string = "info")]
+    Info,
+}
+
+/// A problem reported by `dart analyze --format=json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub code: String,
+    pub severity: Severity,
+    pub location: Location,
+    pub problem_message: String,
+    pub correction_message: Option<String>,
+    pub documentation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Location {
+    pub file: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Range {
+    pub start: Position,
+}
+
+/// One-based line and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Deserialize)]
+struct Report {
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn parse(output: &str) -> Result<Vec<Diagnostic>> {
+    let start = output
+        .find('{')
+        .ok_or(eyre!("dart analyze did not print a report"))?;
+    let report: Report = serde_json::from_str(output[start..].trim_end())?;
+    Ok(report.diagnostics)
+}
+
+/// Analyze the project. `dart analyze` exits with an error code when it finds problems, so only
+/// a missing report counts as a failure.
+pub async fn analyze(project_root: &Path) -> Result<Vec<Diagnostic>> {
+    let output = Command::new("dart")
+        .args(["analyze", "--format=json", "."])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    parse(&String::from_utf8_lossy(&output.stdout)).map_err(|e| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.trim() {
+            "" => e,
+            stderr => eyre!("dart analyze exited with {}: {}", output.status, stderr),
+        }
+    })
+}
+
+/// Diagnostics by file, with each file's sorted by position.
+pub fn group_by_file(diagnostics: &[Diagnostic]) -> BTreeMap<String, Vec<Diagnostic>> {
+    let mut files = BTreeMap::<String, Vec<Diagnostic>>::new();
+    for diagnostic in diagnostics {
+        files
+            .entry(diagnostic.location.file.clone())
+            .or_default()
+            .push(diagnostic.clone());
+    }
+    for diagnostics in files.values_mut() {
+        diagnostics.sort_by_key(|d| (d.location.range.start.line, d.location.range.start.column));
+    }
+    files
+}
+
+/// How many diagnostics there are of each severity.
+pub fn count(diagnostics: &[Diagnostic], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == severity)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_report() {
+        let output = r#"Analyzing app...
+{"version":1,"diagnostics":[{"code":"unused_import","severity":"WARNING","type":"STATIC_WARNING","location":{"file":"/app/lib/main.dart","range":{"start":{"offset":7,"line":1,"column":8},"end":{"offset":20,"line":1,"column":21}}},"problemMessage":"Unused import: 'dart:io'.","correctionMessage":"Try removing the import directive.","documentation":"https://dart.dev/diagnostics/unused_import"},{"code":"undefined_identifier","severity":"ERROR","type":"COMPILE_TIME_ERROR","location":{"file":"/app/lib/main.dart","range":{"start":{"offset":90,"line":5,"column":3},"end":{"offset":93,"line":5,"column":6}}},"problemMessage":"Undefined name 'foo'."}]}
+"#;
+        let diagnostics = parse(output).unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[1].location.range.start,
+            Position { line: 5, column: 3 }
+        );
+        assert_eq!(diagnostics[1].correction_message, None);
+        assert_eq!(count(&diagnostics, Severity::Error), 1);
+        assert_eq!(group_by_file(&diagnostics)["/app/lib/main.dart"].len(), 2);
+        assert!(parse(r#"{"version":1,"diagnostics":[]}"#)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{io::Write, path::Path, process::Stdio, sync::Arc};
 
 use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
@@ -11,6 +11,7 @@ use crate::{
     components::{home::Home, Component},
     config::Config,
     daemon::flutter::FlutterDaemon,
+    editor,
     mode::Mode,
     session::session_manager::{self, SessionManager},
     store::Store,
@@ -39,8 +40,9 @@ impl App {
             _daemon.enable_device().await.unwrap();
         });
 
+        let home_project_root = project_root.clone();
         let session_manager = Arc::new(Mutex::new(SessionManager::new(project_root)));
-        let home = Home::new(daemon, session_manager);
+        let home = Home::new(daemon, session_manager, home_project_root.as_deref());
         let config = Config::new()?;
         let mode = Mode::Home;
         Ok(Self {
@@ -65,6 +67,10 @@ impl App {
         // tui.mouse(true);
         tui.enter()?;
 
+        for component in self.components.iter_mut() {
+            component.register_action_handler(action_tx.clone())?;
+        }
+
         for component in self.components.iter_mut() {
             component.register_config_handler(self.config.clone())?;
         }
@@ -120,6 +126,42 @@ impl App {
                     Action::Quit => self.should_quit = true,
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
+                    Action::EnterInputMode => self.mode = Mode::Input,
+                    Action::ExitInputMode => self.mode = Mode::Home,
+                    Action::OpenInEditor {
+                        ref path,
+                        line,
+                        column,
+                    } => {
+                        let command_line =
+                            editor::command_line(&editor::editor(), Path::new(path), line, column);
+                        let mut command = tokio::process::Command::new(&command_line[0]);
+                        command.args(&command_line[1..]);
+                        let result = if editor::is_gui(&command_line) {
+                            // Keep the output of the editor off the screen of the TUI.
+                            command
+                                .stdin(Stdio::null())
+                                .stdout(Stdio::null())
+                                .stderr(Stdio::null())
+                                .spawn()
+                                .map(|_| ())
+                        } else {
+                            // Terminal editors take over the terminal until they exit.
+                            tui.exit()?;
+                            let status = command.status().await;
+                            tui.enter()?;
+                            tui.clear()?;
+                            status.map(|_| ())
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to open {}: {:?}", command_line[0], e);
+                        }
+                    }
+                    Action::Bell => {
+                        let mut io = tui::io();
+                        io.write_all(b"\x07")?;
+                        io.flush()?;
+                    }
                     Action::Resize(w, h) => {
                         tui.resize(Rect::new(0, 0, w, h))?;
                         let store = self.store.lock().await;
@@ -151,6 +193,11 @@ impl App {
                     }
                     _ => {}
                 }
+                for component in self.components.iter_mut() {
+                    if let Some(action) = component.update(action.clone(), self.store.clone())? {
+                        action_tx.send(action)?
+                    };
+                }
             }
             if self.should_suspend {
                 tui.suspend()?;
@@ -0,0 +1,181 @@
+use std::{
+    io::{Cursor, Write},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use tokio::process::Command;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::{
+    daemon::{
+        flutter::FlutterDaemon,
+        io::device::Device,
+        traffic::{TrafficDirection, TrafficEntry},
+    },
+    session::{launch_configuration::LaunchConfiguration, session::Session},
+    store::state::{SessionState, State},
+    utils::get_data_dir,
+};
+
+const DAEMON_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Everything QA needs to reproduce an issue with a single session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BugReport {
+    pub session_id: String,
+    pub logs: Vec<String>,
+    pub device: Option<Device>,
+    pub flutter_version: Option<String>,
+    pub daemon_version: Option<String>,
+    pub launch_configuration: LaunchConfiguration,
+    pub traffic: Vec<TrafficEntry>,
+}
+
+impl BugReport {
+    pub async fn collect(
+        daemon: &FlutterDaemon,
+        session: &Session,
+        session_state: &SessionState,
+        state: &State,
+    ) -> Self {
+        let device = session_state
+            .device_id
+            .as_ref()
+            .and_then(|id| state.devices.iter().find(|d| &d.id == id))
+            .cloned();
+        let daemon_version = tokio::time::timeout(DAEMON_VERSION_TIMEOUT, daemon.version())
+            .await
+            .ok()
+            .and_then(|version| version.ok());
+        Self {
+            session_id: session.id.clone(),
+            logs: session_state.logs.clone(),
+            device,
+            flutter_version: flutter_version().await.ok(),
+            daemon_version,
+            launch_configuration: session.launch_configuration.clone(),
+            traffic: session.run.traffic().await,
+        }
+    }
+
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+
+        zip.start_file("logs.txt", options)?;
+        for line in &self.logs {
+            writeln!(zip, "{}", line)?;
+        }
+
+        zip.start_file("device.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&self.device)?.as_bytes())?;
+
+        zip.start_file("flutter_version.json", options)?;
+        zip.write_all(self.flutter_version.as_deref().unwrap_or("null").as_bytes())?;
+
+        zip.start_file("daemon_version.txt", options)?;
+        zip.write_all(
+            self.daemon_version
+                .as_deref()
+                .unwrap_or("unknown")
+                .as_bytes(),
+        )?;
+
+        zip.start_file("launch_configuration.json", options)?;
+        zip.write_all(serde_json::to_string_pretty(&self.launch_configuration)?.as_bytes())?;
+
+        zip.start_file("protocol_traffic.txt", options)?;
+        for entry in &self.traffic {
+            let prefix = match entry.direction {
+                TrafficDirection::Sent => "->",
+                TrafficDirection::Received => "<-",
+            };
+            writeln!(zip, "{} {}", prefix, entry.message)?;
+        }
+
+        Ok(zip.finish()?.into_inner())
+    }
+
+    pub async fn save(&self) -> Result<PathBuf> {
+        let directory = get_data_dir().join("bug_reports");
+        tokio::fs::create_dir_all(&directory).await?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = directory.join(format!("{}-{}.zip", self.session_id, timestamp));
+        tokio::fs::write(&path, self.to_zip()?).await?;
+        Ok(path)
+    }
+}
+
+async fn flutter_version() -> Result<String> {
+    let output = Command::new("flutter")
+        .args(["--version", "--machine"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(eyre!("flutter --version exited with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    use super::*;
+
+    #[test]
+    fn to_zip_contains_all_entries() {
+        let report = BugReport {
+            session_id: "session-1".to_string(),
+            logs: vec!["flutter: Hello".to_string()],
+            device: None,
+            flutter_version: Some(r#"{"frameworkVersion":"3.16.0"}"#.to_string()),
+            daemon_version: Some("0.6.1".to_string()),
+            launch_configuration: LaunchConfiguration {
+                project_root: Some("/path/to/app".to_string()),
+                flavor: None,
+            },
+            traffic: vec![
+                TrafficEntry {
+                    direction: TrafficDirection::Sent,
+                    message: r#"[{"method":"daemon.version","id":1}]"#.to_string(),
+                },
+                TrafficEntry {
+                    direction: TrafficDirection::Received,
+                    message: r#"[{"id":1,"result":"0.6.1"}]"#.to_string(),
+                },
+            ],
+        };
+        let bytes = report.to_zip().unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let read = |archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str| {
+            let mut content = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        };
+        assert_eq!(read(&mut archive, "logs.txt"), "flutter: Hello\n");
+        assert_eq!(read(&mut archive, "device.json"), "null");
+        assert_eq!(
+            read(&mut archive, "flutter_version.json"),
+            r#"{"frameworkVersion":"3.16.0"}"#
+        );
+        assert_eq!(read(&mut archive, "daemon_version.txt"), "0.6.1");
+        assert_eq!(
+            read(&mut archive, "launch_configuration.json"),
+            "{\n  \"projectRoot\": \"/path/to/app\"\n}"
+        );
+        assert_eq!(
+            read(&mut archive, "protocol_traffic.txt"),
+            "-> [{\"method\":\"daemon.version\",\"id\":1}]\n<- [{\"id\":1,\"result\":\"0.6.1\"}]\n"
+        );
+    }
+}
@@ -12,16 +12,47 @@ use crate::{
     tui::{Event, Frame},
 };
 
+pub mod analysis;
 pub mod apps;
+pub mod debug_flags;
+pub mod debugger;
+pub mod dependencies;
+pub mod dependency_graph;
 pub mod devices;
 pub mod devtools;
+pub mod frames;
 pub mod home;
+pub mod inspector;
+pub mod isolates;
+pub mod memory;
+pub mod network;
+pub mod overrides;
+pub mod profiler;
 pub mod project;
+pub mod rebuilds;
+pub mod repl;
+pub mod service_extensions;
+pub mod test_runner;
+pub mod text_input;
+pub mod tree_dump;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 /// Implementors of this trait can be registered with the main application loop and will be able to receive events,
 /// update state, and be rendered on the screen.
 pub trait Component {
+    /// Register an action handler that can send actions for processing if necessary.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - An unbounded sender that can send actions.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    #[allow(unused_variables)]
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        Ok(())
+    }
     /// Register a configuration handler that provides configuration settings if necessary.
     ///
     /// # Arguments
@@ -102,6 +133,19 @@ pub trait Component {
     ) -> Result<Option<Action>> {
         Ok(None)
     }
+    /// Update the state of the component based on a received action. (OPTIONAL)
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - An action that may modify the state of the component.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Action>>` - An action to be processed or none.
+    #[allow(unused_variables)]
+    fn update(&mut self, action: Action, store: Arc<Mutex<Store>>) -> Result<Option<Action>> {
+        Ok(None)
+    }
     /// Render the component on the screen. (REQUIRED)
     ///
     /// # Arguments
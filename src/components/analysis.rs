@@ -0,0 +1,349 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    analysis::{self, Diagnostic, Severity},
+    pubspec,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+const KEY_HINTS: &str =
+    "a: analyze  Enter: open in editor  e/w/i: show or hide errors, warnings and infos";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AnalysisRow {
+    File { path: String, count: usize },
+    Diagnostic(Diagnostic),
+}
+
+pub struct AnalysisComponent {
+    project_root: PathBuf,
+    is_selected: bool,
+    task: Option<JoinHandle<()>>,
+    hidden: BTreeSet<Severity>,
+    // As of the last draw.
+    rows: Vec<AnalysisRow>,
+    table_state: TableState,
+}
+
+impl AnalysisComponent {
+    pub fn new(project_root: Option<&str>) -> Self {
+        Self {
+            project_root: pubspec::project_root(project_root),
+            is_selected: false,
+            task: None,
+            hidden: BTreeSet::new(),
+            rows: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn analyze(&mut self, store: Arc<Mutex<Store>>) {
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        self.task = Some(tokio::spawn(async move {
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::StartAnalysis)
+                .await;
+            let result = analysis::analyze(&project_root)
+                .await
+                .map_err(|e| e.to_string());
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::FinishAnalysis { result })
+                .await;
+        }));
+    }
+
+    fn toggle(&mut self, severity: Severity) {
+        if !self.hidden.remove(&severity) {
+            self.hidden.insert(severity);
+        }
+    }
+
+    fn open_in_editor(&self) -> Option<Action> {
+        let row = self.rows.get(self.table_state.selected()?)?;
+        let action = match row {
+            AnalysisRow::File { path, .. } => Action::OpenInEditor {
+                path: path.clone(),
+                line: 1,
+                column: 1,
+            },
+            AnalysisRow::Diagnostic(diagnostic) => Action::OpenInEditor {
+                path: diagnostic.location.file.clone(),
+                line: diagnostic.location.range.start.line,
+                column: diagnostic.location.range.start.column,
+            },
+        };
+        Some(action)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+}
+
+/// File rows, each followed by its diagnostics of the severities that are not hidden.
+fn rows(diagnostics: &[Diagnostic], hidden: &BTreeSet<Severity>) -> Vec<AnalysisRow> {
+    let shown = diagnostics
+        .iter()
+        .filter(|diagnostic| !hidden.contains(&diagnostic.severity))
+        .cloned()
+        .collect::<Vec<_>>();
+    analysis::group_by_file(&shown)
+        .into_iter()
+        .flat_map(|(path, diagnostics)| {
+            let file = AnalysisRow::File {
+                path,
+                count: diagnostics.len(),
+            };
+            std::iter::once(file).chain(diagnostics.into_iter().map(AnalysisRow::Diagnostic))
+        })
+        .collect()
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+        Severity::Info => Color::Blue,
+    }
+}
+
+fn display_path(project_root: &Path, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(project_root)
+        .map(|path| path.display().to_string())
+        .unwrap_or(path.to_string())
+}
+
+/// Diagnostic counts for the status bar.
+pub fn status(state: &State) -> Vec<Span<'static>> {
+    let analysis = &state.analysis;
+    if analysis.is_running {
+        return vec![Span::styled(
+            "Analyzing...",
+            Style::default().fg(Color::DarkGray),
+        )];
+    }
+    if let Some(error) = &analysis.error {
+        return vec![Span::styled(
+            format!(
+                "Analysis failed: {}",
+                error.lines().next().unwrap_or_default()
+            ),
+            Style::default().fg(Color::Red),
+        )];
+    }
+    let Some(diagnostics) = &analysis.diagnostics else {
+        return vec![];
+    };
+    [Severity::Error, Severity::Warning, Severity::Info]
+        .into_iter()
+        .map(|severity| {
+            let count = analysis::count(diagnostics, severity);
+            let color = if count == 0 {
+                Color::DarkGray
+            } else {
+                severity_color(severity)
+            };
+            Span::styled(
+                format!("{} {}s ", count, severity),
+                Style::default().fg(color),
+            )
+        })
+        .collect()
+}
+
+impl Component for AnalysisComponent {
+    fn init(&mut self, _: Rect, store: Arc<Mutex<Store>>) -> Result<()> {
+        self.analyze(store);
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('a') => self.analyze(store),
+            KeyCode::Char('e') => self.toggle(Severity::Error),
+            KeyCode::Char('w') => self.toggle(Severity::Warning),
+            KeyCode::Char('i') => self.toggle(Severity::Info),
+            KeyCode::Enter => return Ok(self.open_in_editor()),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let filters = [Severity::Error, Severity::Warning, Severity::Info]
+            .into_iter()
+            .filter(|severity| !self.hidden.contains(severity))
+            .map(|severity| format!("{}s", severity))
+            .collect::<Vec<_>>();
+        let block = Block::default()
+            .title(format!("Analysis ({})", filters.join(", ")))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(diagnostics) = &state.analysis.diagnostics else {
+            let text = if state.analysis.is_running {
+                " Analyzing... ".to_string()
+            } else if let Some(error) = &state.analysis.error {
+                format!(" {} ", error)
+            } else {
+                " Press a to run dart analyze ".to_string()
+            };
+            let text = Paragraph::new(text)
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+        self.rows = rows(diagnostics, &self.hidden);
+        if self.table_state.selected().unwrap_or(0) >= self.rows.len() {
+            self.table_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let table_rows = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                AnalysisRow::File { path, count } => Row::new(vec![
+                    Cell::from(display_path(&self.project_root, path))
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                    Cell::from(""),
+                    Cell::from(format!("{} problems", count))
+                        .style(Style::default().fg(Color::DarkGray)),
+                ]),
+                AnalysisRow::Diagnostic(diagnostic) => {
+                    let start = diagnostic.location.range.start;
+                    Row::new(vec![
+                        Cell::from(format!("  {}:{}", start.line, start.column)),
+                        Cell::from(diagnostic.severity.to_string())
+                            .style(Style::default().fg(severity_color(diagnostic.severity))),
+                        Cell::from(format!(
+                            "{} ({})",
+                            diagnostic.problem_message, diagnostic.code
+                        )),
+                    ])
+                }
+            })
+            .collect::<Vec<_>>();
+        if table_rows.is_empty() {
+            let text =
+                Paragraph::new(" No issues found ").style(Style::default().fg(Color::DarkGray));
+            f.render_widget(text, layout[0]);
+        } else {
+            let table = Table::new(
+                table_rows,
+                [
+                    Constraint::Length(10),
+                    Constraint::Length(8),
+                    Constraint::Min(0),
+                ],
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            f.render_stateful_widget(table, layout[0], &mut self.table_state);
+        }
+
+        let selected = self
+            .table_state
+            .selected()
+            .and_then(|index| self.rows.get(index));
+        if let Some(AnalysisRow::Diagnostic(diagnostic)) = selected {
+            let details = [
+                diagnostic.correction_message.clone(),
+                diagnostic.documentation.clone(),
+            ]
+            .into_iter()
+            .flatten()
+            .map(Line::from)
+            .collect::<Vec<_>>();
+            f.render_widget(
+                Paragraph::new(details).style(Style::default().fg(Color::Cyan)),
+                layout[1],
+            );
+        }
+        f.render_widget(
+            Paragraph::new(KEY_HINTS).style(Style::default().fg(Color::DarkGray)),
+            layout[2],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_group_shown_diagnostics_by_file() {
+        let diagnostics = analysis::parse(
+            r#"{"version":1,"diagnostics":[
+{"code":"dead_code","severity":"INFO","location":{"file":"/app/lib/b.dart","range":{"start":{"line":3,"column":1}}},"problemMessage":"Dead code."},
+{"code":"unused_import","severity":"WARNING","location":{"file":"/app/lib/a.dart","range":{"start":{"line":1,"column":8}}},"problemMessage":"Unused import."}
+]}"#,
+        )
+        .unwrap();
+        let all = rows(&diagnostics, &BTreeSet::new());
+        assert_eq!(all.len(), 4);
+        assert_eq!(
+            all[0],
+            AnalysisRow::File {
+                path: "/app/lib/a.dart".to_string(),
+                count: 1
+            }
+        );
+        let without_infos = rows(&diagnostics, &BTreeSet::from([Severity::Info]));
+        assert_eq!(without_infos.len(), 2);
+    }
+}
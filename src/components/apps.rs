@@ -1,28 +1,59 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crossterm::event::{KeyCode, KeyEvent};
+use futures::{future::BoxFuture, FutureExt};
 use ratatui::{prelude::*, widgets::*};
-use tokio::sync::Mutex;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc::UnboundedSender,
+    Mutex,
+};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     action::Action,
-    daemon::{self, flutter::FlutterDaemon, io::device::Device},
-    session::{
-        session::Session,
-        session_manager::{self, SessionManager},
+    bug_report::BugReport,
+    config::{Config, TriggerConfig},
+    daemon::{
+        flutter::FlutterDaemon,
+        io::{event::FlutterDaemonEvent, parse_event},
     },
-    store::{state::State, Store},
+    packages::{PubCommand, StaleReason},
+    pubspec,
+    session::session_manager::SessionManager,
+    store::{action::Action as StoreAction, state::State, Store},
+    trigger::{self, SessionTriggers, TriggerEffect},
     tui::Frame,
+    vm_service::{
+        client::VmService, frames::FrameTiming, io::event::EventKind, isolates::IsolateStatus,
+        memory::MemorySample,
+    },
 };
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 
-use super::Component;
+use super::{dependencies::run_pub_task, Component};
+
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+// Frame timings arrive every few milliseconds, so they are dispatched in batches.
+const FRAME_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 pub struct AppsComponent {
     session_manager: Arc<Mutex<SessionManager>>,
     daemon: Arc<FlutterDaemon>,
-    devices: Arc<Mutex<Vec<Device>>>,
+    action_tx: Option<UnboundedSender<Action>>,
+    triggers: Vec<TriggerConfig>,
+    session_triggers: Arc<std::sync::Mutex<SessionTriggers>>,
+    listeners: Arc<std::sync::Mutex<HashMap<String, CancellationToken>>>,
     is_selected: bool,
+    // Why packages are out of date, as of the last draw.
+    stale_lock: Option<StaleReason>,
+    // Set while asking whether to run pub get before launching.
+    confirm_pub_get: bool,
 
     list_state: ListState,
 }
@@ -32,9 +63,14 @@ impl AppsComponent {
         Self {
             session_manager,
             daemon,
-            devices: Arc::new(Mutex::new(vec![])),
+            action_tx: None,
+            triggers: vec![],
+            session_triggers: Arc::new(std::sync::Mutex::new(SessionTriggers::default())),
+            listeners: Arc::new(std::sync::Mutex::new(HashMap::new())),
             list_state: ListState::default().with_selected(Some(0)),
             is_selected: false,
+            stale_lock: None,
+            confirm_pub_get: false,
         }
     }
 
@@ -42,63 +78,498 @@ impl AppsComponent {
         self.is_selected = is_selected;
     }
 
-    fn next(&mut self) {
-        // let i = match self.list_state.selected() {
-        //     Some(i) => {
-        //         let sessions = &self.session_manager.lock().unwrap().sessions;
-        //         if i >= sessions.len() {
-        //             0
-        //         } else {
-        //             i + 1
-        //         }
-        //     }
-        //     None => 0,
-        // };
-        // self.list_state.select(Some(i));
-    }
-
-    fn previous(&mut self) {
-        // let i = match self.list_state.selected() {
-        //     Some(i) => {
-        //         if i == 0 {
-        //             let sessions = &self.session_manager.lock().unwrap().sessions;
-        //             sessions.len()
-        //         } else {
-        //             i - 1
-        //         }
-        //     }
-        //     None => 0,
-        // };
-        // self.list_state.select(Some(i));
-    }
-
-    fn unselect(&mut self) {
-        self.list_state.select(None);
-    }
-
-    fn run_new_app(&mut self) -> Result<()> {
-        // if let Ok(mut session_manager) = self.session_manager.lock() {
-        //     session_manager.run_new_app()?;
-        // }
+    fn next(&mut self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            store.lock().await.dispatch(StoreAction::NextSession).await;
+        });
+    }
+
+    fn previous(&mut self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::PreviousSession)
+                .await;
+        });
+    }
+
+    fn run_new_app(&mut self, store: Arc<Mutex<Store>>, pub_get_first: bool) {
+        let handler = self.session_handler(store);
+        tokio::spawn(async move {
+            if pub_get_first {
+                let project_root = handler
+                    .session_manager
+                    .lock()
+                    .await
+                    .project_root()
+                    .map(String::from);
+                let project_root = pubspec::project_root(project_root.as_deref());
+                if let Err(e) = run_pub_task(&project_root, &PubCommand::Get, &handler.store).await
+                {
+                    log::error!("Failed to get packages: {:?}", e);
+                    return;
+                }
+            }
+            let session = match handler.session_manager.lock().await.run_new_app() {
+                Ok(session) => session,
+                Err(e) => {
+                    log::error!("Failed to run new app: {:?}", e);
+                    return;
+                }
+            };
+            handler
+                .store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddSession {
+                    session_id: session.id.clone(),
+                })
+                .await;
+            handler
+                .listen(session.id.clone(), session.run.subscribe())
+                .await;
+        });
+    }
+
+    fn perform_on_selected_session(&mut self, action: Action, store: Arc<Mutex<Store>>) {
+        let handler = self.session_handler(store);
+        tokio::spawn(async move {
+            let state = handler.store.lock().await.get_state().await;
+            if let Some(session_id) = state.selected_session_id {
+                handler.perform(session_id, action).await;
+            }
+        });
+    }
+
+    fn session_handler(&self, store: Arc<Mutex<Store>>) -> SessionHandler {
+        SessionHandler {
+            session_manager: self.session_manager.clone(),
+            store,
+            action_tx: self.action_tx.clone(),
+            triggers: self.triggers.clone(),
+            session_triggers: self.session_triggers.clone(),
+            listeners: self.listeners.clone(),
+        }
+    }
+
+    fn export_bug_report(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let daemon = self.daemon.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session_state) = state.selected_session() else {
+                return;
+            };
+            let Some(session) = session_manager.lock().await.session(&session_state.id) else {
+                return;
+            };
+            let report = BugReport::collect(&daemon, &session, session_state, &state).await;
+            match report.save().await {
+                Ok(path) => log::info!("Saved bug report to {}", path.display()),
+                Err(e) => log::error!("Failed to save bug report: {:?}", e),
+            }
+        });
+    }
+}
+
+/// Runs session-scoped actions, whether they come from key bindings or log triggers.
+#[derive(Clone)]
+struct SessionHandler {
+    session_manager: Arc<Mutex<SessionManager>>,
+    store: Arc<Mutex<Store>>,
+    action_tx: Option<UnboundedSender<Action>>,
+    triggers: Vec<TriggerConfig>,
+    session_triggers: Arc<std::sync::Mutex<SessionTriggers>>,
+    // Cancels the listener of the daemon output of each session.
+    listeners: Arc<std::sync::Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl SessionHandler {
+    // Boxed because restarting a session from a trigger starts listening again.
+    fn listen(
+        self,
+        session_id: String,
+        mut rx: broadcast::Receiver<String>,
+    ) -> BoxFuture<'static, ()> {
+        async move {
+            let cancellation_token = CancellationToken::new();
+            self.listeners
+                .lock()
+                .unwrap()
+                .insert(session_id.clone(), cancellation_token.clone());
+            loop {
+                let line = tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    line = rx.recv() => match line {
+                        Ok(line) => line,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                let action = match parse_event(&line) {
+                    Some(FlutterDaemonEvent::AppStart { params }) => StoreAction::StartApp {
+                        session_id: session_id.clone(),
+                        app_id: params.app_id,
+                        device_id: params.device_id,
+                    },
+                    Some(FlutterDaemonEvent::AppDebugPort { params }) => {
+                        let handler = self.clone();
+                        let session_id = session_id.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) =
+                                handler.connect_vm_service(session_id, params.ws_uri).await
+                            {
+                                log::error!("Failed to connect to vm service: {:?}", e);
+                            }
+                        });
+                        continue;
+                    }
+                    Some(FlutterDaemonEvent::AppStarted { .. }) => StoreAction::StartedApp {
+                        session_id: session_id.clone(),
+                    },
+                    Some(FlutterDaemonEvent::AppLog { params }) => {
+                        let effects = self.session_triggers.lock().unwrap().evaluate(
+                            &self.triggers,
+                            &session_id,
+                            &params.log,
+                        );
+                        for effect in effects {
+                            match effect {
+                                TriggerEffect::Action(action) => {
+                                    let handler = self.clone();
+                                    let session_id = session_id.clone();
+                                    tokio::spawn(async move {
+                                        handler.perform(session_id, action).await;
+                                    });
+                                }
+                                TriggerEffect::Command(command) => {
+                                    trigger::run_command(&command, &params.log)
+                                }
+                            }
+                        }
+                        StoreAction::AppendLog {
+                            session_id: session_id.clone(),
+                            line: params.log,
+                        }
+                    }
+                    _ => continue,
+                };
+                self.store.lock().await.dispatch(action).await;
+            }
+        }
+        .boxed()
+    }
+
+    async fn connect_vm_service(&self, session_id: String, ws_uri: String) -> Result<()> {
+        let session = self
+            .session_manager
+            .lock()
+            .await
+            .session(&session_id)
+            .ok_or(eyre!("Session {} is not found", session_id))?;
+        let vm_service = session.connect_vm_service(&ws_uri).await?;
+        drop(session);
+        self.store
+            .lock()
+            .await
+            .dispatch(StoreAction::ConnectVmService {
+                session_id: session_id.clone(),
+                ws_uri,
+            })
+            .await;
+        tokio::spawn(
+            self.clone()
+                .collect_frames(session_id.clone(), vm_service.clone()),
+        );
+        tokio::spawn(
+            self.clone()
+                .collect_memory_usage(session_id.clone(), vm_service.clone()),
+        );
+        tokio::spawn(
+            self.clone()
+                .collect_isolate_statuses(session_id.clone(), vm_service.clone()),
+        );
+
+        // Keep the isolate and service extension lists in sync with the running app.
+        let mut events = vm_service.events();
+        loop {
+            let vm = vm_service.get_vm().await?;
+            let main_isolate = vm.isolates.iter().find(|i| !i.is_system_isolate).cloned();
+            self.store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetIsolates {
+                    session_id: session_id.clone(),
+                    isolates: vm.isolates,
+                })
+                .await;
+            if let Some(isolate) = main_isolate {
+                let isolate = vm_service.get_isolate(&isolate.id).await?;
+                self.store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::SetServiceExtensions {
+                        session_id: session_id.clone(),
+                        extensions: isolate.extension_rpcs,
+                    })
+                    .await;
+            }
+            loop {
+                let Some(params) = events.recv().await else {
+                    return Ok(());
+                };
+                match params.event.kind {
+                    EventKind::IsolateStart
+                    | EventKind::IsolateRunnable
+                    | EventKind::IsolateExit => break,
+                    EventKind::ServiceExtensionAdded => {
+                        if let Some(extension) = params.event.extension_rpc {
+                            self.store
+                                .lock()
+                                .await
+                                .dispatch(StoreAction::AddServiceExtension {
+                                    session_id: session_id.clone(),
+                                    extension,
+                                })
+                                .await;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    async fn collect_frames(self, session_id: String, vm_service: Arc<VmService>) {
+        let mut events = vm_service.events();
+        let mut interval = tokio::time::interval(FRAME_FLUSH_INTERVAL);
+        let mut frames = vec![];
+        loop {
+            tokio::select! {
+                params = events.recv() => {
+                    let Some(params) = params else {
+                        break;
+                    };
+                    if let Some(frame) = FrameTiming::from_event(&params.event) {
+                        frames.push(frame);
+                    }
+                }
+                _ = interval.tick() => {
+                    if frames.is_empty() {
+                        continue;
+                    }
+                    self.store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::AppendFrames {
+                            session_id: session_id.clone(),
+                            frames: std::mem::take(&mut frames),
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+
+    /// Keep the status of every isolate in sync with the Isolate and Debug streams.
+    async fn collect_isolate_statuses(self, session_id: String, vm_service: Arc<VmService>) {
+        let mut events = vm_service.events();
+        let isolates = match vm_service.get_vm().await {
+            Ok(vm) => vm.isolates,
+            Err(e) => {
+                log::error!("Failed to get isolates: {:?}", e);
+                return;
+            }
+        };
+        for isolate in isolates {
+            let status = match vm_service.get_isolate(&isolate.id).await {
+                Ok(isolate) => IsolateStatus::of(&isolate),
+                Err(e) => {
+                    log::error!("Failed to get isolate {}: {:?}", isolate.id, e);
+                    continue;
+                }
+            };
+            self.store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetIsolateStatus {
+                    session_id: session_id.clone(),
+                    isolate_id: isolate.id,
+                    status,
+                })
+                .await;
+        }
+        while let Some(params) = events.recv().await {
+            let (Some(isolate), Some(status)) = (
+                params.event.isolate,
+                IsolateStatus::after(&params.event.kind),
+            ) else {
+                continue;
+            };
+            self.store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetIsolateStatus {
+                    session_id: session_id.clone(),
+                    isolate_id: isolate.id,
+                    status,
+                })
+                .await;
+        }
+    }
+
+    /// Poll memory usage, marking the samples taken after a garbage collection.
+    async fn collect_memory_usage(self, session_id: String, vm_service: Arc<VmService>) {
+        let mut events = vm_service.events();
+        let mut interval = tokio::time::interval(MEMORY_POLL_INTERVAL);
+        // Collections come in bursts, so they are folded into the next sample.
+        let mut collected = false;
+        loop {
+            tokio::select! {
+                params = events.recv() => {
+                    let Some(params) = params else {
+                        break;
+                    };
+                    collected |= params.event.kind == EventKind::Gc;
+                    continue;
+                }
+                _ = interval.tick() => {}
+            }
+            let gc = std::mem::take(&mut collected);
+            let state = self.store.lock().await.get_state().await;
+            let Some(isolate_id) = state
+                .sessions
+                .iter()
+                .find(|s| s.id == session_id)
+                .and_then(|s| s.main_isolate())
+                .map(|i| i.id.clone())
+            else {
+                continue;
+            };
+            let usage = match vm_service.get_memory_usage(&isolate_id).await {
+                Ok(usage) => usage,
+                Err(e) => {
+                    log::error!("Failed to get memory usage: {:?}", e);
+                    continue;
+                }
+            };
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            self.store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddMemorySample {
+                    session_id: session_id.clone(),
+                    sample: MemorySample {
+                        timestamp,
+                        heap_usage: usage.heap_usage,
+                        heap_capacity: usage.heap_capacity,
+                        external_usage: usage.external_usage,
+                        gc,
+                    },
+                })
+                .await;
+        }
+    }
+
+    async fn perform(&self, session_id: String, action: Action) {
+        let session = self.session_manager.lock().await.session(&session_id);
+        let Some(session) = session else {
+            return;
+        };
+        let result = match action {
+            Action::HotReload => session.run.hot_reload().await.map(|_| ()),
+            Action::HotRestart => session.run.hot_restart().await.map(|_| ()),
+            Action::RestartSession => self.restart(&session_id).await,
+            action => {
+                if let Some(action_tx) = &self.action_tx {
+                    let _ = action_tx.send(action);
+                }
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            log::error!("Failed to perform action on {}: {:?}", session_id, e);
+        }
+    }
+
+    async fn restart(&self, session_id: &str) -> Result<()> {
+        let (previous, session) = self
+            .session_manager
+            .lock()
+            .await
+            .restart_session(session_id)?;
+        // The previous app keeps logging while it stops, under the same session id.
+        if let Some(listener) = self.listeners.lock().unwrap().remove(session_id) {
+            listener.cancel();
+        }
+        tokio::spawn(async move {
+            let _ = tokio::time::timeout(STOP_TIMEOUT, previous.run.stop()).await;
+        });
+        self.store
+            .lock()
+            .await
+            .dispatch(StoreAction::RestartSession {
+                session_id: session.id.clone(),
+            })
+            .await;
+        let handler = self.clone();
+        tokio::spawn(handler.listen(session.id.clone(), session.run.subscribe()));
         Ok(())
     }
 }
 
 impl Component for AppsComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.triggers = config.triggers;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action, store: Arc<Mutex<Store>>) -> Result<Option<Action>> {
+        if let Action::HotReload | Action::HotRestart | Action::RestartSession = action {
+            self.perform_on_selected_session(action, store);
+        }
+        Ok(None)
+    }
+
     fn handle_key_events(
         &mut self,
         key: KeyEvent,
         store: Arc<Mutex<Store>>,
     ) -> Result<Option<Action>> {
+        if self.confirm_pub_get {
+            self.confirm_pub_get = false;
+            match key.code {
+                KeyCode::Char('y') => self.run_new_app(store, true),
+                KeyCode::Char('n') => self.run_new_app(store, false),
+                _ => {}
+            }
+            return Ok(None);
+        }
         match key.code {
             KeyCode::Char('n') => {
-                self.run_new_app()?;
+                if self.stale_lock.is_some() {
+                    self.confirm_pub_get = true;
+                } else {
+                    self.run_new_app(store, false);
+                }
+            }
+            KeyCode::Char('b') => {
+                self.export_bug_report(store);
             }
             KeyCode::Up => {
-                self.previous();
+                self.previous(store);
             }
             KeyCode::Down => {
-                self.next();
+                self.next(store);
             }
             _ => {}
         }
@@ -122,17 +593,39 @@ impl Component for AppsComponent {
             .borders(Borders::ALL)
             .border_style(Style::default().fg(default_color));
 
-        // let sessions = &self.session_manager.lock().unwrap().sessions;
-        let sessions: Vec<Session> = vec![];
-        let mut items = sessions
+        let mut items = state
+            .sessions
             .iter()
             .enumerate()
-            .map(|(index, _)| {
-                ListItem::new(format!(" App {} ", index + 1))
+            .map(|(index, session)| {
+                let device = session.device_id.as_deref().unwrap_or("launching...");
+                ListItem::new(format!(" App {} ({}) ", index + 1, device))
                     .style(Style::default().fg(enabled_color))
             })
             .collect::<Vec<_>>();
-        items.push(ListItem::new(" ▶ Run new app ").style(Style::default().fg(default_color)));
+        self.stale_lock = state.project.stale_lock.clone();
+        // Packages got up to date while asking, so there is nothing left to confirm.
+        if self.stale_lock.is_none() {
+            self.confirm_pub_get = false;
+        }
+        match &self.stale_lock {
+            Some(reason) if self.confirm_pub_get => items.push(
+                ListItem::new(vec![
+                    Line::from(format!(" ⚠ {} ", reason.message())),
+                    Line::from(" Run pub get first? (y/n) "),
+                ])
+                .style(Style::default().fg(Color::Yellow)),
+            ),
+            _ => items
+                .push(ListItem::new(" ▶ Run new app ").style(Style::default().fg(default_color))),
+        }
+
+        let selected_index = state
+            .selected_session_id
+            .as_ref()
+            .and_then(|id| state.sessions.iter().position(|s| &s.id == id));
+        self.list_state
+            .select(Some(selected_index.unwrap_or(state.sessions.len())));
 
         let list = List::new(items)
             .block(block)
@@ -0,0 +1,193 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use strum::IntoEnumIterator;
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    debug_flags::DebugFlag,
+    session::session_manager::SessionManager,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+pub struct DebugFlagsComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    list_state: ListState,
+    // Flags are read back once per app, keyed by app id so that a restarted session is
+    // read again.
+    loaded_app_ids: HashSet<String>,
+    pending_load: Option<String>,
+}
+
+impl DebugFlagsComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            list_state: ListState::default().with_selected(Some(0)),
+            loaded_app_ids: HashSet::new(),
+            pending_load: None,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn selected_flag(&self) -> Option<DebugFlag> {
+        DebugFlag::iter().nth(self.list_state.selected()?)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let count = DebugFlag::iter().count();
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(count - 1);
+        self.list_state.select(Some(index));
+    }
+
+    fn refresh(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session_id) = state.selected_session_id else {
+                return;
+            };
+            let Some(session) = session_manager.lock().await.session(&session_id) else {
+                return;
+            };
+            for flag in DebugFlag::iter() {
+                match flag.read(&session.run).await {
+                    Ok(value) => {
+                        store
+                            .lock()
+                            .await
+                            .dispatch(StoreAction::SetDebugFlag {
+                                session_id: session_id.clone(),
+                                flag,
+                                value,
+                            })
+                            .await;
+                    }
+                    Err(e) => log::error!("Failed to read {}: {:?}", flag, e),
+                }
+            }
+        });
+    }
+
+    fn toggle(&mut self, store: Arc<Mutex<Store>>) {
+        let Some(flag) = self.selected_flag() else {
+            return;
+        };
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session_state) = state.selected_session() else {
+                return;
+            };
+            let Some(session) = session_manager.lock().await.session(&session_state.id) else {
+                return;
+            };
+            let value = flag.next_value(session_state.debug_flags.get(&flag).map(|v| v.as_str()));
+            match flag.write(&session.run, &value).await {
+                Ok(value) => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetDebugFlag {
+                            session_id: session_state.id.clone(),
+                            flag,
+                            value,
+                        })
+                        .await;
+                }
+                Err(e) => log::error!("Failed to set {}: {:?}", flag, e),
+            }
+        });
+    }
+}
+
+impl Component for DebugFlagsComponent {
+    fn update(&mut self, action: Action, store: Arc<Mutex<Store>>) -> Result<Option<Action>> {
+        if action == Action::Tick {
+            if let Some(app_id) = self.pending_load.take() {
+                self.loaded_app_ids.insert(app_id);
+                self.refresh(store);
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('u') => self.refresh(store),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle(store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("Debug flags")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(session) = state.selected_session().filter(|s| s.started) else {
+            let text = Paragraph::new(" Select a running app to change its debug flags ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+        if let Some(app_id) = &session.app_id {
+            if !self.loaded_app_ids.contains(app_id) {
+                self.pending_load = Some(app_id.clone());
+            }
+        }
+
+        let items = DebugFlag::iter()
+            .map(|flag| {
+                let value = session.debug_flags.get(&flag);
+                let value_color = match value.map(|v| v.as_str()) {
+                    Some("true") => Color::Green,
+                    Some(_) => Color::White,
+                    None => Color::DarkGray,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(" {:<22}", flag.to_string())),
+                    Span::styled(
+                        value.map(|v| v.as_str()).unwrap_or("-").to_string(),
+                        Style::default().fg(value_color),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -0,0 +1,894 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    dap::{
+        client::{DapEvents, DebugAdapter},
+        io::{Breakpoint, BreakpointEventBody, OutputEventBody, StoppedEventBody},
+    },
+    session::session_manager::SessionManager,
+    store::{
+        action::Action as StoreAction,
+        state::{DebuggerState, DebuggerStatus, LineBreakpoint, State},
+        Store,
+    },
+    tui::Frame,
+};
+use color_eyre::eyre::{eyre, Result};
+
+use super::{text_input::TextInput, Component};
+
+const KEY_HINTS: &str = "c: continue  p: pause  n: step over  i: step in  o: step out  \
+                         b/x: breakpoints  e: exceptions  f: focus";
+
+#[derive(PartialEq, Clone, Copy)]
+enum Focus {
+    Breakpoints,
+    Stack,
+    Variables,
+}
+
+#[derive(Clone, Copy)]
+enum Command {
+    Resume,
+    Pause,
+    StepOver,
+    StepIn,
+    StepOut,
+}
+
+struct VariableRow {
+    variables_reference: i64,
+    line: Line<'static>,
+}
+
+pub struct DebuggerComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    focus: Focus,
+    // Set while a `file:line` breakpoint is being typed.
+    breakpoint_input: Option<TextInput>,
+    breakpoint_rows: Vec<(String, i64)>,
+    frame_ids: Vec<i64>,
+    variable_rows: Vec<VariableRow>,
+    breakpoints_state: ListState,
+    stack_state: ListState,
+    variables_state: ListState,
+}
+
+impl DebuggerComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            focus: Focus::Breakpoints,
+            breakpoint_input: None,
+            breakpoint_rows: vec![],
+            frame_ids: vec![],
+            variable_rows: vec![],
+            breakpoints_state: ListState::default().with_selected(Some(0)),
+            stack_state: ListState::default().with_selected(Some(0)),
+            variables_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn toggle_debugging(&self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let running = session_manager.lock().await.debugger.take();
+            if let Some(adapter) = running {
+                if let Err(e) = adapter.disconnect().await {
+                    log::error!("Failed to stop debugging: {:?}", e);
+                }
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::SetDebuggerStatus {
+                        status: DebuggerStatus::Terminated,
+                    })
+                    .await;
+                return;
+            }
+            if let Err(e) = Self::start_debugging(session_manager, store.clone()).await {
+                log::error!("Failed to start debugging: {:?}", e);
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::SetDebuggerStatus {
+                        status: DebuggerStatus::Terminated,
+                    })
+                    .await;
+            }
+        });
+    }
+
+    async fn start_debugging(
+        session_manager: Arc<Mutex<SessionManager>>,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<()> {
+        let project_root = Self::project_root(&session_manager).await?;
+        let adapter = Arc::new(DebugAdapter::spawn(project_root.to_str())?);
+        session_manager.lock().await.debugger = Some(adapter.clone());
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetDebuggerStatus {
+                status: DebuggerStatus::Running,
+            })
+            .await;
+        tokio::spawn(Self::handle_events(
+            adapter.clone(),
+            adapter.events(),
+            session_manager,
+            store.clone(),
+        ));
+
+        let state = store.lock().await.get_state().await;
+        let breakpoints = state
+            .debugger
+            .breakpoints
+            .iter()
+            .map(|(path, breakpoints)| (path.clone(), breakpoints.iter().map(|b| b.line).collect()))
+            .collect::<BTreeMap<String, Vec<i64>>>();
+        let arguments = json!({
+            "program": project_root.join("lib").join("main.dart"),
+            "cwd": project_root,
+        });
+        let set = adapter
+            .start(
+                arguments,
+                &breakpoints,
+                state.debugger.exception_pause_mode.filters(),
+            )
+            .await?;
+        for (path, set) in set {
+            let breakpoints = to_line_breakpoints(&breakpoints[&path], set);
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetBreakpoints { path, breakpoints })
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Follow the adapter until the app terminates.
+    async fn handle_events(
+        adapter: Arc<DebugAdapter>,
+        mut events: DapEvents,
+        session_manager: Arc<Mutex<SessionManager>>,
+        store: Arc<Mutex<Store>>,
+    ) {
+        while let Some(event) = events.recv().await {
+            let body = event.body.unwrap_or_default();
+            match event.event.as_str() {
+                "stopped" => {
+                    let Ok(stopped) = serde_json::from_value::<StoppedEventBody>(body) else {
+                        continue;
+                    };
+                    if let Err(e) = Self::on_stopped(&adapter, stopped, &store).await {
+                        log::error!("Failed to get the paused stack: {:?}", e);
+                    }
+                }
+                "continued" => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetDebuggerStatus {
+                            status: DebuggerStatus::Running,
+                        })
+                        .await;
+                }
+                "output" => {
+                    let Ok(output) = serde_json::from_value::<OutputEventBody>(body) else {
+                        continue;
+                    };
+                    if output.category.as_deref() == Some("telemetry") {
+                        continue;
+                    }
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::AddDebuggerOutput {
+                            output: output.output,
+                        })
+                        .await;
+                }
+                "breakpoint" => {
+                    let Ok(changed) = serde_json::from_value::<BreakpointEventBody>(body) else {
+                        continue;
+                    };
+                    if changed.reason == "changed" {
+                        store
+                            .lock()
+                            .await
+                            .dispatch(StoreAction::UpdateBreakpoint {
+                                breakpoint: changed.breakpoint,
+                            })
+                            .await;
+                    }
+                }
+                "terminated" => break,
+                _ => {}
+            }
+        }
+        {
+            let mut session_manager = session_manager.lock().await;
+            if session_manager
+                .debugger
+                .as_ref()
+                .is_some_and(|debugger| Arc::ptr_eq(debugger, &adapter))
+            {
+                session_manager.debugger = None;
+            }
+        }
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetDebuggerStatus {
+                status: DebuggerStatus::Terminated,
+            })
+            .await;
+    }
+
+    async fn on_stopped(
+        adapter: &DebugAdapter,
+        stopped: StoppedEventBody,
+        store: &Arc<Mutex<Store>>,
+    ) -> Result<()> {
+        let thread_id = match stopped.thread_id {
+            Some(thread_id) => thread_id,
+            None => adapter.main_thread_id().await?,
+        };
+        let reason = stopped.description.unwrap_or(stopped.reason);
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetDebuggerStatus {
+                status: DebuggerStatus::Paused { thread_id, reason },
+            })
+            .await;
+        let frames = adapter.stack_trace(thread_id).await?;
+        let top_frame_id = frames.first().map(|frame| frame.id);
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetStackFrames { frames })
+            .await;
+        if let Some(frame_id) = top_frame_id {
+            Self::load_frame(adapter, frame_id, store).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the scopes of a frame along with the variables of the inexpensive ones.
+    async fn load_frame(
+        adapter: &DebugAdapter,
+        frame_id: i64,
+        store: &Arc<Mutex<Store>>,
+    ) -> Result<()> {
+        let scopes = adapter.scopes(frame_id).await?;
+        let references = scopes
+            .iter()
+            .filter(|scope| !scope.expensive)
+            .map(|scope| scope.variables_reference)
+            .collect::<Vec<_>>();
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SelectStackFrame { frame_id, scopes })
+            .await;
+        for variables_reference in references {
+            let variables = adapter.variables(variables_reference).await?;
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetVariables {
+                    variables_reference,
+                    variables,
+                })
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn project_root(session_manager: &Arc<Mutex<SessionManager>>) -> Result<PathBuf> {
+        match session_manager.lock().await.project_root() {
+            Some(root) => Ok(PathBuf::from(root)),
+            None => Ok(std::env::current_dir()?),
+        }
+    }
+
+    fn run_command(&self, command: Command, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let Some(adapter) = session_manager.lock().await.debugger.clone() else {
+                return;
+            };
+            let status = store.lock().await.get_state().await.debugger.status;
+            let thread_id = match (&status, command) {
+                (DebuggerStatus::Paused { .. }, Command::Pause) => return,
+                (DebuggerStatus::Paused { thread_id, .. }, _) => *thread_id,
+                (DebuggerStatus::Running, Command::Pause) => match adapter.main_thread_id().await {
+                    Ok(thread_id) => thread_id,
+                    Err(e) => {
+                        log::error!("Failed to pause: {:?}", e);
+                        return;
+                    }
+                },
+                _ => return,
+            };
+            // Mark the app as running first, as a step may stop again before it responds.
+            if !matches!(command, Command::Pause) {
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::SetDebuggerStatus {
+                        status: DebuggerStatus::Running,
+                    })
+                    .await;
+            }
+            let result = match command {
+                Command::Resume => adapter.resume(thread_id).await,
+                Command::Pause => adapter.pause(thread_id).await,
+                Command::StepOver => adapter.step_over(thread_id).await,
+                Command::StepIn => adapter.step_in(thread_id).await,
+                Command::StepOut => adapter.step_out(thread_id).await,
+            };
+            if let Err(e) = result {
+                log::error!("Failed to control the debugged app: {:?}", e);
+                // Fetch the stack again since the app did not move.
+                if let DebuggerStatus::Paused { thread_id, reason } = status {
+                    let stopped = StoppedEventBody {
+                        reason,
+                        thread_id: Some(thread_id),
+                        description: None,
+                        text: None,
+                    };
+                    if let Err(e) = Self::on_stopped(&adapter, stopped, &store).await {
+                        log::error!("Failed to get the paused stack: {:?}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    fn add_breakpoint(&self, location: &str, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let location = location.trim().to_string();
+        tokio::spawn(async move {
+            let (path, line) = match parse_location(&location) {
+                Ok(location) => location,
+                Err(e) => {
+                    log::error!("{}", e);
+                    return;
+                }
+            };
+            let path = match Self::project_root(&session_manager).await {
+                Ok(root) => root.join(path).to_string_lossy().to_string(),
+                Err(e) => {
+                    log::error!("Failed to resolve {}: {:?}", location, e);
+                    return;
+                }
+            };
+            let state = store.lock().await.get_state().await;
+            let mut lines = file_lines(&state.debugger, &path);
+            lines.push(line);
+            lines.sort();
+            lines.dedup();
+            Self::set_breakpoints(session_manager, path, lines, store).await;
+        });
+    }
+
+    fn remove_breakpoint(&self, store: Arc<Mutex<Store>>) {
+        let Some((path, line)) = self
+            .breakpoints_state
+            .selected()
+            .and_then(|index| self.breakpoint_rows.get(index))
+            .cloned()
+        else {
+            return;
+        };
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let lines = file_lines(&state.debugger, &path)
+                .into_iter()
+                .filter(|l| *l != line)
+                .collect();
+            Self::set_breakpoints(session_manager, path, lines, store).await;
+        });
+    }
+
+    /// Replace the breakpoints of a file, on the running app if there is one.
+    async fn set_breakpoints(
+        session_manager: Arc<Mutex<SessionManager>>,
+        path: String,
+        lines: Vec<i64>,
+        store: Arc<Mutex<Store>>,
+    ) {
+        let adapter = session_manager.lock().await.debugger.clone();
+        let breakpoints = match adapter {
+            Some(adapter) => match adapter.set_breakpoints(&path, &lines).await {
+                Ok(set) => to_line_breakpoints(&lines, set),
+                Err(e) => {
+                    log::error!("Failed to set breakpoints in {}: {:?}", path, e);
+                    return;
+                }
+            },
+            None => to_line_breakpoints(&lines, vec![]),
+        };
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetBreakpoints { path, breakpoints })
+            .await;
+    }
+
+    fn cycle_exception_pause_mode(&self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let mode = state.debugger.exception_pause_mode.next();
+            let adapter = session_manager.lock().await.debugger.clone();
+            if let Some(adapter) = adapter {
+                if let Err(e) = adapter.set_exception_breakpoints(mode.filters()).await {
+                    log::error!("Failed to set the exception pause mode: {:?}", e);
+                    return;
+                }
+            }
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetExceptionPauseMode { mode })
+                .await;
+        });
+    }
+
+    fn select_frame(&self, store: Arc<Mutex<Store>>) {
+        let Some(frame_id) = self
+            .stack_state
+            .selected()
+            .and_then(|index| self.frame_ids.get(index))
+            .copied()
+        else {
+            return;
+        };
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let Some(adapter) = session_manager.lock().await.debugger.clone() else {
+                return;
+            };
+            if let Err(e) = Self::load_frame(&adapter, frame_id, &store).await {
+                log::error!("Failed to get the variables of frame {}: {:?}", frame_id, e);
+            }
+        });
+    }
+
+    fn toggle_variable(&self, store: Arc<Mutex<Store>>) {
+        let Some(variables_reference) = self
+            .variables_state
+            .selected()
+            .and_then(|index| self.variable_rows.get(index))
+            .map(|row| row.variables_reference)
+            .filter(|reference| *reference > 0)
+        else {
+            return;
+        };
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            if state.debugger.variables.contains_key(&variables_reference) {
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::CollapseVariables {
+                        variables_reference,
+                    })
+                    .await;
+                return;
+            }
+            let Some(adapter) = session_manager.lock().await.debugger.clone() else {
+                return;
+            };
+            match adapter.variables(variables_reference).await {
+                Ok(variables) => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetVariables {
+                            variables_reference,
+                            variables,
+                        })
+                        .await
+                }
+                Err(e) => log::error!("Failed to get variables: {:?}", e),
+            }
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let (state, len) = match self.focus {
+            Focus::Breakpoints => (&mut self.breakpoints_state, self.breakpoint_rows.len()),
+            Focus::Stack => (&mut self.stack_state, self.frame_ids.len()),
+            Focus::Variables => (&mut self.variables_state, self.variable_rows.len()),
+        };
+        let index = state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(len.saturating_sub(1));
+        state.select(Some(index));
+    }
+
+    fn handle_breakpoint_input(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        let Some(input) = self.breakpoint_input.as_mut() else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.breakpoint_input = None;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter => {
+                let location = input.value().to_string();
+                self.breakpoint_input = None;
+                if !location.trim().is_empty() {
+                    self.add_breakpoint(&location, store);
+                }
+                return Ok(Some(Action::ExitInputMode));
+            }
+            _ => {
+                input.handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn section_block(&self, title: &str, focus: Focus) -> Block<'static> {
+        let title = if self.focus == focus {
+            title.to_string().bold()
+        } else {
+            title.to_string().dark_gray()
+        };
+        Block::default().title(title).borders(Borders::TOP)
+    }
+
+    fn draw_breakpoints(&mut self, f: &mut Frame<'_>, area: Rect, debugger: &DebuggerState) {
+        self.breakpoint_rows = vec![];
+        let mut items = vec![];
+        for (path, breakpoints) in &debugger.breakpoints {
+            let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+            for breakpoint in breakpoints {
+                self.breakpoint_rows.push((path.clone(), breakpoint.line));
+                let marker = if breakpoint.verified {
+                    "● ".red()
+                } else {
+                    "○ ".dark_gray()
+                };
+                items.push(ListItem::new(Line::from(vec![
+                    marker,
+                    Span::raw(format!("{}:{}", file, breakpoint.line)),
+                ])));
+            }
+        }
+        if self.breakpoints_state.selected().unwrap_or(0) >= items.len() {
+            self.breakpoints_state
+                .select(Some(items.len().saturating_sub(1)));
+        }
+        let block = self.section_block("Breakpoints", Focus::Breakpoints);
+        if items.is_empty() {
+            let text = Paragraph::new(" Press b to add a file:line breakpoint ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return;
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.breakpoints_state);
+    }
+
+    fn draw_stack(&mut self, f: &mut Frame<'_>, area: Rect, debugger: &DebuggerState) {
+        self.frame_ids = debugger.stack_frames.iter().map(|frame| frame.id).collect();
+        let items = debugger
+            .stack_frames
+            .iter()
+            .map(|frame| {
+                let location = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.name.clone().or(source.path.clone()))
+                    .map(|name| format!(" {}:{}", name, frame.line))
+                    .unwrap_or_default();
+                let name = if Some(frame.id) == debugger.selected_frame_id {
+                    frame.name.clone().bold()
+                } else {
+                    Span::raw(frame.name.clone())
+                };
+                ListItem::new(Line::from(vec![name, location.dark_gray()]))
+            })
+            .collect::<Vec<_>>();
+        if self.stack_state.selected().unwrap_or(0) >= items.len() {
+            self.stack_state.select(Some(items.len().saturating_sub(1)));
+        }
+        let list = List::new(items)
+            .block(self.section_block("Call stack", Focus::Stack))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.stack_state);
+    }
+
+    fn draw_variables(&mut self, f: &mut Frame<'_>, area: Rect, debugger: &DebuggerState) {
+        self.variable_rows = variable_rows(debugger);
+        if self.variables_state.selected().unwrap_or(0) >= self.variable_rows.len() {
+            self.variables_state
+                .select(Some(self.variable_rows.len().saturating_sub(1)));
+        }
+        let items = self
+            .variable_rows
+            .iter()
+            .map(|row| ListItem::new(row.line.clone()))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(self.section_block("Variables", Focus::Variables))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.variables_state);
+    }
+}
+
+/// Split `lib/main.dart:12` into a path and a line.
+fn parse_location(location: &str) -> Result<(&str, i64)> {
+    let (path, line) = location
+        .rsplit_once(':')
+        .ok_or(eyre!("Breakpoints are set as file:line, got {}", location))?;
+    let line = line
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| eyre!("Invalid breakpoint line in {}", location))?;
+    Ok((path.trim(), line))
+}
+
+fn file_lines(debugger: &DebuggerState, path: &str) -> Vec<i64> {
+    debugger
+        .breakpoints
+        .get(path)
+        .map(|breakpoints| breakpoints.iter().map(|b| b.line).collect())
+        .unwrap_or_default()
+}
+
+/// Pair the requested lines with the breakpoints the adapter set, in the same order.
+fn to_line_breakpoints(lines: &[i64], set: Vec<Breakpoint>) -> Vec<LineBreakpoint> {
+    let mut set = set.into_iter();
+    lines
+        .iter()
+        .map(|line| match set.next() {
+            Some(breakpoint) => LineBreakpoint {
+                line: breakpoint.line.unwrap_or(*line),
+                id: breakpoint.id,
+                verified: breakpoint.verified,
+            },
+            None => LineBreakpoint {
+                line: *line,
+                id: None,
+                verified: false,
+            },
+        })
+        .collect()
+}
+
+/// Flatten the scopes and the expanded variables into indented rows.
+fn variable_rows(debugger: &DebuggerState) -> Vec<VariableRow> {
+    fn push_children(
+        debugger: &DebuggerState,
+        variables_reference: i64,
+        depth: usize,
+        ancestors: &mut HashSet<i64>,
+        rows: &mut Vec<VariableRow>,
+    ) {
+        let Some(variables) = debugger.variables.get(&variables_reference) else {
+            return;
+        };
+        ancestors.insert(variables_reference);
+        for variable in variables {
+            let reference = variable.variables_reference;
+            let expanded = debugger.variables.contains_key(&reference);
+            let marker = match (reference > 0, expanded) {
+                (false, _) => "  ",
+                (true, false) => "▸ ",
+                (true, true) => "▾ ",
+            };
+            let mut spans = vec![
+                Span::raw(format!("{}{}", "  ".repeat(depth), marker)),
+                Span::styled(variable.name.clone(), Style::default().fg(Color::Cyan)),
+                Span::raw(format!(": {}", variable.value)),
+            ];
+            if let Some(kind) = &variable.kind {
+                spans.push(format!(" ({})", kind).dark_gray());
+            }
+            rows.push(VariableRow {
+                variables_reference: reference,
+                line: Line::from(spans),
+            });
+            if reference > 0 && !ancestors.contains(&reference) {
+                push_children(debugger, reference, depth + 1, ancestors, rows);
+            }
+        }
+        ancestors.remove(&variables_reference);
+    }
+
+    let mut rows = vec![];
+    for scope in &debugger.scopes {
+        let expanded = debugger.variables.contains_key(&scope.variables_reference);
+        let marker = if expanded { "▾ " } else { "▸ " };
+        rows.push(VariableRow {
+            variables_reference: scope.variables_reference,
+            line: Line::from(format!("{}{}", marker, scope.name).bold()),
+        });
+        push_children(
+            debugger,
+            scope.variables_reference,
+            1,
+            &mut HashSet::new(),
+            &mut rows,
+        );
+    }
+    rows
+}
+
+impl Component for DebuggerComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        if self.breakpoint_input.is_some() {
+            return self.handle_breakpoint_input(key, store);
+        }
+        match key.code {
+            KeyCode::Char('d') => self.toggle_debugging(store),
+            KeyCode::Char('b') => {
+                self.breakpoint_input = Some(TextInput::new("lib/main.dart:"));
+                return Ok(Some(Action::EnterInputMode));
+            }
+            KeyCode::Char('x') if self.focus == Focus::Breakpoints => self.remove_breakpoint(store),
+            KeyCode::Char('e') => self.cycle_exception_pause_mode(store),
+            KeyCode::Char('c') => self.run_command(Command::Resume, store),
+            KeyCode::Char('p') => self.run_command(Command::Pause, store),
+            KeyCode::Char('n') => self.run_command(Command::StepOver, store),
+            KeyCode::Char('i') => self.run_command(Command::StepIn, store),
+            KeyCode::Char('o') => self.run_command(Command::StepOut, store),
+            KeyCode::Char('f') => {
+                self.focus = match self.focus {
+                    Focus::Breakpoints => Focus::Stack,
+                    Focus::Stack => Focus::Variables,
+                    Focus::Variables => Focus::Breakpoints,
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => match self.focus {
+                Focus::Breakpoints => {}
+                Focus::Stack => self.select_frame(store),
+                Focus::Variables => self.toggle_variable(store),
+            },
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let debugger = &state.debugger;
+        let status = match &debugger.status {
+            DebuggerStatus::NotStarted => "d: debug the app".dark_gray(),
+            DebuggerStatus::Running => "● running".green().bold(),
+            DebuggerStatus::Paused { reason, .. } => {
+                format!("‖ paused ({})", reason).yellow().bold()
+            }
+            DebuggerStatus::Terminated => "terminated (d: debug again)".dark_gray(),
+        };
+        let title = vec![
+            Span::raw("Debugger "),
+            status,
+            format!(" exceptions: {}", debugger.exception_pause_mode.label()).dark_gray(),
+        ];
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[0]);
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(columns[0]);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(columns[1]);
+        self.draw_breakpoints(f, left[0], debugger);
+        self.draw_stack(f, left[1], debugger);
+        self.draw_variables(f, right[0], debugger);
+
+        let output_height = right[1].height.saturating_sub(1) as usize;
+        let output = debugger
+            .output
+            .iter()
+            .skip(debugger.output.len().saturating_sub(output_height))
+            .map(|line| Line::from(line.clone()))
+            .collect::<Vec<_>>();
+        f.render_widget(
+            Paragraph::new(output).block(Block::default().title("Output").borders(Borders::TOP)),
+            right[1],
+        );
+
+        match &self.breakpoint_input {
+            Some(input) => {
+                let prompt = "Breakpoint (file:line): ";
+                f.render_widget(
+                    Paragraph::new(format!("{}{}", prompt, input.value())),
+                    rows[1],
+                );
+                f.set_cursor(
+                    rows[1].x + (prompt.len() + input.cursor()) as u16,
+                    rows[1].y,
+                );
+            }
+            None => f.render_widget(
+                Paragraph::new(KEY_HINTS).style(Style::default().fg(Color::DarkGray)),
+                rows[1],
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_breakpoint_location() {
+        assert_eq!(
+            parse_location("lib/main.dart:12").unwrap(),
+            ("lib/main.dart", 12)
+        );
+        assert_eq!(
+            parse_location("C:/app/lib/main.dart: 3").unwrap(),
+            ("C:/app/lib/main.dart", 3)
+        );
+        assert!(parse_location("lib/main.dart").is_err());
+        assert!(parse_location("lib/main.dart:x").is_err());
+    }
+}
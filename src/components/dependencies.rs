@@ -0,0 +1,458 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::{
+    sync::{mpsc, Mutex},
+    task::JoinHandle,
+};
+
+use crate::{
+    action::Action,
+    packages::{self, PubCommand, PubspecLock},
+    pubspec,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+const TASK_LOG_HEIGHT: u16 = 8;
+const KEY_HINTS: &str = "o: check outdated  g: pub get  u: upgrade selected  U: upgrade all  \
+                         m: upgrade major versions";
+
+#[derive(Debug, PartialEq, Eq)]
+struct DependencyRow {
+    name: String,
+    kind: &'static str,
+    constraint: String,
+    current: Option<String>,
+    upgradable: Option<String>,
+    resolvable: Option<String>,
+    latest: Option<String>,
+    is_discontinued: bool,
+}
+
+pub struct DependenciesComponent {
+    project_root: PathBuf,
+    is_selected: bool,
+    task: Option<JoinHandle<()>>,
+    // Names of the listed packages, in the order they are drawn.
+    packages: Vec<String>,
+    table_state: TableState,
+}
+
+impl DependenciesComponent {
+    pub fn new(project_root: Option<&str>) -> Self {
+        Self {
+            project_root: pubspec::project_root(project_root),
+            is_selected: false,
+            task: None,
+            packages: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn is_running(&self) -> bool {
+        self.task.as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    async fn load_lock(project_root: &Path, store: &Arc<Mutex<Store>>) {
+        let lock = match PubspecLock::load(project_root) {
+            Ok(lock) => Some(lock),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        };
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetPubspecLock { lock })
+            .await;
+    }
+
+    async fn check_outdated(project_root: &Path, store: &Arc<Mutex<Store>>) {
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::StartPubTask {
+                command_line: "flutter pub outdated --json".to_string(),
+            })
+            .await;
+        let result = packages::outdated(project_root).await;
+        let error = result.as_ref().err().map(|e| e.to_string());
+        if let Ok(packages) = result {
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetOutdatedPackages {
+                    packages: Some(packages),
+                })
+                .await;
+        }
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::FinishPubTask { error })
+            .await;
+    }
+
+    fn refresh(&mut self, store: Arc<Mutex<Store>>) {
+        if self.is_running() {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        self.task = Some(tokio::spawn(async move {
+            Self::load_lock(&project_root, &store).await;
+            Self::check_outdated(&project_root, &store).await;
+        }));
+    }
+
+    fn run(&mut self, command: PubCommand, store: Arc<Mutex<Store>>) {
+        if self.is_running() {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        self.task = Some(tokio::spawn(async move {
+            if run_pub_task(&project_root, &command, &store).await.is_err() {
+                return;
+            }
+            let checked = store
+                .lock()
+                .await
+                .get_state()
+                .await
+                .dependencies
+                .outdated
+                .is_some();
+            if checked {
+                Self::check_outdated(&project_root, &store).await;
+            }
+        }));
+    }
+
+    fn selected_package(&self) -> Option<String> {
+        self.table_state
+            .selected()
+            .and_then(|index| self.packages.get(index))
+            .cloned()
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.packages.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+}
+
+/// Run a pub command, streaming its output into the task log, then reload the lock file.
+pub async fn run_pub_task(
+    project_root: &Path,
+    command: &PubCommand,
+    store: &Arc<Mutex<Store>>,
+) -> Result<()> {
+    store
+        .lock()
+        .await
+        .dispatch(StoreAction::StartPubTask {
+            command_line: command.command_line(),
+        })
+        .await;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let output_store = store.clone();
+    let output = tokio::spawn(async move {
+        while let Some(line) = rx.recv().await {
+            output_store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddPubTaskOutput { line })
+                .await;
+        }
+    });
+    let result = packages::run(project_root, command, tx).await;
+    let _ = output.await;
+    store
+        .lock()
+        .await
+        .dispatch(StoreAction::FinishPubTask {
+            error: result.as_ref().err().map(|e| e.to_string()),
+        })
+        .await;
+    DependenciesComponent::load_lock(project_root, store).await;
+    store
+        .lock()
+        .await
+        .dispatch(StoreAction::SetStaleLock {
+            reason: packages::stale_reason(project_root),
+        })
+        .await;
+    result
+}
+
+/// Packages of the lock file, with the versions of `pub outdated` once it has run.
+fn rows(state: &State) -> Vec<DependencyRow> {
+    let constraint = |name: &str| {
+        let pubspec = state.project.pubspec.as_ref()?;
+        let value = pubspec
+            .dependencies
+            .get(name)
+            .or(pubspec.dev_dependencies.get(name))?;
+        Some(match value {
+            serde_yaml::Value::String(constraint) => constraint.clone(),
+            serde_yaml::Value::Mapping(source) => source
+                .keys()
+                .filter_map(|key| key.as_str())
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            _ => "any".to_string(),
+        })
+    };
+    let dependencies = &state.dependencies;
+    let mut rows = match (&dependencies.outdated, &dependencies.lock) {
+        (Some(outdated), _) => outdated
+            .iter()
+            .map(|package| DependencyRow {
+                name: package.package.clone(),
+                kind: match package.kind.as_deref() {
+                    Some("direct") => "direct",
+                    Some("dev") => "dev",
+                    _ => "transitive",
+                },
+                constraint: constraint(&package.package).unwrap_or_default(),
+                current: package.current.as_ref().map(|v| v.version.clone()),
+                upgradable: package.upgradable.as_ref().map(|v| v.version.clone()),
+                resolvable: package.resolvable.as_ref().map(|v| v.version.clone()),
+                latest: package.latest.as_ref().map(|v| v.version.clone()),
+                is_discontinued: package.is_discontinued,
+            })
+            .collect::<Vec<_>>(),
+        (None, Some(lock)) => lock
+            .packages
+            .iter()
+            .map(|(name, package)| DependencyRow {
+                name: name.clone(),
+                kind: match package.dependency.as_str() {
+                    "direct main" => "direct",
+                    "direct dev" => "dev",
+                    "direct overridden" => "overridden",
+                    _ => "transitive",
+                },
+                constraint: constraint(name).unwrap_or_default(),
+                current: Some(package.version.clone()),
+                upgradable: None,
+                resolvable: None,
+                latest: None,
+                is_discontinued: false,
+            })
+            .collect(),
+        (None, None) => vec![],
+    };
+    let order = |kind: &str| match kind {
+        "direct" => 0,
+        "dev" => 1,
+        "overridden" => 2,
+        _ => 3,
+    };
+    rows.sort_by(|a, b| {
+        order(a.kind)
+            .cmp(&order(b.kind))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+impl Component for DependenciesComponent {
+    fn init(&mut self, _: Rect, store: Arc<Mutex<Store>>) -> Result<()> {
+        let project_root = self.project_root.clone();
+        tokio::spawn(async move {
+            Self::load_lock(&project_root, &store).await;
+        });
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('o') => self.refresh(store),
+            KeyCode::Char('g') => self.run(PubCommand::Get, store),
+            KeyCode::Char('u') => {
+                if let Some(package) = self.selected_package() {
+                    self.run(PubCommand::Upgrade(Some(package)), store);
+                }
+            }
+            KeyCode::Char('U') => self.run(PubCommand::Upgrade(None), store),
+            KeyCode::Char('m') => self.run(PubCommand::UpgradeMajorVersions, store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let mut title = vec![Span::raw("Dependencies")];
+        if let Some(task) = &state.dependencies.task {
+            title.push(Span::raw(" "));
+            title.push(format!("● {}", task).yellow().bold());
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(TASK_LOG_HEIGHT),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let rows = rows(state);
+        self.packages = rows.iter().map(|row| row.name.clone()).collect();
+        if self.table_state.selected().unwrap_or(0) >= rows.len() {
+            self.table_state.select(Some(rows.len().saturating_sub(1)));
+        }
+        if rows.is_empty() {
+            let text = Paragraph::new(" No pubspec.lock yet, press g to run pub get ")
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(text, layout[0]);
+        } else {
+            let version = |version: &Option<String>, current: &Option<String>| {
+                let text = version.clone().unwrap_or("-".to_string());
+                let color = if state.dependencies.outdated.is_none() || version == current {
+                    Color::DarkGray
+                } else {
+                    Color::Yellow
+                };
+                Cell::from(text).style(Style::default().fg(color))
+            };
+            let rows = rows
+                .iter()
+                .map(|row| {
+                    let name = if row.is_discontinued {
+                        Cell::from(format!("{} (discontinued)", row.name))
+                            .style(Style::default().fg(Color::Red))
+                    } else {
+                        Cell::from(row.name.clone())
+                    };
+                    Row::new(vec![
+                        name,
+                        Cell::from(row.kind).style(Style::default().fg(Color::DarkGray)),
+                        Cell::from(row.constraint.clone()),
+                        Cell::from(row.current.clone().unwrap_or("-".to_string())),
+                        version(&row.upgradable, &row.current),
+                        version(&row.resolvable, &row.current),
+                        version(&row.latest, &row.current),
+                    ])
+                })
+                .collect::<Vec<_>>();
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Min(20),
+                    Constraint::Length(10),
+                    Constraint::Length(14),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+                    Constraint::Length(12),
+                ],
+            )
+            .header(
+                Row::new(vec![
+                    "Package",
+                    "Kind",
+                    "Constraint",
+                    "Current",
+                    "Upgradable",
+                    "Resolvable",
+                    "Latest",
+                ])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+            f.render_stateful_widget(table, layout[0], &mut self.table_state);
+        }
+
+        let log = &state.dependencies.task_log;
+        let visible = log.len().saturating_sub(TASK_LOG_HEIGHT as usize - 1);
+        let lines = log[visible..]
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect::<Vec<_>>();
+        let task_log = Paragraph::new(lines).block(
+            Block::default()
+                .title("Task log")
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        );
+        f.render_widget(task_log, layout[1]);
+        f.render_widget(
+            Paragraph::new(KEY_HINTS).style(Style::default().fg(Color::DarkGray)),
+            layout[2],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        packages::{LockedPackage, PubspecLock},
+        pubspec::Pubspec,
+    };
+
+    #[test]
+    fn rows_from_lock_list_direct_dependencies_first() {
+        let locked = |dependency: &str, version: &str| LockedPackage {
+            dependency: dependency.to_string(),
+            source: "hosted".to_string(),
+            version: version.to_string(),
+            description: serde_yaml::Value::Null,
+        };
+        let mut state = State::default();
+        state.project.pubspec =
+            Some(Pubspec::parse("name: app\ndependencies:\n  http: ^1.1.0\n").unwrap());
+        state.dependencies.lock = Some(PubspecLock {
+            packages: [
+                ("async".to_string(), locked("transitive", "2.11.0")),
+                ("http".to_string(), locked("direct main", "1.1.2")),
+            ]
+            .into_iter()
+            .collect(),
+        });
+        let rows = rows(&state);
+        assert_eq!(
+            rows.iter().map(|row| row.name.as_str()).collect::<Vec<_>>(),
+            vec!["http", "async"]
+        );
+        assert_eq!(rows[0].constraint, "^1.1.0");
+        assert_eq!(rows[0].current.as_deref(), Some("1.1.2"));
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use strum::Display;
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    packages::{cache, graph::DependencyGraph, license, PubspecLock},
+    pubspec::{self, Pubspec},
+    store::{
+        action::Action as StoreAction,
+        state::{DependencyState, State},
+        Store,
+    },
+    tui::Frame,
+    utils::get_data_dir,
+};
+use color_eyre::eyre::{eyre, Result};
+
+use super::Component;
+
+const KEY_HINTS: &str = "l: load  Enter: fold  v: switch view  e: export licenses as CSV";
+
+#[derive(PartialEq, Clone, Copy, Display)]
+enum View {
+    #[strum(to_string = "tree")]
+    Tree,
+    #[strum(to_string = "licenses")]
+    Licenses,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TreeRow {
+    depth: usize,
+    package: String,
+    // Packages from a root down to this one, which identifies the row.
+    path: Vec<String>,
+    has_children: bool,
+    // Already on the path, so expanding it would loop.
+    is_cycle: bool,
+}
+
+pub struct DependencyGraphComponent {
+    project_root: PathBuf,
+    is_selected: bool,
+    view: View,
+    loader: Option<JoinHandle<()>>,
+    expanded: BTreeSet<Vec<String>>,
+    // Row paths of the tree, or the package of each license row, as drawn.
+    rows: Vec<Vec<String>>,
+    table_state: TableState,
+}
+
+impl DependencyGraphComponent {
+    pub fn new(project_root: Option<&str>) -> Self {
+        Self {
+            project_root: pubspec::project_root(project_root),
+            is_selected: false,
+            view: View::Tree,
+            loader: None,
+            expanded: BTreeSet::new(),
+            rows: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn load(&mut self, store: Arc<Mutex<Store>>) {
+        if self.loader.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        self.loader = Some(tokio::spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let pubspec = Pubspec::load(&project_root)?;
+                let lock = PubspecLock::load(&project_root)?;
+                let package_dir =
+                    |name: &str| cache::package_dir(&project_root, name, lock.packages.get(name)?);
+                let graph = DependencyGraph::build(&pubspec, &lock, |name| {
+                    Pubspec::load(&package_dir(name)?).ok()
+                });
+                let licenses = license::audit(&lock, package_dir);
+                Ok::<_, color_eyre::eyre::Error>((graph, licenses))
+            })
+            .await;
+            match result {
+                Ok(Ok((graph, licenses))) => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetDependencyGraph { graph, licenses })
+                        .await
+                }
+                Ok(Err(e)) => log::error!("Failed to load the dependency graph: {:?}", e),
+                Err(e) => log::error!("Failed to load the dependency graph: {:?}", e),
+            }
+        }));
+    }
+
+    fn export(&self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let name = state
+                .project
+                .pubspec
+                .map(|pubspec| pubspec.name)
+                .unwrap_or("project".to_string());
+            match Self::write_report(&name, &state.dependencies).await {
+                Ok(path) => {
+                    log::info!("Exported licenses to {}", path.display());
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetLicenseReport { path })
+                        .await;
+                }
+                Err(e) => log::error!("Failed to export licenses: {:?}", e),
+            }
+        });
+    }
+
+    async fn write_report(name: &str, dependencies: &DependencyState) -> Result<PathBuf> {
+        if dependencies.licenses.is_empty() {
+            return Err(eyre!("No licenses are loaded"));
+        }
+        let directory = get_data_dir().join("licenses");
+        tokio::fs::create_dir_all(&directory).await?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = directory.join(format!("{}-{}.csv", name, timestamp));
+        tokio::fs::write(&path, license::to_csv(&dependencies.licenses)).await?;
+        Ok(path)
+    }
+
+    fn toggle_fold(&mut self) {
+        let Some(path) = self.selected_row().cloned() else {
+            return;
+        };
+        if self.view == View::Tree && !self.expanded.remove(&path) {
+            self.expanded.insert(path);
+        }
+    }
+
+    fn selected_row(&self) -> Option<&Vec<String>> {
+        self.table_state
+            .selected()
+            .and_then(|index| self.rows.get(index))
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+
+    fn draw_tree(&self, tree: &[TreeRow], dependencies: &DependencyState) -> Table<'static> {
+        let rows = tree
+            .iter()
+            .map(|row| {
+                let marker = if row.is_cycle {
+                    "↻ "
+                } else if !row.has_children {
+                    "  "
+                } else if self.expanded.contains(&row.path) {
+                    "▼ "
+                } else {
+                    "▶ "
+                };
+                let version = dependencies
+                    .licenses
+                    .iter()
+                    .find(|license| license.package == row.package)
+                    .map(|license| license.version.clone())
+                    .unwrap_or_default();
+                Row::new(vec![
+                    Cell::from(format!(
+                        "{}{}{}",
+                        "  ".repeat(row.depth),
+                        marker,
+                        row.package
+                    )),
+                    Cell::from(version).style(Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect::<Vec<_>>();
+        Table::new(rows, [Constraint::Min(0), Constraint::Length(16)])
+    }
+
+    fn draw_licenses(&self, dependencies: &DependencyState) -> Table<'static> {
+        let rows = sorted_licenses(dependencies)
+            .into_iter()
+            .map(|license| {
+                let color = match license.license.as_str() {
+                    "Unknown" | "Not found" => Color::Red,
+                    _ => Color::Reset,
+                };
+                Row::new(vec![
+                    Cell::from(license.package.clone()),
+                    Cell::from(license.version.clone()).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(license.license.clone()).style(Style::default().fg(color)),
+                ])
+            })
+            .collect::<Vec<_>>();
+        Table::new(
+            rows,
+            [
+                Constraint::Min(0),
+                Constraint::Length(16),
+                Constraint::Length(14),
+            ],
+        )
+        .header(
+            Row::new(vec!["Package", "Version", "License"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+    }
+
+    fn details(
+        &self,
+        graph: &DependencyGraph,
+        dependencies: &DependencyState,
+    ) -> Vec<Line<'static>> {
+        let Some(package) = self.selected_row().and_then(|row| row.last()) else {
+            return vec![];
+        };
+        let label =
+            |text: &str| Span::styled(format!("{:<8}", text), Style::default().fg(Color::DarkGray));
+        let mut lines = vec![Line::from(package.clone().bold())];
+        if let Some(license) = dependencies
+            .licenses
+            .iter()
+            .find(|license| &license.package == package)
+        {
+            lines.push(Line::from(vec![
+                label("version"),
+                Span::raw(license.version.clone()),
+            ]));
+            lines.push(Line::from(vec![
+                label("source"),
+                Span::raw(license.source.clone()),
+            ]));
+            lines.push(Line::from(vec![
+                label("license"),
+                Span::raw(license.license.clone()),
+            ]));
+            if let Some(file) = &license.file {
+                lines.push(Line::from(Span::styled(
+                    file.display().to_string(),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+        lines.push(Line::from(""));
+        let mut used_by = graph.dependents(package);
+        if graph.roots.contains(package) {
+            used_by.insert(0, "project".to_string());
+        }
+        lines.push(Line::from(vec![
+            label("used by"),
+            Span::raw(used_by.join(", ")),
+        ]));
+        if let Some(chain) = graph.why(package) {
+            lines.push(Line::from(vec![
+                label("why"),
+                Span::raw(format!("project → {}", chain.join(" → "))),
+            ]));
+        }
+        lines
+    }
+}
+
+/// Rows of the dependency tree, with children of the `expanded` paths.
+fn tree_rows(graph: &DependencyGraph, expanded: &BTreeSet<Vec<String>>) -> Vec<TreeRow> {
+    fn visit(
+        graph: &DependencyGraph,
+        expanded: &BTreeSet<Vec<String>>,
+        path: Vec<String>,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let package = path.last().cloned().unwrap_or_default();
+        let children = graph
+            .dependencies
+            .get(&package)
+            .cloned()
+            .unwrap_or_default();
+        let is_cycle = path[..path.len() - 1].contains(&package);
+        let is_expanded = !is_cycle && expanded.contains(&path);
+        rows.push(TreeRow {
+            depth: path.len() - 1,
+            package,
+            path: path.clone(),
+            has_children: !children.is_empty(),
+            is_cycle,
+        });
+        if is_expanded {
+            for child in children {
+                visit(graph, expanded, [path.clone(), vec![child]].concat(), rows);
+            }
+        }
+    }
+
+    let mut rows = vec![];
+    for root in &graph.roots {
+        visit(graph, expanded, vec![root.clone()], &mut rows);
+    }
+    rows
+}
+
+fn sorted_licenses(dependencies: &DependencyState) -> Vec<&license::PackageLicense> {
+    let mut licenses = dependencies.licenses.iter().collect::<Vec<_>>();
+    licenses.sort_by(|a, b| a.license.cmp(&b.license).then(a.package.cmp(&b.package)));
+    licenses
+}
+
+impl Component for DependencyGraphComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('l') => self.load(store),
+            KeyCode::Char('v') => {
+                self.view = match self.view {
+                    View::Tree => View::Licenses,
+                    View::Licenses => View::Tree,
+                };
+                self.table_state.select(Some(0));
+            }
+            KeyCode::Char('e') => self.export(store),
+            KeyCode::Enter => self.toggle_fold(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let dependencies = &state.dependencies;
+        let mut title = vec![
+            Span::raw("Dependency graph"),
+            format!(" {} (v)", self.view).dark_gray(),
+        ];
+        if self.loader.as_ref().is_some_and(|task| !task.is_finished()) {
+            title.push(" ● loading".yellow().bold());
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(graph) = &dependencies.graph else {
+            self.rows = vec![];
+            let text = Paragraph::new(
+                " Press l to read dependencies and licenses from pubspec.lock and the pub cache ",
+            )
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let content = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(layout[0]);
+
+        let tree = match self.view {
+            View::Tree => tree_rows(graph, &self.expanded),
+            View::Licenses => vec![],
+        };
+        self.rows = match self.view {
+            View::Tree => tree.iter().map(|row| row.path.clone()).collect(),
+            View::Licenses => sorted_licenses(dependencies)
+                .into_iter()
+                .map(|license| vec![license.package.clone()])
+                .collect(),
+        };
+        if self.table_state.selected().unwrap_or(0) >= self.rows.len() {
+            self.table_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+        let table = match self.view {
+            View::Tree => self.draw_tree(&tree, dependencies),
+            View::Licenses => self.draw_licenses(dependencies),
+        }
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, content[0], &mut self.table_state);
+
+        let details = Paragraph::new(self.details(graph, dependencies))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::LEFT)
+                    .border_style(Style::default().fg(Color::DarkGray))
+                    .padding(Padding::horizontal(1)),
+            );
+        f.render_widget(details, content[1]);
+
+        let hints = match &dependencies.license_report {
+            Some(path) => format!("{}  (last export: {})", KEY_HINTS, path.display()),
+            None => KEY_HINTS.to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(hints).style(Style::default().fg(Color::DarkGray)),
+            layout[1],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_rows_expand_paths_and_stop_at_cycles() {
+        let graph = DependencyGraph {
+            roots: vec!["a".to_string()],
+            dependencies: [
+                ("a".to_string(), vec!["b".to_string()]),
+                ("b".to_string(), vec!["a".to_string()]),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let path = |packages: &[&str]| packages.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let expanded = [path(&["a"]), path(&["a", "b"]), path(&["a", "b", "a"])]
+            .into_iter()
+            .collect();
+        let rows = tree_rows(&graph, &expanded);
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.depth, row.package.as_str(), row.is_cycle))
+                .collect::<Vec<_>>(),
+            vec![(0, "a", false), (1, "b", false), (2, "a", true)]
+        );
+    }
+}
@@ -1,26 +1,219 @@
 use std::sync::Arc;
 
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{prelude::*, widgets::*};
+use tokio::sync::Mutex;
 
-use crate::{daemon::flutter::FlutterDaemon, store::state::State, tui::Frame};
+use crate::{
+    action::Action,
+    daemon::flutter::FlutterDaemon,
+    desktop,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+};
 use color_eyre::eyre::Result;
 
 use super::Component;
 
 pub struct DevToolsComponent {
     daemon: Arc<FlutterDaemon>,
+    is_selected: bool,
+    // The DevTools URL followed by the deep link of each connected session.
+    links: Vec<String>,
+    list_state: ListState,
 }
 
 impl DevToolsComponent {
     pub fn new(daemon: Arc<FlutterDaemon>) -> Self {
-        Self { daemon }
+        Self {
+            daemon,
+            is_selected: false,
+            links: vec![],
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn serve(&self, store: Arc<Mutex<Store>>) {
+        let daemon = self.daemon.clone();
+        tokio::spawn(async move {
+            let url = match daemon.serve_devtools().await.map(|result| result.url()) {
+                Ok(Some(url)) => url,
+                Ok(None) => {
+                    log::error!("DevTools did not report its address");
+                    return;
+                }
+                Err(e) => {
+                    log::error!("Failed to start DevTools: {:?}", e);
+                    return;
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetDevToolsUrl { url })
+                .await;
+        });
+    }
+
+    fn selected_link(&self) -> Option<String> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.links.get(index))
+            .cloned()
+    }
+
+    fn open(&self) {
+        let Some(link) = self.selected_link() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if let Err(e) = desktop::open_url(&link).await {
+                log::error!("Failed to open {}: {:?}", link, e);
+            }
+        });
+    }
+
+    fn copy(&self) {
+        let Some(link) = self.selected_link() else {
+            return;
+        };
+        tokio::spawn(async move {
+            match desktop::copy_to_clipboard(&link).await {
+                Ok(()) => log::info!("Copied {}", link),
+                Err(e) => log::error!("Failed to copy {}: {:?}", link, e),
+            }
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.links.len().saturating_sub(1));
+        self.list_state.select(Some(index));
     }
 }
 
+/// The DevTools URL connecting to an app through its VM service.
+pub fn deep_link(devtools_url: &str, vm_service_uri: &str) -> String {
+    format!(
+        "{}/?uri={}",
+        devtools_url,
+        encode_uri_component(vm_service_uri)
+    )
+}
+
+fn encode_uri_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
 impl Component for DevToolsComponent {
-    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, _: &State) -> Result<()> {
-        let block = Block::default().title("DevTools").borders(Borders::ALL);
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('s') => self.serve(store),
+            KeyCode::Char('o') | KeyCode::Enter => self.open(),
+            KeyCode::Char('c') => self.copy(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("DevTools")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(devtools_url) = &state.devtools_url else {
+            self.links = vec![];
+            let text = Paragraph::new(" Press s to start DevTools ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let mut items = vec![ListItem::new(Line::from(vec![
+            "DevTools ".bold(),
+            Span::raw(devtools_url.clone()),
+        ]))];
+        self.links = vec![devtools_url.clone()];
+        for session in &state.sessions {
+            let Some(vm_service_uri) = &session.vm_service_uri else {
+                continue;
+            };
+            let link = deep_link(devtools_url, vm_service_uri);
+            let name = match &session.device_id {
+                Some(device_id) => format!("{} ({}) ", session.id, device_id),
+                None => format!("{} ", session.id),
+            };
+            let name = if state.selected_session_id.as_ref() == Some(&session.id) {
+                name.bold()
+            } else {
+                Span::raw(name)
+            };
+            items.push(ListItem::new(Line::from(vec![
+                name,
+                Span::styled(link.clone(), Style::default().fg(Color::Cyan)),
+            ])));
+            self.links.push(link);
+        }
+        if self.list_state.selected().unwrap_or(0) >= self.links.len() {
+            self.list_state
+                .select(Some(self.links.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
         f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, layout[0], &mut self.list_state);
+        f.render_widget(
+            Paragraph::new("Enter/o: open in browser  c: copy link")
+                .style(Style::default().fg(Color::DarkGray)),
+            layout[1],
+        );
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_link_encodes_vm_service_uri() {
+        assert_eq!(
+            deep_link("http://127.0.0.1:9100", "ws://127.0.0.1:50300/AbC=/ws"),
+            "http://127.0.0.1:9100/?uri=ws%3A%2F%2F127.0.0.1%3A50300%2FAbC%3D%2Fws"
+        );
+    }
+}
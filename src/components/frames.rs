@@ -0,0 +1,340 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    utils::get_data_dir,
+    vm_service::{
+        frames::{FrameSummary, FrameTiming, Percentiles},
+        io::response::Timeline,
+        timeline::{to_chrome_trace, RECORDED_STREAMS},
+    },
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+const BUDGET_60HZ: Duration = Duration::from_millis(16);
+const BUDGET_120HZ: Duration = Duration::from_millis(8);
+
+pub struct FramesComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    budget: Duration,
+}
+
+impl FramesComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            budget: BUDGET_60HZ,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn clear(&mut self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            if let Some(session_id) = state.selected_session_id {
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::ClearFrames { session_id })
+                    .await;
+            }
+        });
+    }
+
+    /// Start or stop recording the timeline, clearing the VM timeline first if `clear` is set.
+    fn toggle_timeline_recording(&self, store: Arc<Mutex<Store>>, clear: bool) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let Some(timeline) = state.selected_session().map(|session| &session.timeline) else {
+                return;
+            };
+            let result = if timeline.recording_since.is_some() {
+                Self::stop_timeline_recording(&selected, &timeline.previous_streams, &store).await
+            } else {
+                Self::start_timeline_recording(&selected, clear, &store).await
+            };
+            if let Err(e) = result {
+                log::error!("Failed to record timeline: {:?}", e);
+            }
+        });
+    }
+
+    async fn start_timeline_recording(
+        selected: &SelectedIsolate,
+        clear: bool,
+        store: &Arc<Mutex<Store>>,
+    ) -> Result<()> {
+        if clear {
+            selected.vm_service.clear_vm_timeline().await?;
+        }
+        // Other tools like DevTools may be recording streams too.
+        let previous_streams = selected
+            .vm_service
+            .get_vm_timeline_flags()
+            .await?
+            .recorded_streams;
+        let mut streams = previous_streams
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        streams.extend(
+            RECORDED_STREAMS
+                .into_iter()
+                .filter(|stream| !previous_streams.iter().any(|previous| previous == stream)),
+        );
+        selected.vm_service.set_vm_timeline_flags(&streams).await?;
+        let since = selected.vm_service.get_vm_timeline_micros().await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::StartTimelineRecording {
+                session_id: selected.session_id.clone(),
+                since,
+                previous_streams,
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn stop_timeline_recording(
+        selected: &SelectedIsolate,
+        previous_streams: &[String],
+        store: &Arc<Mutex<Store>>,
+    ) -> Result<()> {
+        let until = selected.vm_service.get_vm_timeline_micros().await?;
+        let previous_streams = previous_streams
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        selected
+            .vm_service
+            .set_vm_timeline_flags(&previous_streams)
+            .await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::StopTimelineRecording {
+                session_id: selected.session_id.clone(),
+                until,
+            })
+            .await;
+        Ok(())
+    }
+
+    fn save_timeline(&self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some((since, until)) = state
+                .selected_session()
+                .and_then(|session| session.timeline.recorded)
+            else {
+                return;
+            };
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let result = async {
+                let timeline = selected
+                    .vm_service
+                    .get_vm_timeline(since, until - since)
+                    .await?;
+                save_chrome_trace(&selected.session_id, &timeline).await
+            }
+            .await;
+            match result {
+                Ok(path) => log::info!("Saved timeline to {}", path.display()),
+                Err(e) => log::error!("Failed to save timeline: {:?}", e),
+            }
+        });
+    }
+
+    fn draw_chart(
+        &self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        title: &str,
+        frames: &[FrameTiming],
+        value: fn(&FrameTiming) -> u64,
+    ) {
+        let block = Block::default().title(title).borders(Borders::TOP);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+
+        let budget = self.budget.as_micros() as u64;
+        let frames = &frames[frames.len().saturating_sub(inner.width as usize)..];
+        // Keep the budget line at half height unless a frame is even slower.
+        let max = frames
+            .iter()
+            .map(value)
+            .max()
+            .unwrap_or_default()
+            .max(budget * 2);
+        let bars = frames
+            .iter()
+            .map(|frame| {
+                let color = if value(frame) > budget {
+                    Color::Red
+                } else {
+                    Color::Cyan
+                };
+                Bar::default()
+                    .value(value(frame))
+                    .text_value(String::new())
+                    .style(Style::default().fg(color))
+            })
+            .collect::<Vec<_>>();
+        let chart = BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(0)
+            .max(max);
+        f.render_widget(chart, inner);
+
+        let offset = (budget as f64 / max as f64 * inner.height as f64).round() as u16;
+        if offset == 0 || offset > inner.height {
+            return;
+        }
+        let y = inner.bottom() - offset;
+        let buffer = f.buffer_mut();
+        for x in inner.left()..inner.right() {
+            let cell = buffer.get_mut(x, y);
+            if cell.symbol() == " " {
+                cell.set_symbol("┄").set_fg(Color::Yellow);
+            }
+        }
+    }
+}
+
+async fn save_chrome_trace(session_id: &str, timeline: &Timeline) -> Result<PathBuf> {
+    let directory = get_data_dir().join("timelines");
+    tokio::fs::create_dir_all(&directory).await?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = directory.join(format!("{}-{}.trace.json", session_id, timestamp));
+    tokio::fs::write(&path, serde_json::to_vec(&to_chrome_trace(timeline))?).await?;
+    Ok(path)
+}
+
+fn format_ms(us: u64) -> String {
+    format!("{:.1}ms", us as f64 / 1000.0)
+}
+
+fn format_percentiles(percentiles: &Percentiles) -> String {
+    format!(
+        "p50 {}  p90 {}  p99 {}",
+        format_ms(percentiles.p50),
+        format_ms(percentiles.p90),
+        format_ms(percentiles.p99)
+    )
+}
+
+impl Component for FramesComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('b') => {
+                self.budget = if self.budget == BUDGET_60HZ {
+                    BUDGET_120HZ
+                } else {
+                    BUDGET_60HZ
+                };
+            }
+            KeyCode::Char('c') => self.clear(store),
+            KeyCode::Char('t') => self.toggle_timeline_recording(store, false),
+            KeyCode::Char('T') => self.toggle_timeline_recording(store, true),
+            KeyCode::Char('w') => self.save_timeline(store),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let mut title = vec![Span::raw(format!(
+            "Frames ({}ms budget)",
+            self.budget.as_millis()
+        ))];
+        match state.selected_session().map(|session| &session.timeline) {
+            Some(timeline) if timeline.recording_since.is_some() => {
+                title.push(Span::raw(" "));
+                title.push("● REC timeline (t: stop)".red().bold());
+            }
+            Some(timeline) if timeline.recorded.is_some() => {
+                title.push(Span::raw(" timeline recorded (w: save trace)"));
+            }
+            _ => {}
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(session) = state.selected_session().filter(|s| !s.frames.is_empty()) else {
+            let text = Paragraph::new(" Frame timings of the selected app will appear here ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+            ])
+            .split(inner);
+
+        let summary = FrameSummary::new(&session.frames, self.budget);
+        let lines = vec![
+            Line::from(format!(
+                " {} frames  janky build {}  janky raster {}",
+                summary.frames, summary.janky_build, summary.janky_raster
+            )),
+            Line::from(format!(" Build   {}", format_percentiles(&summary.build))),
+            Line::from(format!(" Raster  {}", format_percentiles(&summary.raster))),
+        ];
+        f.render_widget(Paragraph::new(lines), layout[0]);
+
+        self.draw_chart(f, layout[1], "Build", &session.frames, |frame| frame.build);
+        self.draw_chart(f, layout[2], "Raster", &session.frames, |frame| {
+            frame.raster
+        });
+        Ok(())
+    }
+}
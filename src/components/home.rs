@@ -4,11 +4,32 @@ use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::{prelude::*, widgets::*};
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
 use tokio::sync::{mpsc::UnboundedSender, Mutex};
 
 use super::{
-    apps::AppsComponent, devices::DevicesComponent, devtools::DevToolsComponent,
-    project::ProjectComponent, Component, Frame,
+    analysis::{self, AnalysisComponent},
+    apps::AppsComponent,
+    debug_flags::DebugFlagsComponent,
+    debugger::DebuggerComponent,
+    dependencies::DependenciesComponent,
+    dependency_graph::DependencyGraphComponent,
+    devices::DevicesComponent,
+    devtools::DevToolsComponent,
+    frames::FramesComponent,
+    inspector::InspectorComponent,
+    isolates::IsolatesComponent,
+    memory::MemoryComponent,
+    network::NetworkComponent,
+    overrides::OverridesComponent,
+    profiler::ProfilerComponent,
+    project::{ProjectComponent, PROJECT_LINES},
+    rebuilds::RebuildsComponent,
+    repl::ReplComponent,
+    service_extensions::ServiceExtensionsComponent,
+    test_runner::{self, TestRunnerComponent},
+    tree_dump::TreeDumpComponent,
+    Component, Frame,
 };
 use crate::{
     action::Action,
@@ -23,6 +44,34 @@ enum Tab {
     Project,
     Apps,
     Devices,
+    Panel,
+}
+
+#[derive(PartialEq, Clone, Copy, EnumIter, Display)]
+enum Panel {
+    DevTools,
+    Inspector,
+    #[strum(to_string = "Debug flags")]
+    DebugFlags,
+    Extensions,
+    Frames,
+    Memory,
+    #[strum(to_string = "CPU profiler")]
+    Profiler,
+    Network,
+    #[strum(to_string = "REPL")]
+    Repl,
+    Debugger,
+    Isolates,
+    #[strum(to_string = "Tree dumps")]
+    TreeDump,
+    Rebuilds,
+    Dependencies,
+    #[strum(to_string = "Dependency graph")]
+    DependencyGraph,
+    Overrides,
+    Analysis,
+    Tests,
 }
 
 pub struct Home {
@@ -30,41 +79,229 @@ pub struct Home {
     apps: AppsComponent,
     devices: DevicesComponent,
     devtools: DevToolsComponent,
+    inspector: InspectorComponent,
+    debug_flags: DebugFlagsComponent,
+    service_extensions: ServiceExtensionsComponent,
+    frames: FramesComponent,
+    memory: MemoryComponent,
+    profiler: ProfilerComponent,
+    network: NetworkComponent,
+    repl: ReplComponent,
+    debugger: DebuggerComponent,
+    isolates: IsolatesComponent,
+    tree_dump: TreeDumpComponent,
+    rebuilds: RebuildsComponent,
+    dependencies: DependenciesComponent,
+    dependency_graph: DependencyGraphComponent,
+    overrides: OverridesComponent,
+    analysis: AnalysisComponent,
+    test_runner: TestRunnerComponent,
 
     selected_tab: Tab,
+    selected_panel: Panel,
+    // While a text field has focus every key goes to the selected panel.
+    is_input_mode: bool,
 }
 
 impl Home {
-    pub fn new(daemon: Arc<FlutterDaemon>, session_manager: Arc<Mutex<SessionManager>>) -> Self {
+    pub fn new(
+        daemon: Arc<FlutterDaemon>,
+        session_manager: Arc<Mutex<SessionManager>>,
+        project_root: Option<&str>,
+    ) -> Self {
         let devices = DevicesComponent::new(daemon.clone());
-        let project = ProjectComponent::new(daemon.clone());
+        let project = ProjectComponent::new(daemon.clone(), project_root);
         let mut apps = AppsComponent::new(daemon.clone(), session_manager.clone());
         apps.set_selected(true);
         let devtools = DevToolsComponent::new(daemon.clone());
+        let inspector = InspectorComponent::new(session_manager.clone());
+        let debug_flags = DebugFlagsComponent::new(session_manager.clone());
+        let service_extensions =
+            ServiceExtensionsComponent::new(session_manager.clone(), project_root);
+        let frames = FramesComponent::new(session_manager.clone());
+        let memory = MemoryComponent::new(session_manager.clone());
+        let profiler = ProfilerComponent::new(session_manager.clone());
+        let network = NetworkComponent::new(session_manager.clone());
+        let repl = ReplComponent::new(session_manager.clone());
+        let debugger = DebuggerComponent::new(session_manager.clone());
+        let isolates = IsolatesComponent::new(session_manager.clone());
+        let tree_dump = TreeDumpComponent::new(session_manager.clone());
+        let rebuilds = RebuildsComponent::new(session_manager.clone());
+        let dependencies = DependenciesComponent::new(project_root);
+        let dependency_graph = DependencyGraphComponent::new(project_root);
+        let overrides = OverridesComponent::new(project_root);
+        let analysis = AnalysisComponent::new(project_root);
+        let test_runner = TestRunnerComponent::new(project_root);
         Self {
             project,
             devices,
             apps,
             devtools,
+            inspector,
+            debug_flags,
+            service_extensions,
+            frames,
+            memory,
+            profiler,
+            network,
+            repl,
+            debugger,
+            isolates,
+            tree_dump,
+            rebuilds,
+            dependencies,
+            dependency_graph,
+            overrides,
+            analysis,
+            test_runner,
             selected_tab: Tab::Apps,
+            selected_panel: Panel::DevTools,
+            is_input_mode: false,
+        }
+    }
+
+    fn components(&mut self) -> Vec<&mut dyn Component> {
+        vec![
+            &mut self.project,
+            &mut self.apps,
+            &mut self.devices,
+            &mut self.devtools,
+            &mut self.inspector,
+            &mut self.debug_flags,
+            &mut self.service_extensions,
+            &mut self.frames,
+            &mut self.memory,
+            &mut self.profiler,
+            &mut self.network,
+            &mut self.repl,
+            &mut self.debugger,
+            &mut self.isolates,
+            &mut self.tree_dump,
+            &mut self.rebuilds,
+            &mut self.dependencies,
+            &mut self.dependency_graph,
+            &mut self.overrides,
+            &mut self.analysis,
+            &mut self.test_runner,
+        ]
+    }
+
+    fn panel(&mut self) -> &mut dyn Component {
+        match self.selected_panel {
+            Panel::DevTools => &mut self.devtools,
+            Panel::Inspector => &mut self.inspector,
+            Panel::DebugFlags => &mut self.debug_flags,
+            Panel::Extensions => &mut self.service_extensions,
+            Panel::Frames => &mut self.frames,
+            Panel::Memory => &mut self.memory,
+            Panel::Profiler => &mut self.profiler,
+            Panel::Network => &mut self.network,
+            Panel::Repl => &mut self.repl,
+            Panel::Debugger => &mut self.debugger,
+            Panel::Isolates => &mut self.isolates,
+            Panel::TreeDump => &mut self.tree_dump,
+            Panel::Rebuilds => &mut self.rebuilds,
+            Panel::Dependencies => &mut self.dependencies,
+            Panel::DependencyGraph => &mut self.dependency_graph,
+            Panel::Overrides => &mut self.overrides,
+            Panel::Analysis => &mut self.analysis,
+            Panel::Tests => &mut self.test_runner,
         }
     }
+
+    fn update_selection(&mut self) {
+        self.project.set_selected(self.selected_tab == Tab::Project);
+        self.apps.set_selected(self.selected_tab == Tab::Apps);
+        self.devices.set_selected(self.selected_tab == Tab::Devices);
+        self.devtools.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::DevTools,
+        );
+        self.inspector.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Inspector,
+        );
+        self.debug_flags.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::DebugFlags,
+        );
+        self.service_extensions.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Extensions,
+        );
+        self.frames
+            .set_selected(self.selected_tab == Tab::Panel && self.selected_panel == Panel::Frames);
+        self.memory
+            .set_selected(self.selected_tab == Tab::Panel && self.selected_panel == Panel::Memory);
+        self.profiler.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Profiler,
+        );
+        self.network
+            .set_selected(self.selected_tab == Tab::Panel && self.selected_panel == Panel::Network);
+        self.repl
+            .set_selected(self.selected_tab == Tab::Panel && self.selected_panel == Panel::Repl);
+        self.debugger.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Debugger,
+        );
+        self.isolates.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Isolates,
+        );
+        self.tree_dump.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::TreeDump,
+        );
+        self.rebuilds.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Rebuilds,
+        );
+        self.dependencies.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Dependencies,
+        );
+        self.dependency_graph.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::DependencyGraph,
+        );
+        self.overrides.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Overrides,
+        );
+        self.analysis.set_selected(
+            self.selected_tab == Tab::Panel && self.selected_panel == Panel::Analysis,
+        );
+        self.test_runner
+            .set_selected(self.selected_tab == Tab::Panel && self.selected_panel == Panel::Tests);
+    }
+
+    fn cycle_panel(&mut self, forward: bool, store: Arc<Mutex<Store>>) {
+        let panels = Panel::iter().collect::<Vec<_>>();
+        let index = panels
+            .iter()
+            .position(|panel| *panel == self.selected_panel)
+            .unwrap_or(0);
+        let index = if forward {
+            (index + 1) % panels.len()
+        } else {
+            (index + panels.len() - 1) % panels.len()
+        };
+        if self.selected_panel == Panel::Inspector {
+            self.inspector.release(store);
+        }
+        self.selected_panel = panels[index];
+        self.update_selection();
+    }
 }
 
 impl Component for Home {
     fn init(&mut self, area: Rect, store: Arc<Mutex<Store>>) -> Result<()> {
-        self.project.init(area, store.clone())?;
-        self.apps.init(area, store.clone())?;
-        self.devices.init(area, store.clone())?;
-        self.devtools.init(area, store.clone())?;
+        for component in self.components() {
+            component.init(area, store.clone())?;
+        }
+        Ok(())
+    }
+
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        for component in self.components() {
+            component.register_action_handler(tx.clone())?;
+        }
         Ok(())
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
-        self.project.register_config_handler(config.clone())?;
-        self.apps.register_config_handler(config.clone())?;
-        self.devices.register_config_handler(config.clone())?;
-        self.devtools.register_config_handler(config.clone())?;
+        for component in self.components() {
+            component.register_config_handler(config.clone())?;
+        }
         Ok(())
     }
 
@@ -73,35 +310,56 @@ impl Component for Home {
         key: KeyEvent,
         store: Arc<Mutex<Store>>,
     ) -> Result<Option<Action>> {
-        if key.code == KeyCode::Left {
-            self.selected_tab = match self.selected_tab {
-                Tab::Project => Tab::Devices,
-                Tab::Apps => Tab::Project,
-                Tab::Devices => Tab::Apps,
-            };
-            self.project.set_selected(self.selected_tab == Tab::Project);
-            self.apps.set_selected(self.selected_tab == Tab::Apps);
-            self.devices.set_selected(self.selected_tab == Tab::Devices);
-            return Ok(None);
-        } else if key.code == KeyCode::Right {
-            self.selected_tab = match self.selected_tab {
-                Tab::Project => Tab::Apps,
-                Tab::Apps => Tab::Devices,
-                Tab::Devices => Tab::Project,
-            };
-            self.project.set_selected(self.selected_tab == Tab::Project);
-            self.apps.set_selected(self.selected_tab == Tab::Apps);
-            self.devices.set_selected(self.selected_tab == Tab::Devices);
-            return Ok(None);
+        if self.is_input_mode {
+            return self.panel().handle_key_events(key, store);
+        }
+        match key.code {
+            KeyCode::Left => {
+                self.selected_tab = match self.selected_tab {
+                    Tab::Project => Tab::Panel,
+                    Tab::Apps => Tab::Project,
+                    Tab::Devices => Tab::Apps,
+                    Tab::Panel => Tab::Devices,
+                };
+                self.update_selection();
+                return Ok(None);
+            }
+            KeyCode::Right => {
+                self.selected_tab = match self.selected_tab {
+                    Tab::Project => Tab::Apps,
+                    Tab::Apps => Tab::Devices,
+                    Tab::Devices => Tab::Panel,
+                    Tab::Panel => Tab::Project,
+                };
+                self.update_selection();
+                return Ok(None);
+            }
+            KeyCode::Tab => {
+                self.cycle_panel(true, store);
+                return Ok(None);
+            }
+            KeyCode::BackTab => {
+                self.cycle_panel(false, store);
+                return Ok(None);
+            }
+            _ => {}
         }
-        if let Tab::Project = self.selected_tab {
-            return self.project.handle_key_events(key, store);
+        match self.selected_tab {
+            Tab::Project => self.project.handle_key_events(key, store),
+            Tab::Apps => self.apps.handle_key_events(key, store),
+            Tab::Devices => self.devices.handle_key_events(key, store),
+            Tab::Panel => self.panel().handle_key_events(key, store),
         }
-        if let Tab::Apps = self.selected_tab {
-            return self.apps.handle_key_events(key, store);
+    }
+
+    fn update(&mut self, action: Action, store: Arc<Mutex<Store>>) -> Result<Option<Action>> {
+        match action {
+            Action::EnterInputMode => self.is_input_mode = true,
+            Action::ExitInputMode => self.is_input_mode = false,
+            _ => {}
         }
-        if let Tab::Devices = self.selected_tab {
-            return self.devices.handle_key_events(key, store);
+        for component in self.components() {
+            component.update(action.clone(), store.clone())?;
         }
         Ok(None)
     }
@@ -111,22 +369,25 @@ impl Component for Home {
         mouse: MouseEvent,
         store: Arc<Mutex<Store>>,
     ) -> Result<Option<Action>> {
-        self.project.handle_mouse_events(mouse, store.clone())?;
-        self.apps.handle_mouse_events(mouse, store.clone())?;
-        self.devices.handle_mouse_events(mouse, store.clone())?;
-        self.devtools.handle_mouse_events(mouse, store.clone())?;
+        for component in self.components() {
+            component.handle_mouse_events(mouse, store.clone())?;
+        }
         Ok(None)
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let status_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(area);
         let layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
-            .split(area);
+            .split(status_layout[0]);
         let tab_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3),
+                Constraint::Length(PROJECT_LINES + 2),
                 Constraint::Percentage(50),
                 Constraint::Percentage(50),
             ])
@@ -135,7 +396,46 @@ impl Component for Home {
         self.project.draw(f, tab_layout[0], state)?;
         self.apps.draw(f, tab_layout[1], state)?;
         self.devices.draw(f, tab_layout[2], state)?;
-        self.devtools.draw(f, layout[1], state)?;
+
+        let panel_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(layout[1]);
+        let titles = Panel::iter()
+            .map(|panel| format!(" {} ", panel))
+            .collect::<Vec<_>>();
+        let selected_panel = Panel::iter()
+            .position(|panel| panel == self.selected_panel)
+            .unwrap_or(0);
+        let tabs = Tabs::new(titles)
+            .select(selected_panel)
+            .style(Style::default().fg(Color::DarkGray))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_widget(tabs, panel_layout[0]);
+        self.panel().draw(f, panel_layout[1], state)?;
+
+        let analysis_status = analysis::status(state);
+        let mut status = vec![];
+        if !analysis_status.is_empty() {
+            status.push(Span::styled(
+                " Analysis: ",
+                Style::default().fg(Color::DarkGray),
+            ));
+            status.extend(analysis_status);
+        }
+        let test_status = test_runner::status(state);
+        if !test_status.is_empty() {
+            status.push(Span::styled(
+                " Tests: ",
+                Style::default().fg(Color::DarkGray),
+            ));
+            status.extend(test_status);
+        }
+        f.render_widget(Paragraph::new(Line::from(status)), status_layout[1]);
         Ok(())
     }
 }
@@ -0,0 +1,410 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    vm_service::io::{diagnostics::DiagnosticsNode, event::EventKind},
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+struct InspectorRow {
+    value_id: Option<String>,
+    depth: usize,
+    has_children: bool,
+    is_collapsed: bool,
+    created_by_local_project: bool,
+    label: String,
+}
+
+pub struct InspectorComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    collapsed: HashSet<String>,
+    rows: Vec<InspectorRow>,
+    selected_row: Option<usize>,
+    inspect_listener: Option<JoinHandle<()>>,
+    // The isolate holding the references of the tree shown, to dispose them.
+    inspected: Arc<Mutex<Option<SelectedIsolate>>>,
+    list_state: ListState,
+}
+
+impl InspectorComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            collapsed: HashSet::new(),
+            rows: vec![],
+            selected_row: None,
+            inspect_listener: None,
+            inspected: Arc::new(Mutex::new(None)),
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn refresh(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let inspected = self.inspected.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            Self::dispose(&inspected).await;
+            if let Err(e) = Self::fetch_tree(&selected, &store, &inspected).await {
+                log::error!("Failed to get widget tree: {:?}", e);
+            }
+        });
+    }
+
+    /// Let the app free the widget tree, once the panel is left.
+    pub fn release(&mut self, store: Arc<Mutex<Store>>) {
+        let inspected = self.inspected.clone();
+        tokio::spawn(async move {
+            let Some(session_id) = Self::dispose(&inspected).await else {
+                return;
+            };
+            // The value ids of the tree are no longer valid.
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::ClearInspectorTree { session_id })
+                .await;
+        });
+    }
+
+    /// Dispose the references of the tree shown, returning the session it belongs to.
+    async fn dispose(inspected: &Mutex<Option<SelectedIsolate>>) -> Option<String> {
+        let previous = inspected.lock().await.take()?;
+        // The references of a stopped app go away with its isolate.
+        if let Err(e) = previous
+            .vm_service
+            .dispose_inspector_group(&previous.isolate_id)
+            .await
+        {
+            log::debug!("Failed to dispose inspector references: {:?}", e);
+        }
+        Some(previous.session_id)
+    }
+
+    async fn fetch_tree(
+        selected: &SelectedIsolate,
+        store: &Arc<Mutex<Store>>,
+        inspected: &Mutex<Option<SelectedIsolate>>,
+    ) -> Result<()> {
+        *inspected.lock().await = Some(selected.clone());
+        let root = selected
+            .vm_service
+            .get_root_widget_summary_tree(&selected.isolate_id)
+            .await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetInspectorTree {
+                session_id: selected.session_id.clone(),
+                root,
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn select_node(
+        selected: &SelectedIsolate,
+        store: &Arc<Mutex<Store>>,
+        value_id: String,
+    ) -> Result<()> {
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SelectInspectorNode {
+                session_id: selected.session_id.clone(),
+                value_id: value_id.clone(),
+            })
+            .await;
+        let properties = selected
+            .vm_service
+            .get_inspector_properties(&selected.isolate_id, &value_id)
+            .await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetInspectorProperties {
+                session_id: selected.session_id.clone(),
+                properties,
+            })
+            .await;
+        Ok(())
+    }
+
+    fn move_selection(&mut self, offset: isize, store: Arc<Mutex<Store>>) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let index = match self.selected_row {
+            Some(index) => index.saturating_add_signed(offset).min(self.rows.len() - 1),
+            None => 0,
+        };
+        let Some(value_id) = self.rows[index].value_id.clone() else {
+            return;
+        };
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            if let Err(e) = Self::select_node(&selected, &store, value_id).await {
+                log::error!("Failed to get widget properties: {:?}", e);
+            }
+        });
+    }
+
+    fn toggle_collapsed(&mut self) {
+        let Some(row) = self.selected_row.and_then(|index| self.rows.get(index)) else {
+            return;
+        };
+        if let Some(value_id) = &row.value_id {
+            if !self.collapsed.remove(value_id) {
+                self.collapsed.insert(value_id.clone());
+            }
+        }
+    }
+
+    fn toggle_select_mode(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let inspected = self.inspected.clone();
+        let previous_listener = self.inspect_listener.take();
+        self.inspect_listener = Some(tokio::spawn(async move {
+            if let Some(listener) = previous_listener {
+                listener.abort();
+            }
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let enabled = !state
+                .selected_session()
+                .map(|session| session.inspector.select_mode)
+                .unwrap_or_default();
+            if let Err(e) = selected
+                .vm_service
+                .set_inspector_select_mode(&selected.isolate_id, enabled)
+                .await
+            {
+                log::error!("Failed to toggle select widget mode: {:?}", e);
+                return;
+            }
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetInspectorSelectMode {
+                    session_id: selected.session_id.clone(),
+                    enabled,
+                })
+                .await;
+            if enabled {
+                Self::listen_inspect_events(selected, store, inspected).await;
+            }
+        }));
+    }
+
+    /// Follow widgets selected by tapping on the device while select widget mode is on.
+    async fn listen_inspect_events(
+        selected: SelectedIsolate,
+        store: Arc<Mutex<Store>>,
+        inspected: Arc<Mutex<Option<SelectedIsolate>>>,
+    ) {
+        let mut events = selected.vm_service.events();
+        while let Some(params) = events.recv().await {
+            if params.event.kind != EventKind::Inspect {
+                continue;
+            }
+            // Before fetching the selected widget, whose reference goes in the same group.
+            Self::dispose(&inspected).await;
+            let node = match selected
+                .vm_service
+                .get_selected_summary_widget(&selected.isolate_id)
+                .await
+            {
+                Ok(Some(node)) => node,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::error!("Failed to get selected widget: {:?}", e);
+                    continue;
+                }
+            };
+            let Some(value_id) = node.value_id else {
+                continue;
+            };
+            let result = async {
+                Self::fetch_tree(&selected, &store, &inspected).await?;
+                Self::select_node(&selected, &store, value_id).await
+            }
+            .await;
+            if let Err(e) = result {
+                log::error!("Failed to show selected widget: {:?}", e);
+            }
+        }
+    }
+
+    fn flatten(&self, node: &DiagnosticsNode, depth: usize, rows: &mut Vec<InspectorRow>) {
+        let is_collapsed = node
+            .value_id
+            .as_ref()
+            .map(|value_id| self.collapsed.contains(value_id))
+            .unwrap_or_default();
+        rows.push(InspectorRow {
+            value_id: node.value_id.clone(),
+            depth,
+            has_children: !node.children.is_empty(),
+            is_collapsed,
+            created_by_local_project: node.created_by_local_project,
+            label: node.label(),
+        });
+        if !is_collapsed {
+            for child in &node.children {
+                self.flatten(child, depth + 1, rows);
+            }
+        }
+    }
+
+    fn details(&self, state: &State) -> Vec<Line<'static>> {
+        let Some(inspector) = state.selected_session().map(|s| &s.inspector) else {
+            return vec![];
+        };
+        let mut lines = vec![];
+        let node = inspector
+            .selected_value_id
+            .as_ref()
+            .and_then(|value_id| find_node(inspector.root.as_ref()?, value_id));
+        if let Some(node) = node {
+            if let Some(widget_type) = &node.widget_runtime_type {
+                lines.push(Line::from(widget_type.clone().bold()));
+            }
+            if let Some(location) = &node.creation_location {
+                lines.push(Line::from(format!(
+                    "{}:{}:{}",
+                    location.file, location.line, location.column
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+        for property in &inspector.properties {
+            lines.push(Line::from(property.label()));
+        }
+        lines
+    }
+}
+
+fn find_node<'a>(node: &'a DiagnosticsNode, value_id: &str) -> Option<&'a DiagnosticsNode> {
+    if node.value_id.as_deref() == Some(value_id) {
+        return Some(node);
+    }
+    node.children
+        .iter()
+        .find_map(|child| find_node(child, value_id))
+}
+
+impl Component for InspectorComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('u') => self.refresh(store),
+            KeyCode::Char('s') => self.toggle_select_mode(store),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_collapsed(),
+            KeyCode::Up => self.move_selection(-1, store),
+            KeyCode::Down => self.move_selection(1, store),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let inspector = state.selected_session().map(|s| &s.inspector);
+        let title = match inspector {
+            Some(inspector) if inspector.select_mode => "Inspector (select widget mode)",
+            _ => "Inspector",
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(root) = inspector.and_then(|inspector| inspector.root.as_ref()) else {
+            self.rows = vec![];
+            self.selected_row = None;
+            let text = Paragraph::new(" Press u to load the widget tree of the selected app ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let mut rows = vec![];
+        self.flatten(root, 0, &mut rows);
+        self.rows = rows;
+        let selected_value_id = inspector.and_then(|i| i.selected_value_id.as_ref());
+        self.selected_row = selected_value_id
+            .and_then(|value_id| {
+                self.rows
+                    .iter()
+                    .position(|row| row.value_id.as_ref() == Some(value_id))
+            })
+            .or(self.selected_row.filter(|index| *index < self.rows.len()));
+        self.list_state.select(self.selected_row);
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(inner);
+
+        let items = self
+            .rows
+            .iter()
+            .map(|row| {
+                let marker = match (row.has_children, row.is_collapsed) {
+                    (true, true) => "▸ ",
+                    (true, false) => "▾ ",
+                    _ => "  ",
+                };
+                let color = if row.created_by_local_project {
+                    Color::White
+                } else {
+                    Color::DarkGray
+                };
+                ListItem::new(format!("{}{}{}", "  ".repeat(row.depth), marker, row.label))
+                    .style(Style::default().fg(color))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, layout[0], &mut self.list_state);
+
+        let details = Paragraph::new(self.details(state))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::LEFT));
+        f.render_widget(details, layout[1]);
+        Ok(())
+    }
+}
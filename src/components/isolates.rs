@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{state::State, Store},
+    tui::Frame,
+    vm_service::isolates::IsolateStatus,
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+#[derive(Clone, Copy)]
+enum IsolateCommand {
+    Pause,
+    Resume,
+    Kill,
+    ReloadSources,
+}
+
+pub struct IsolatesComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    // Ids of the listed isolates, in the order they are drawn.
+    isolate_ids: Vec<String>,
+    table_state: TableState,
+    // Set while waiting for the kill to be confirmed.
+    confirm_kill: Option<String>,
+}
+
+impl IsolatesComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            isolate_ids: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+            confirm_kill: None,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn selected_isolate_id(&self) -> Option<String> {
+        self.table_state
+            .selected()
+            .and_then(|index| self.isolate_ids.get(index))
+            .cloned()
+    }
+
+    fn run(&self, command: IsolateCommand, isolate_id: String, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(SelectedIsolate { vm_service, .. }) =
+                session_manager.lock().await.selected_isolate(&state).await
+            else {
+                return;
+            };
+            let (name, result) = match command {
+                IsolateCommand::Pause => ("pause", vm_service.pause(&isolate_id).await),
+                IsolateCommand::Resume => ("resume", vm_service.resume(&isolate_id).await),
+                IsolateCommand::Kill => ("kill", vm_service.kill(&isolate_id).await),
+                IsolateCommand::ReloadSources => (
+                    "reload the sources of",
+                    vm_service.reload_sources(&isolate_id).await,
+                ),
+            };
+            match result {
+                Ok(()) => log::info!("Requested to {} {}", name, isolate_id),
+                Err(e) => log::error!("Failed to {} {}: {:?}", name, isolate_id, e),
+            }
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.isolate_ids.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+}
+
+impl Component for IsolatesComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        if let Some(isolate_id) = self.confirm_kill.take() {
+            if key.code == KeyCode::Char('y') {
+                self.run(IsolateCommand::Kill, isolate_id, store);
+            }
+            return Ok(None);
+        }
+        let Some(isolate_id) = self.selected_isolate_id() else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Char('p') => self.run(IsolateCommand::Pause, isolate_id, store),
+            KeyCode::Char('c') => self.run(IsolateCommand::Resume, isolate_id, store),
+            KeyCode::Char('l') => self.run(IsolateCommand::ReloadSources, isolate_id, store),
+            KeyCode::Char('x') => self.confirm_kill = Some(isolate_id),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("Isolates")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(session) = state
+            .selected_session()
+            .filter(|session| !session.isolates.is_empty())
+        else {
+            self.isolate_ids = vec![];
+            let text = Paragraph::new(" Isolates of the selected app are listed here ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        self.isolate_ids = session.isolates.iter().map(|i| i.id.clone()).collect();
+        if self.table_state.selected().unwrap_or(0) >= self.isolate_ids.len() {
+            self.table_state
+                .select(Some(self.isolate_ids.len().saturating_sub(1)));
+        }
+        let rows = session
+            .isolates
+            .iter()
+            .map(|isolate| {
+                let status = session.isolate_statuses.get(&isolate.id);
+                let color = match status {
+                    Some(IsolateStatus::Running) => Color::Green,
+                    Some(IsolateStatus::Paused(_)) => Color::Yellow,
+                    Some(IsolateStatus::Starting) | None => Color::DarkGray,
+                };
+                let kind = if isolate.is_system_isolate {
+                    "system"
+                } else {
+                    ""
+                };
+                Row::new(vec![
+                    Cell::from(isolate.name.clone()),
+                    Cell::from(isolate.id.clone()),
+                    Cell::from(status.map(|s| s.label()).unwrap_or("…"))
+                        .style(Style::default().fg(color)),
+                    Cell::from(kind).style(Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(35),
+                Constraint::Percentage(30),
+                Constraint::Min(20),
+                Constraint::Length(6),
+            ],
+        )
+        .header(
+            Row::new(vec!["Name", "Id", "Status", ""])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, layout[0], &mut self.table_state);
+
+        let footer = match &self.confirm_kill {
+            Some(isolate_id) => Paragraph::new(format!("Kill {}? (y/n)", isolate_id))
+                .style(Style::default().fg(Color::Red)),
+            None => Paragraph::new("p: pause  c: resume  l: reload sources  x: kill")
+                .style(Style::default().fg(Color::DarkGray)),
+        };
+        f.render_widget(footer, layout[1]);
+        Ok(())
+    }
+}
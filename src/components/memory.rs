@@ -0,0 +1,278 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::SessionManager,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    vm_service::memory::{diff_snapshots, AllocationSnapshot},
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+pub struct MemoryComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    table_state: TableState,
+}
+
+impl MemoryComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn take_snapshot(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let profile = match selected
+                .vm_service
+                .get_allocation_profile(&selected.isolate_id, true)
+                .await
+            {
+                Ok(profile) => profile,
+                Err(e) => {
+                    log::error!("Failed to get allocation profile: {:?}", e);
+                    return;
+                }
+            };
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddAllocationSnapshot {
+                    session_id: selected.session_id,
+                    snapshot: AllocationSnapshot::new(timestamp, profile),
+                })
+                .await;
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset);
+        self.table_state.select(Some(index));
+    }
+
+    fn draw_chart(&self, f: &mut Frame<'_>, area: Rect, state: &State) {
+        let Some(session) = state.selected_session() else {
+            return;
+        };
+        let samples = &session.memory.samples;
+        let Some(first) = samples.first() else {
+            let text = Paragraph::new(" Memory usage of the selected app will appear here ")
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(text, area);
+            return;
+        };
+        let points = |value: fn(&_) -> u64| {
+            samples
+                .iter()
+                .map(|sample| {
+                    let seconds = sample.timestamp.saturating_sub(first.timestamp) as f64 / 1000.0;
+                    (seconds, value(sample) as f64 / MB)
+                })
+                .collect::<Vec<_>>()
+        };
+        let used = points(|s| s.heap_usage);
+        let capacity = points(|s| s.heap_capacity);
+        let external = points(|s| s.external_usage);
+        let gcs = samples
+            .iter()
+            .zip(used.iter())
+            .filter(|(sample, _)| sample.gc)
+            .map(|(_, point)| *point)
+            .collect::<Vec<_>>();
+
+        let max_x = used.last().map(|(x, _)| *x).unwrap_or_default().max(1.0);
+        let max_y = capacity
+            .iter()
+            .chain(external.iter())
+            .map(|(_, y)| *y)
+            .fold(1.0, f64::max);
+
+        let datasets = vec![
+            Dataset::default()
+                .name("capacity")
+                .graph_type(GraphType::Line)
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(&capacity),
+            Dataset::default()
+                .name("used")
+                .graph_type(GraphType::Line)
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&used),
+            Dataset::default()
+                .name("external")
+                .graph_type(GraphType::Line)
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Magenta))
+                .data(&external),
+            Dataset::default()
+                .name("GC")
+                .graph_type(GraphType::Scatter)
+                .marker(symbols::Marker::Dot)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&gcs),
+        ];
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .bounds([0.0, max_x])
+                    .labels(vec!["0s".into(), format!("{:.0}s", max_x).into()]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max_y])
+                    .labels(vec!["0".into(), format!("{:.1}MB", max_y).into()]),
+            );
+        f.render_widget(chart, area);
+    }
+
+    fn draw_snapshots(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) {
+        let snapshots = state
+            .selected_session()
+            .map(|session| session.memory.snapshots.as_slice())
+            .unwrap_or_default();
+        let (title, header, rows): (_, _, Vec<Row>) = match snapshots {
+            [before, after] => (
+                " Allocation diff (s: take another snapshot) ",
+                Row::new(vec!["Class", "Library", "Δ Instances", "Δ Bytes"]),
+                diff_snapshots(before, after)
+                    .into_iter()
+                    .map(|diff| {
+                        let color = if diff.bytes > 0 {
+                            Color::Red
+                        } else {
+                            Color::Green
+                        };
+                        Row::new(vec![
+                            diff.name,
+                            diff.library.unwrap_or_default(),
+                            format!("{:+}", diff.instances),
+                            format!("{:+}", diff.bytes),
+                        ])
+                        .style(Style::default().fg(color))
+                    })
+                    .collect(),
+            ),
+            [snapshot] => (
+                " Allocations (s: take another snapshot to diff) ",
+                Row::new(vec!["Class", "Library", "Instances", "Bytes"]),
+                snapshot
+                    .classes
+                    .iter()
+                    .map(|class| {
+                        Row::new(vec![
+                            class.name.clone(),
+                            class.library.clone().unwrap_or_default(),
+                            class.instances.to_string(),
+                            class.bytes.to_string(),
+                        ])
+                    })
+                    .collect(),
+            ),
+            _ => (
+                " Allocations (s: take a snapshot) ",
+                Row::new(vec!["Class", "Library", "Instances", "Bytes"]),
+                vec![],
+            ),
+        };
+        if let Some(selected) = self.table_state.selected() {
+            self.table_state
+                .select(Some(selected.min(rows.len().saturating_sub(1))));
+        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(30),
+                Constraint::Percentage(40),
+                Constraint::Percentage(15),
+                Constraint::Percentage(15),
+            ],
+        )
+        .header(header.style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().title(title).borders(Borders::TOP))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+impl Component for MemoryComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('s') => self.take_snapshot(store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let title = match state
+            .selected_session()
+            .and_then(|session| session.memory.samples.last())
+        {
+            Some(sample) => format!(
+                "Memory (used {:.1}MB / capacity {:.1}MB, external {:.1}MB)",
+                sample.heap_usage as f64 / MB,
+                sample.heap_capacity as f64 / MB,
+                sample.external_usage as f64 / MB
+            ),
+            None => "Memory".to_string(),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+        self.draw_chart(f, layout[0], state);
+        self.draw_snapshots(f, layout[1], state);
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::{Map, Value};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    vm_service::{io::http_profile::HttpProfileRequest, network::format_body},
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct NetworkComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    // Each session with HTTP logging enabled is polled on its own, by session id.
+    pollers: Arc<std::sync::Mutex<HashMap<String, JoinHandle<()>>>>,
+    // Ids of the listed requests, in the order they are drawn.
+    request_ids: Vec<String>,
+    table_state: TableState,
+    details_scroll: u16,
+}
+
+impl NetworkComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            pollers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            request_ids: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+            details_scroll: 0,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn toggle_logging(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let pollers = self.pollers.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let enabled = !state
+                .selected_session()
+                .is_some_and(|session| session.network.logging);
+            if let Err(e) = selected
+                .vm_service
+                .set_http_timeline_logging(&selected.isolate_id, enabled)
+                .await
+            {
+                log::error!("Failed to toggle HTTP logging: {:?}", e);
+                return;
+            }
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetHttpLogging {
+                    session_id: selected.session_id.clone(),
+                    enabled,
+                })
+                .await;
+            let session_id = selected.session_id.clone();
+            let mut pollers = pollers.lock().unwrap();
+            if let Some(poller) = pollers.remove(&session_id) {
+                poller.abort();
+            }
+            if enabled {
+                let poller = tokio::spawn(Self::poll_http_profile(selected, store));
+                pollers.insert(session_id, poller);
+            }
+        });
+    }
+
+    /// Fetch requests updated since the previous poll until the app goes away.
+    async fn poll_http_profile(selected: SelectedIsolate, store: Arc<Mutex<Store>>) {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = store.lock().await.get_state().await;
+            let updated_since = state
+                .sessions
+                .iter()
+                .find(|session| session.id == selected.session_id)
+                .and_then(|session| session.network.updated_since);
+            let profile = match selected
+                .vm_service
+                .get_http_profile(&selected.isolate_id, updated_since)
+                .await
+            {
+                Ok(profile) => profile,
+                Err(e) => {
+                    log::error!("Failed to get HTTP profile: {:?}", e);
+                    return;
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::UpdateHttpProfile {
+                    session_id: selected.session_id.clone(),
+                    profile,
+                })
+                .await;
+        }
+    }
+
+    fn clear(&self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            if let Err(e) = selected
+                .vm_service
+                .clear_http_profile(&selected.isolate_id)
+                .await
+            {
+                log::error!("Failed to clear HTTP profile: {:?}", e);
+            }
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::ClearHttpRequests {
+                    session_id: selected.session_id,
+                })
+                .await;
+        });
+    }
+
+    fn load_details(&mut self, store: Arc<Mutex<Store>>) {
+        let Some(id) = self
+            .table_state
+            .selected()
+            .and_then(|index| self.request_ids.get(index))
+            .cloned()
+        else {
+            return;
+        };
+        self.details_scroll = 0;
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let request = match selected
+                .vm_service
+                .get_http_profile_request(&selected.isolate_id, &id)
+                .await
+            {
+                Ok(request) => request,
+                Err(e) => {
+                    log::error!("Failed to get HTTP request {}: {:?}", id, e);
+                    return;
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetHttpRequestDetails {
+                    session_id: selected.session_id,
+                    request,
+                })
+                .await;
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.request_ids.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+        self.details_scroll = 0;
+    }
+
+    fn draw_requests(&mut self, f: &mut Frame<'_>, area: Rect, requests: &[HttpProfileRequest]) {
+        let rows = requests
+            .iter()
+            .map(|request| {
+                let (status, color) = match (request.status_code(), request.error()) {
+                    (_, Some(_)) => ("error".to_string(), Color::Red),
+                    (Some(code), None) if code >= 400 => (code.to_string(), Color::Red),
+                    (Some(code), None) => (code.to_string(), Color::Green),
+                    (None, None) => ("…".to_string(), Color::DarkGray),
+                };
+                Row::new(vec![
+                    Cell::from(request.method.clone()),
+                    Cell::from(status).style(Style::default().fg(color)),
+                    Cell::from(request.duration().map(format_duration).unwrap_or_default()),
+                    Cell::from(request.response_size().map(format_size).unwrap_or_default()),
+                    Cell::from(request.uri.clone()),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(6),
+                Constraint::Length(9),
+                Constraint::Length(9),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new(vec!["Method", "Status", "Time", "Size", "URL"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn details(request: &HttpProfileRequest) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(
+            format!("{} {}", request.method, request.uri).bold(),
+        )];
+        if let Some(code) = request.status_code() {
+            lines.push(Line::from(format!("Status: {}", code)));
+        }
+        if let Some(error) = request.error() {
+            lines.push(Line::from(error.to_string().red()));
+        }
+        let sections = [
+            (
+                "Request headers",
+                request.request.as_ref().map(|r| &r.headers),
+                &request.request_body,
+            ),
+            (
+                "Response headers",
+                request.response.as_ref().map(|r| &r.headers),
+                &request.response_body,
+            ),
+        ];
+        for (title, headers, body) in sections {
+            lines.push(Line::from(""));
+            lines.push(Line::from(title.to_string().bold()));
+            lines.extend(headers.into_iter().flat_map(format_headers));
+            match body {
+                Some(body) if !body.is_empty() => {
+                    lines.push(Line::from(""));
+                    lines.extend(format_body(body).lines().map(|l| Line::from(l.to_string())));
+                }
+                Some(_) => {}
+                None => lines.push(Line::from("Press Enter to load the body".dark_gray())),
+            }
+        }
+        lines
+    }
+}
+
+fn format_headers(headers: &Map<String, Value>) -> Vec<Line<'static>> {
+    headers
+        .iter()
+        .map(|(name, values)| {
+            let values = match values {
+                Value::Array(values) => values
+                    .iter()
+                    .map(|value| value.as_str().unwrap_or_default().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                value => value.to_string(),
+            };
+            Line::from(vec![
+                Span::styled(format!("{}: ", name), Style::default().fg(Color::Cyan)),
+                Span::raw(values),
+            ])
+        })
+        .collect()
+}
+
+fn format_duration(us: i64) -> String {
+    format!("{:.0}ms", us as f64 / 1000.0)
+}
+
+fn format_size(bytes: i64) -> String {
+    match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1_048_575 => format!("{:.1}KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1}MB", bytes as f64 / 1024.0 / 1024.0),
+    }
+}
+
+impl Component for NetworkComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('l') => self.toggle_logging(store),
+            KeyCode::Char('c') => self.clear(store),
+            KeyCode::Enter => self.load_details(store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.details_scroll = self.details_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.details_scroll = self.details_scroll.saturating_add(10),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let network = state.selected_session().map(|session| &session.network);
+        let mut title = vec![Span::raw("Network")];
+        if network.is_some_and(|network| network.logging) {
+            title.push(Span::raw(" "));
+            title.push("● logging".red().bold());
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(network) = network.filter(|network| !network.requests.is_empty()) else {
+            self.request_ids = vec![];
+            let text = Paragraph::new(" Press l to log HTTP requests of the selected app ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        self.request_ids = network.requests.iter().map(|r| r.id.clone()).collect();
+        if self.table_state.selected().unwrap_or(0) >= self.request_ids.len() {
+            self.table_state
+                .select(Some(self.request_ids.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(inner);
+        self.draw_requests(f, layout[0], &network.requests);
+
+        let selected_id = self
+            .table_state
+            .selected()
+            .and_then(|index| self.request_ids.get(index));
+        // Prefer the fetched request, which has the bodies.
+        let request = network
+            .selected
+            .as_ref()
+            .filter(|request| Some(&request.id) == selected_id)
+            .or_else(|| {
+                network
+                    .requests
+                    .iter()
+                    .find(|request| Some(&request.id) == selected_id)
+            });
+        if let Some(request) = request {
+            let details = Paragraph::new(Self::details(request))
+                .block(Block::default().borders(Borders::LEFT))
+                .wrap(Wrap { trim: false })
+                .scroll((self.details_scroll, 0));
+            f.render_widget(details, layout[1]);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    packages::{overrides, PubCommand},
+    pubspec::{self, Pubspec},
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+
+use super::{dependencies::run_pub_task, Component};
+
+const KEY_HINTS: &str = "Enter: toggle override  ←/→: choose checkout  s: scan for checkouts  \
+                         u: revert the overrides changed here";
+
+enum Edit {
+    Toggle {
+        package: String,
+        path: Option<PathBuf>,
+    },
+    Revert,
+}
+
+pub struct OverridesComponent {
+    project_root: PathBuf,
+    is_selected: bool,
+    task: Option<JoinHandle<()>>,
+    // The override entries toggled here as they were before, `None` where there was none, so
+    // reverting leaves the rest of pubspec.yaml alone.
+    replaced: Arc<Mutex<BTreeMap<String, Option<Vec<String>>>>>,
+    // Why the last edit failed.
+    error: Arc<std::sync::Mutex<Option<String>>>,
+    // Index of the chosen checkout of each package.
+    chosen: BTreeMap<String, usize>,
+    // As of the last draw.
+    packages: Vec<String>,
+    overridden: BTreeSet<String>,
+    candidates: BTreeMap<String, Vec<PathBuf>>,
+    table_state: TableState,
+}
+
+impl OverridesComponent {
+    pub fn new(project_root: Option<&str>) -> Self {
+        Self {
+            project_root: pubspec::project_root(project_root),
+            is_selected: false,
+            task: None,
+            replaced: Arc::new(Mutex::new(BTreeMap::new())),
+            error: Arc::new(std::sync::Mutex::new(None)),
+            chosen: BTreeMap::new(),
+            packages: vec![],
+            overridden: BTreeSet::new(),
+            candidates: BTreeMap::new(),
+            table_state: TableState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn scan(&self, store: Arc<Mutex<Store>>) {
+        let project_root = self.project_root.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let packages = state
+                .dependencies
+                .lock
+                .map(|lock| lock.packages.into_keys().collect::<Vec<_>>())
+                .unwrap_or_default();
+            let result = tokio::task::spawn_blocking(move || {
+                overrides::find_candidates(&project_root, &packages)
+            })
+            .await;
+            match result {
+                Ok(candidates) => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetOverrideCandidates { candidates })
+                        .await
+                }
+                Err(e) => log::error!("Failed to look for local checkouts: {:?}", e),
+            }
+        });
+    }
+
+    fn selected_package(&self) -> Option<String> {
+        self.table_state
+            .selected()
+            .and_then(|index| self.packages.get(index))
+            .cloned()
+    }
+
+    fn chosen_candidate(&self, package: &str) -> Option<PathBuf> {
+        let candidates = self.candidates.get(package)?;
+        let index = self.chosen.get(package).copied().unwrap_or_default();
+        candidates.get(index).cloned()
+    }
+
+    fn choose(&mut self, offset: isize) {
+        let Some(package) = self.selected_package() else {
+            return;
+        };
+        let count = self
+            .candidates
+            .get(&package)
+            .map(Vec::len)
+            .unwrap_or_default();
+        if count == 0 {
+            return;
+        }
+        let index = self.chosen.get(&package).copied().unwrap_or_default();
+        let index = (index as isize + offset).rem_euclid(count as isize) as usize;
+        self.chosen.insert(package, index);
+    }
+
+    fn toggle(&mut self, store: Arc<Mutex<Store>>) {
+        let Some(package) = self.selected_package() else {
+            return;
+        };
+        let path = if self.overridden.contains(&package) {
+            None
+        } else {
+            match self.chosen_candidate(&package) {
+                Some(path) => Some(path),
+                None => {
+                    log::warn!("No local checkout of {} was found", package);
+                    return;
+                }
+            }
+        };
+        self.edit(Edit::Toggle { package, path }, store);
+    }
+
+    fn edit(&mut self, edit: Edit, store: Arc<Mutex<Store>>) {
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        let replaced = self.replaced.clone();
+        let error = self.error.clone();
+        self.task = Some(tokio::spawn(async move {
+            let path = project_root.join("pubspec.yaml");
+            let content = match tokio::fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(e) => {
+                    log::error!("Failed to read {}: {:?}", path.display(), e);
+                    return;
+                }
+            };
+            let mut replaced = replaced.lock().await;
+            let edited = match edit {
+                Edit::Toggle { package, path } => {
+                    if !replaced.contains_key(&package) {
+                        let entry = overrides::override_entry(&content, &package);
+                        replaced.insert(package.clone(), entry);
+                    }
+                    match path {
+                        Some(checkout) => {
+                            let checkout = overrides::relative_path(&project_root, &checkout);
+                            overrides::set_path_override(
+                                &content,
+                                &package,
+                                &checkout.display().to_string(),
+                            )
+                        }
+                        None => Ok(overrides::remove_override(&content, &package)),
+                    }
+                }
+                Edit::Revert => {
+                    if replaced.is_empty() {
+                        return;
+                    }
+                    let restored =
+                        replaced
+                            .iter()
+                            .try_fold(content.clone(), |content, (package, entry)| {
+                                overrides::restore_override(&content, package, entry.as_deref())
+                            });
+                    if restored.is_ok() {
+                        replaced.clear();
+                    }
+                    restored
+                }
+            };
+            drop(replaced);
+            let edited = match edited {
+                Ok(edited) => edited,
+                Err(e) => {
+                    *error.lock().unwrap() = Some(e.to_string());
+                    return;
+                }
+            };
+            *error.lock().unwrap() = None;
+            if let Err(e) = tokio::fs::write(&path, edited).await {
+                log::error!("Failed to write {}: {:?}", path.display(), e);
+                return;
+            }
+            let pubspec = Pubspec::load(&project_root).map_err(|e| e.to_string());
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetPubspec {
+                    pubspec,
+                    platform_directories: pubspec::platform_directories(&project_root),
+                })
+                .await;
+            let _ = run_pub_task(&project_root, &PubCommand::Get, &store).await;
+        }));
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.packages.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+}
+
+/// A short description of an override, like `path: ../http` or `1.10.0`.
+fn describe(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::String(version) => version.clone(),
+        serde_yaml::Value::Mapping(source) => source
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(value) => value.clone(),
+                    serde_yaml::Value::Mapping(value) => value
+                        .get("url")
+                        .and_then(|url| url.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    _ => String::new(),
+                };
+                format!("{}: {}", key.as_str().unwrap_or_default(), value)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "any".to_string(),
+    }
+}
+
+impl Component for OverridesComponent {
+    fn init(&mut self, _: Rect, store: Arc<Mutex<Store>>) -> Result<()> {
+        self.scan(store);
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Enter => self.toggle(store),
+            KeyCode::Char('u') => self.edit(Edit::Revert, store),
+            KeyCode::Char('s') => self.scan(store),
+            KeyCode::Left => self.choose(-1),
+            KeyCode::Right => self.choose(1),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("Overrides")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let overrides = state
+            .project
+            .pubspec
+            .as_ref()
+            .map(|pubspec| pubspec.dependency_overrides.clone())
+            .unwrap_or_default();
+        let locked = state
+            .dependencies
+            .lock
+            .as_ref()
+            .map(|lock| lock.packages.clone())
+            .unwrap_or_default();
+        self.candidates = state.dependencies.override_candidates.clone();
+        self.overridden = overrides.keys().cloned().collect();
+        // Overridden packages and those with a local checkout come first.
+        let mut packages = locked
+            .keys()
+            .chain(overrides.keys())
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        packages.sort_by_key(|package| {
+            (
+                !self.overridden.contains(package),
+                !self.candidates.contains_key(package),
+                !locked
+                    .get(package)
+                    .is_some_and(|locked| locked.dependency.starts_with("direct")),
+            )
+        });
+        self.packages = packages;
+        if self.table_state.selected().unwrap_or(0) >= self.packages.len() {
+            self.table_state
+                .select(Some(self.packages.len().saturating_sub(1)));
+        }
+
+        if self.packages.is_empty() {
+            let text = Paragraph::new(" No packages yet, run pub get from the Dependencies panel ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        }
+
+        let rows = self
+            .packages
+            .iter()
+            .map(|package| {
+                let version = locked
+                    .get(package)
+                    .map(|locked| locked.version.clone())
+                    .unwrap_or_default();
+                let override_cell = match overrides.get(package) {
+                    Some(value) => {
+                        Cell::from(describe(value)).style(Style::default().fg(Color::Yellow))
+                    }
+                    None => Cell::from("-").style(Style::default().fg(Color::DarkGray)),
+                };
+                let candidates = self
+                    .candidates
+                    .get(package)
+                    .map(Vec::len)
+                    .unwrap_or_default();
+                let candidate = match self.chosen_candidate(package) {
+                    Some(path) => {
+                        let path = overrides::relative_path(&self.project_root, &path);
+                        if candidates > 1 {
+                            format!("{} ({} found)", path.display(), candidates)
+                        } else {
+                            path.display().to_string()
+                        }
+                    }
+                    None => String::new(),
+                };
+                Row::new(vec![
+                    Cell::from(package.clone()),
+                    Cell::from(version).style(Style::default().fg(Color::DarkGray)),
+                    override_cell,
+                    Cell::from(candidate).style(Style::default().fg(Color::Cyan)),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(25),
+                Constraint::Length(12),
+                Constraint::Percentage(30),
+                Constraint::Min(0),
+            ],
+        )
+        .header(
+            Row::new(vec!["Package", "Locked", "Override", "Local checkout"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, layout[0], &mut self.table_state);
+        f.render_widget(
+            match self.error.lock().unwrap().clone() {
+                Some(error) => Paragraph::new(error).style(Style::default().fg(Color::Red)),
+                None => Paragraph::new(KEY_HINTS).style(Style::default().fg(Color::DarkGray)),
+            },
+            layout[1],
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use strum::Display;
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    utils::get_data_dir,
+    vm_service::{
+        io::response::CpuSamples,
+        profiler::{
+            call_tree, flame_blocks, to_speedscope, CallTreeNode, CallTreeOrder, PackageFilter,
+        },
+    },
+};
+use color_eyre::eyre::Result;
+
+use super::{text_input::TextInput, Component};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+enum ProfileView {
+    #[strum(to_string = "top-down")]
+    TopDown,
+    #[strum(to_string = "bottom-up")]
+    BottomUp,
+    #[strum(to_string = "flame graph")]
+    FlameGraph,
+}
+
+impl ProfileView {
+    fn next(self) -> Self {
+        match self {
+            ProfileView::TopDown => ProfileView::BottomUp,
+            ProfileView::BottomUp => ProfileView::FlameGraph,
+            ProfileView::FlameGraph => ProfileView::TopDown,
+        }
+    }
+
+    fn order(self) -> CallTreeOrder {
+        match self {
+            ProfileView::BottomUp => CallTreeOrder::BottomUp,
+            ProfileView::TopDown | ProfileView::FlameGraph => CallTreeOrder::TopDown,
+        }
+    }
+}
+
+struct ProfileRow {
+    // Function indices from the root, used to remember collapsed nodes.
+    path: Vec<usize>,
+    depth: usize,
+    has_children: bool,
+    is_collapsed: bool,
+    self_ticks: u64,
+    total_ticks: u64,
+    function: usize,
+}
+
+// The call tree of the samples it was built from, so it is not rebuilt on every frame.
+struct CachedTree {
+    key: (i64, usize, CallTreeOrder, String),
+    tree: CallTreeNode,
+}
+
+pub struct ProfilerComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    view: ProfileView,
+    filter: String,
+    // Set while the package filter is being edited.
+    filter_input: Option<TextInput>,
+    collapsed: HashSet<Vec<usize>>,
+    rows: Vec<ProfileRow>,
+    list_state: ListState,
+    cache: Option<CachedTree>,
+}
+
+impl ProfilerComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            view: ProfileView::TopDown,
+            filter: String::new(),
+            filter_input: None,
+            collapsed: HashSet::new(),
+            rows: vec![],
+            list_state: ListState::default().with_selected(Some(0)),
+            cache: None,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn toggle_recording(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let recording_since = state
+                .selected_session()
+                .and_then(|session| session.cpu_profile.recording_since);
+            let result = match recording_since {
+                Some(since) => Self::stop_recording(&selected, &store, since).await,
+                None => Self::start_recording(&selected, &store).await,
+            };
+            if let Err(e) = result {
+                log::error!("Failed to record CPU profile: {:?}", e);
+            }
+        });
+    }
+
+    async fn start_recording(selected: &SelectedIsolate, store: &Arc<Mutex<Store>>) -> Result<()> {
+        // The profiler is usually already enabled, and can't be toggled once isolates run.
+        if let Err(e) = selected.vm_service.set_flag("profiler", "true").await {
+            log::debug!("Failed to enable the profiler: {:?}", e);
+        }
+        selected
+            .vm_service
+            .clear_cpu_samples(&selected.isolate_id)
+            .await?;
+        let since = selected.vm_service.get_vm_timeline_micros().await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::StartCpuRecording {
+                session_id: selected.session_id.clone(),
+                since,
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn stop_recording(
+        selected: &SelectedIsolate,
+        store: &Arc<Mutex<Store>>,
+        since: i64,
+    ) -> Result<()> {
+        let now = selected.vm_service.get_vm_timeline_micros().await?;
+        let samples = selected
+            .vm_service
+            .get_cpu_samples(&selected.isolate_id, since, now - since)
+            .await?;
+        store
+            .lock()
+            .await
+            .dispatch(StoreAction::SetCpuSamples {
+                session_id: selected.session_id.clone(),
+                samples,
+            })
+            .await;
+        Ok(())
+    }
+
+    fn export(&self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session) = state.selected_session() else {
+                return;
+            };
+            let Some(samples) = &session.cpu_profile.samples else {
+                return;
+            };
+            match save_speedscope(&session.id, samples).await {
+                Ok(path) => log::info!("Saved CPU profile to {}", path.display()),
+                Err(e) => log::error!("Failed to save CPU profile: {:?}", e),
+            }
+        });
+    }
+
+    fn tree(&mut self, samples: &CpuSamples) -> &CallTreeNode {
+        let order = self.view.order();
+        let key = (
+            samples.time_origin_micros,
+            samples.samples.len(),
+            order,
+            self.filter.clone(),
+        );
+        match &mut self.cache {
+            Some(cache) if cache.key == key => {}
+            cache => {
+                let tree = call_tree(samples, order, &PackageFilter::parse(&self.filter));
+                *cache = Some(CachedTree { key, tree });
+            }
+        }
+        &self.cache.as_ref().unwrap().tree
+    }
+
+    fn flatten(
+        collapsed: &HashSet<Vec<usize>>,
+        node: &CallTreeNode,
+        path: &mut Vec<usize>,
+        rows: &mut Vec<ProfileRow>,
+    ) {
+        for child in &node.children {
+            let Some(function) = child.function else {
+                continue;
+            };
+            path.push(function);
+            let is_collapsed = collapsed.contains(path);
+            rows.push(ProfileRow {
+                path: path.clone(),
+                depth: path.len() - 1,
+                has_children: !child.children.is_empty(),
+                is_collapsed,
+                self_ticks: child.self_ticks,
+                total_ticks: child.total_ticks,
+                function,
+            });
+            if !is_collapsed {
+                Self::flatten(collapsed, child, path, rows);
+            }
+            path.pop();
+        }
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.list_state.select(Some(index));
+    }
+
+    fn toggle_collapsed(&mut self) {
+        let Some(row) = self.list_state.selected().and_then(|i| self.rows.get(i)) else {
+            return;
+        };
+        if row.has_children && !self.collapsed.remove(&row.path) {
+            self.collapsed.insert(row.path.clone());
+        }
+    }
+
+    fn handle_filter_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let Some(input) = &mut self.filter_input else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.filter_input = None;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter => {
+                self.filter = input.value().trim().to_string();
+                self.filter_input = None;
+                self.collapsed.clear();
+                return Ok(Some(Action::ExitInputMode));
+            }
+            _ => {
+                input.handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw_tree(&mut self, f: &mut Frame<'_>, area: Rect, samples: &CpuSamples) {
+        self.tree(samples);
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        let total = cache.tree.total_ticks.max(1) as f64;
+        let mut rows = vec![];
+        Self::flatten(&self.collapsed, &cache.tree, &mut vec![], &mut rows);
+        self.rows = rows;
+        if self.list_state.selected().unwrap_or(0) >= self.rows.len() {
+            self.list_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let items = self
+            .rows
+            .iter()
+            .map(|row| {
+                let marker = match (row.has_children, row.is_collapsed) {
+                    (false, _) => "  ",
+                    (true, true) => "▸ ",
+                    (true, false) => "▾ ",
+                };
+                let function = &samples.functions[row.function];
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(
+                        "{:>6.1}% {:>6.1}% ",
+                        row.total_ticks as f64 / total * 100.0,
+                        row.self_ticks as f64 / total * 100.0
+                    )),
+                    Span::raw(format!("{}{}", "  ".repeat(row.depth), marker)),
+                    Span::raw(function.name()),
+                    Span::styled(
+                        format!("  {}", function.resolved_url),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title("  Total    Self")
+                    .borders(Borders::NONE),
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn draw_flame_graph(&mut self, f: &mut Frame<'_>, area: Rect, samples: &CpuSamples) {
+        let blocks = flame_blocks(self.tree(samples), area.width);
+        let buffer = f.buffer_mut();
+        for block in blocks {
+            if block.depth >= area.height as usize {
+                continue;
+            }
+            let colors = [
+                Color::Red,
+                Color::LightRed,
+                Color::Yellow,
+                Color::LightYellow,
+            ];
+            let style = Style::default()
+                .bg(colors[block.function % colors.len()])
+                .fg(Color::Black);
+            let name = samples.functions[block.function].name();
+            let label = format!("{:<width$}", name, width = block.width as usize)
+                .chars()
+                .take(block.width as usize)
+                .collect::<String>();
+            buffer.set_string(area.x + block.x, area.y + block.depth as u16, label, style);
+        }
+    }
+}
+
+async fn save_speedscope(session_id: &str, samples: &CpuSamples) -> Result<PathBuf> {
+    let directory = get_data_dir().join("profiles");
+    tokio::fs::create_dir_all(&directory).await?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = directory.join(format!("{}-{}.speedscope.json", session_id, timestamp));
+    let profile = to_speedscope(samples, session_id);
+    tokio::fs::write(&path, serde_json::to_vec(&profile)?).await?;
+    Ok(path)
+}
+
+impl Component for ProfilerComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        if self.filter_input.is_some() {
+            return self.handle_filter_key_events(key);
+        }
+        match key.code {
+            KeyCode::Char('s') => self.toggle_recording(store),
+            KeyCode::Char('t') => self.view = self.view.next(),
+            KeyCode::Char('e') => self.export(store),
+            KeyCode::Char('/') => {
+                self.filter_input = Some(TextInput::new(&self.filter));
+                return Ok(Some(Action::EnterInputMode));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_collapsed(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let profile = state.selected_session().map(|s| &s.cpu_profile);
+        let mut title = vec![Span::raw(format!("CPU profiler ({})", self.view))];
+        if profile.and_then(|p| p.recording_since).is_some() {
+            title.push(Span::raw(" "));
+            title.push("● REC".red().bold());
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(inner);
+        match &self.filter_input {
+            Some(input) => {
+                let text = Paragraph::new(format!(" Packages: {}", input.value()));
+                f.render_widget(text, layout[0]);
+                f.set_cursor(layout[0].x + 11 + input.cursor() as u16, layout[0].y);
+            }
+            None => {
+                let filter = if self.filter.is_empty() {
+                    "all packages".to_string()
+                } else {
+                    self.filter.clone()
+                };
+                let text = Paragraph::new(format!(
+                    " Packages: {}  (s: start/stop  t: view  /: filter  e: export)",
+                    filter
+                ))
+                .style(Style::default().fg(Color::DarkGray));
+                f.render_widget(text, layout[0]);
+            }
+        }
+
+        let Some(samples) = profile.and_then(|p| p.samples.as_ref()) else {
+            let text =
+                Paragraph::new(" Press s to start recording CPU samples of the selected app ")
+                    .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(text, layout[1]);
+            return Ok(());
+        };
+        match self.view {
+            ProfileView::TopDown | ProfileView::BottomUp => self.draw_tree(f, layout[1], samples),
+            ProfileView::FlameGraph => self.draw_flame_graph(f, layout[1], samples),
+        }
+        Ok(())
+    }
+}
@@ -1,34 +1,135 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::Rect;
 use ratatui::{prelude::*, widgets::*};
+use tokio::{sync::Mutex, task::JoinHandle};
 
-use crate::store::state::State;
+use crate::{
+    action::Action,
+    packages::{self, PubCommand},
+    pubspec::{self, Pubspec},
+    store::{action::Action as StoreAction, state::State, Store},
+};
 use crate::{daemon::flutter::FlutterDaemon, tui::Frame};
 use color_eyre::eyre::Result;
 
-use super::Component;
+use super::{dependencies::run_pub_task, Component};
+
+/// Lines drawn inside the borders of the project panel.
+pub const PROJECT_LINES: u16 = 6;
+// Branch switches change the pubspec behind our back, so it is checked regularly.
+const STALE_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 pub struct ProjectComponent {
     daemon: Arc<FlutterDaemon>,
+    project_root: PathBuf,
     is_selected: bool,
+    pub_get: Option<JoinHandle<()>>,
 }
 
 impl ProjectComponent {
-    pub fn new(daemon: Arc<FlutterDaemon>) -> Self {
+    pub fn new(daemon: Arc<FlutterDaemon>, project_root: Option<&str>) -> Self {
         Self {
             daemon,
+            project_root: pubspec::project_root(project_root),
             is_selected: false,
+            pub_get: None,
         }
     }
 
     pub fn set_selected(&mut self, is_selected: bool) {
         self.is_selected = is_selected;
     }
+
+    fn load(&self, store: Arc<Mutex<Store>>) {
+        let daemon = self.daemon.clone();
+        let project_root = self.project_root.clone();
+        tokio::spawn(async move {
+            let pubspec = Pubspec::load(&project_root).map_err(|e| e.to_string());
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetPubspec {
+                    pubspec,
+                    platform_directories: pubspec::platform_directories(&project_root),
+                })
+                .await;
+            match daemon
+                .get_supported_platforms(project_root.display().to_string())
+                .await
+            {
+                Ok(platforms) => {
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::SetSupportedPlatforms { platforms })
+                        .await
+                }
+                Err(e) => log::error!("Failed to get supported platforms: {:?}", e),
+            }
+        });
+    }
+
+    fn check_stale_lock(&self, store: Arc<Mutex<Store>>) {
+        let project_root = self.project_root.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STALE_LOCK_CHECK_INTERVAL);
+            let mut last_reason = None;
+            loop {
+                interval.tick().await;
+                let reason = Some(packages::stale_reason(&project_root));
+                if reason == last_reason {
+                    continue;
+                }
+                last_reason = reason.clone();
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::SetStaleLock {
+                        reason: reason.flatten(),
+                    })
+                    .await;
+            }
+        });
+    }
+
+    fn pub_get(&mut self, store: Arc<Mutex<Store>>) {
+        if self
+            .pub_get
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        self.pub_get = Some(tokio::spawn(async move {
+            let _ = run_pub_task(&project_root, &PubCommand::Get, &store).await;
+        }));
+    }
 }
 
 impl Component for ProjectComponent {
-    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, _: &State) -> Result<()> {
+    fn init(&mut self, _: Rect, store: Arc<Mutex<Store>>) -> Result<()> {
+        self.load(store.clone());
+        self.check_stale_lock(store);
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Enter => self.load(store),
+            KeyCode::Char('g') => self.pub_get(store),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
         let default_color = if self.is_selected {
             Color::White
         } else {
@@ -40,7 +141,77 @@ impl Component for ProjectComponent {
             .padding(Padding::horizontal(1))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(default_color));
-        let text = Paragraph::new("lazyflutter")
+
+        let project = &state.project;
+        let Some(pubspec) = &project.pubspec else {
+            let message = project
+                .pubspec_error
+                .clone()
+                .unwrap_or(format!("Loading {}", self.project_root.display()));
+            let text = Paragraph::new(message)
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: true })
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let label = |text: &str| {
+            Span::styled(
+                format!("{:<10}", text),
+                Style::default().fg(Color::DarkGray),
+            )
+        };
+        let sdk = pubspec
+            .environment
+            .iter()
+            .map(|(name, constraint)| format!("{} {}", name, constraint))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let supported = match &project.supported_platforms {
+            Some(platforms) => Line::from(
+                [label("supports")]
+                    .into_iter()
+                    .chain(platforms.iter().map(|platform| {
+                        // Platforms without a host directory can be added with `flutter create`.
+                        let color = if project.platform_directories.contains(platform) {
+                            Color::Green
+                        } else {
+                            Color::DarkGray
+                        };
+                        Span::styled(format!("{} ", platform), Style::default().fg(color))
+                    }))
+                    .collect::<Vec<_>>(),
+            ),
+            None => Line::from(vec![label("supports"), "…".dark_gray()]),
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                pubspec.name.clone().bold(),
+                Span::raw(" "),
+                Span::raw(pubspec.version.clone().unwrap_or_default()),
+            ]),
+            Line::from(vec![label("sdk"), Span::raw(sdk)]),
+            Line::from(vec![
+                label("packages"),
+                Span::raw(format!(
+                    "{} dependencies, {} dev",
+                    pubspec.dependencies.len(),
+                    pubspec.dev_dependencies.len()
+                )),
+            ]),
+            Line::from(vec![
+                label("platforms"),
+                Span::raw(project.platform_directories.join(" ")),
+            ]),
+            supported,
+        ];
+        if let Some(reason) = &project.stale_lock {
+            lines.push(Line::from(
+                format!("⚠ {}, press g to run pub get", reason.message()).yellow(),
+            ));
+        }
+        let text = Paragraph::new(lines)
             .style(Style::default().fg(default_color))
             .block(block);
         f.render_widget(text, area);
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use strum::Display;
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{
+        action::Action as StoreAction,
+        state::{RebuildState, State},
+        Store,
+    },
+    tui::Frame,
+    vm_service::{client::VmServiceEvents, rebuilds::RebuildStats},
+};
+use color_eyre::eyre::Result;
+
+use super::Component;
+
+// Rebuild events come every frame, so they are dispatched in batches.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(PartialEq, Clone, Copy, Display)]
+enum SortOrder {
+    #[strum(to_string = "total")]
+    Total,
+    #[strum(to_string = "last frame")]
+    LastFrame,
+    #[strum(to_string = "widget")]
+    Widget,
+    #[strum(to_string = "location")]
+    Location,
+}
+
+struct RebuildRow {
+    widget: String,
+    location: String,
+    last_frame: i64,
+    total: i64,
+}
+
+pub struct RebuildsComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    // The rebuild collector of each session tracking rebuilds.
+    trackers: Arc<std::sync::Mutex<HashMap<String, JoinHandle<()>>>>,
+    sort_order: SortOrder,
+    table_state: TableState,
+    row_count: usize,
+}
+
+impl RebuildsComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            trackers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            sort_order: SortOrder::Total,
+            table_state: TableState::default().with_selected(Some(0)),
+            row_count: 0,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn toggle_tracking(&mut self, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        let trackers = self.trackers.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let enabled = !state
+                .selected_session()
+                .is_some_and(|session| session.rebuilds.tracking);
+            // Subscribe first, as each location is only sent with the first event using it.
+            let events = selected.vm_service.events();
+            if let Err(e) = selected
+                .vm_service
+                .set_track_rebuild_dirty_widgets(&selected.isolate_id, enabled)
+                .await
+            {
+                log::error!("Failed to toggle rebuild tracking: {:?}", e);
+                return;
+            }
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetRebuildTracking {
+                    session_id: selected.session_id.clone(),
+                    enabled,
+                })
+                .await;
+            let session_id = selected.session_id.clone();
+            let mut trackers = trackers.lock().unwrap();
+            if let Some(tracker) = trackers.remove(&session_id) {
+                tracker.abort();
+            }
+            if enabled {
+                let tracker = tokio::spawn(Self::collect_rebuilds(selected, events, store));
+                trackers.insert(session_id, tracker);
+            }
+        });
+    }
+
+    async fn collect_rebuilds(
+        selected: SelectedIsolate,
+        mut events: VmServiceEvents,
+        store: Arc<Mutex<Store>>,
+    ) {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        let mut stats = vec![];
+        loop {
+            tokio::select! {
+                params = events.recv() => {
+                    let Some(params) = params else {
+                        break;
+                    };
+                    if let Some(frame) = RebuildStats::from_event(&params.event) {
+                        stats.push(frame);
+                    }
+                }
+                _ = interval.tick() => {
+                    if stats.is_empty() {
+                        continue;
+                    }
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::AddRebuildStats {
+                            session_id: selected.session_id.clone(),
+                            stats: std::mem::take(&mut stats),
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+
+    fn clear(&self, store: Arc<Mutex<Store>>) {
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session_id) = state.selected_session_id else {
+                return;
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::ClearRebuildCounts { session_id })
+                .await;
+        });
+    }
+
+    fn cycle_sort_order(&mut self) {
+        self.sort_order = match self.sort_order {
+            SortOrder::Total => SortOrder::LastFrame,
+            SortOrder::LastFrame => SortOrder::Widget,
+            SortOrder::Widget => SortOrder::Location,
+            SortOrder::Location => SortOrder::Total,
+        };
+        self.table_state.select(Some(0));
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.row_count.saturating_sub(1));
+        self.table_state.select(Some(index));
+    }
+
+    fn rows(&self, rebuilds: &RebuildState) -> Vec<RebuildRow> {
+        let mut rows = rebuilds
+            .totals
+            .iter()
+            .map(|(id, total)| {
+                let location = rebuilds.locations.get(id);
+                let file = location
+                    .map(|l| l.file.rsplit('/').next().unwrap_or(&l.file).to_string())
+                    .unwrap_or_default();
+                RebuildRow {
+                    widget: location
+                        .and_then(|l| l.name.clone())
+                        .unwrap_or_else(|| format!("#{}", id)),
+                    location: location
+                        .map(|l| format!("{}:{}:{}", file, l.line, l.column))
+                        .unwrap_or_default(),
+                    last_frame: rebuilds.last_frame.get(id).copied().unwrap_or_default(),
+                    total: *total,
+                }
+            })
+            .collect::<Vec<_>>();
+        match self.sort_order {
+            SortOrder::Total => rows.sort_by_key(|row| std::cmp::Reverse(row.total)),
+            SortOrder::LastFrame => rows.sort_by_key(|row| std::cmp::Reverse(row.last_frame)),
+            SortOrder::Widget => rows.sort_by(|a, b| a.widget.cmp(&b.widget)),
+            SortOrder::Location => rows.sort_by(|a, b| a.location.cmp(&b.location)),
+        }
+        rows
+    }
+}
+
+impl Component for RebuildsComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('t') => self.toggle_tracking(store),
+            KeyCode::Char('s') => self.cycle_sort_order(),
+            KeyCode::Char('c') => self.clear(store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let rebuilds = state.selected_session().map(|session| &session.rebuilds);
+        let mut title = vec![Span::raw("Rebuilds")];
+        if let Some(rebuilds) = rebuilds.filter(|rebuilds| rebuilds.tracking) {
+            title.push(Span::raw(" "));
+            title.push("● tracking".red().bold());
+            title.push(format!(" {} frames", rebuilds.frames).dark_gray());
+        }
+        title.push(format!(" sorted by {} (s)", self.sort_order).dark_gray());
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(rebuilds) = rebuilds.filter(|rebuilds| !rebuilds.totals.is_empty()) else {
+            self.row_count = 0;
+            let text = Paragraph::new(
+                " Press t to track widget rebuilds of the selected app, c to reset the counts ",
+            )
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let rows = self.rows(rebuilds);
+        self.row_count = rows.len();
+        if self.table_state.selected().unwrap_or(0) >= rows.len() {
+            self.table_state.select(Some(rows.len().saturating_sub(1)));
+        }
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                let style = if row.last_frame > 0 {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    Cell::from(row.widget),
+                    Cell::from(row.location).style(Style::default().fg(Color::DarkGray)),
+                    Cell::from(row.last_frame.to_string()).style(style),
+                    Cell::from(row.total.to_string()),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(30),
+                Constraint::Min(0),
+                Constraint::Length(10),
+                Constraint::Length(8),
+            ],
+        )
+        .header(
+            Row::new(vec!["Widget", "Location", "Last frame", "Total"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(block)
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+        Ok(())
+    }
+}
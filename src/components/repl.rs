@@ -0,0 +1,542 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::{SelectedIsolate, SessionManager},
+    store::{
+        action::Action as StoreAction,
+        state::{ReplEntry, ReplState, State},
+        Store,
+    },
+    tui::Frame,
+};
+use color_eyre::eyre::{eyre, Result};
+
+use super::{
+    text_input::{TextArea, TextInput},
+    Component,
+};
+
+#[derive(PartialEq)]
+enum Focus {
+    Transcript,
+    Expression,
+    Library,
+}
+
+struct ReplRow {
+    object_id: Option<String>,
+    line: Line<'static>,
+}
+
+pub struct ReplComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    focus: Focus,
+    input: TextArea,
+    library_input: TextInput,
+    history: Vec<String>,
+    // Position in `history` while browsing it with Up and Down.
+    history_index: Option<usize>,
+    expanded: HashSet<String>,
+    rows: Vec<ReplRow>,
+    entry_count: usize,
+    list_state: ListState,
+}
+
+impl ReplComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            focus: Focus::Transcript,
+            input: TextArea::default(),
+            library_input: TextInput::default(),
+            history: vec![],
+            history_index: None,
+            expanded: HashSet::new(),
+            rows: vec![],
+            entry_count: 0,
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn evaluate(&mut self, store: Arc<Mutex<Store>>) {
+        let expression = self.input.value();
+        if expression.trim().is_empty() {
+            return;
+        }
+        if self.history.last() != Some(&expression) {
+            self.history.push(expression.clone());
+        }
+        self.history_index = None;
+        self.input.clear();
+
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let library = state
+                .selected_session()
+                .and_then(|session| session.repl.library.clone());
+            let result = async {
+                let target_id = match library {
+                    Some(library) => library.id,
+                    None => Self::root_library_id(&selected).await?,
+                };
+                selected
+                    .vm_service
+                    .evaluate(&selected.isolate_id, &target_id, &expression)
+                    .await
+            }
+            .await
+            .map_err(|e| e.to_string());
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddReplEntry {
+                    session_id: selected.session_id,
+                    entry: ReplEntry { expression, result },
+                })
+                .await;
+        });
+    }
+
+    async fn root_library_id(selected: &SelectedIsolate) -> Result<String> {
+        let isolate = selected
+            .vm_service
+            .get_isolate(&selected.isolate_id)
+            .await?;
+        let library = isolate
+            .root_lib
+            .ok_or(eyre!("The isolate has no root library"))?;
+        Ok(library.id)
+    }
+
+    /// Evaluate in the library whose URI matches the query best, or the root library if empty.
+    fn choose_library(&self, query: String, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let library = if query.is_empty() {
+                None
+            } else {
+                let isolate = match selected.vm_service.get_isolate(&selected.isolate_id).await {
+                    Ok(isolate) => isolate,
+                    Err(e) => {
+                        log::error!("Failed to get libraries: {:?}", e);
+                        return;
+                    }
+                };
+                let library = isolate
+                    .libraries
+                    .iter()
+                    .find(|library| library.uri == query)
+                    .or_else(|| {
+                        isolate
+                            .libraries
+                            .iter()
+                            .find(|library| library.uri.contains(&query))
+                    });
+                match library {
+                    Some(library) => Some(library.clone()),
+                    None => {
+                        log::error!("No library matches {}", query);
+                        return;
+                    }
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetReplLibrary {
+                    session_id: selected.session_id,
+                    library,
+                })
+                .await;
+        });
+    }
+
+    fn clear(&mut self, store: Arc<Mutex<Store>>) {
+        self.expanded.clear();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            if let Some(session_id) = state.selected_session_id {
+                store
+                    .lock()
+                    .await
+                    .dispatch(StoreAction::ClearRepl { session_id })
+                    .await;
+            }
+        });
+    }
+
+    fn toggle_expanded(&mut self, store: Arc<Mutex<Store>>) {
+        let Some(object_id) = self
+            .list_state
+            .selected()
+            .and_then(|index| self.rows.get(index))
+            .and_then(|row| row.object_id.clone())
+        else {
+            return;
+        };
+        if self.expanded.remove(&object_id) {
+            return;
+        }
+        self.expanded.insert(object_id.clone());
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let instance = match selected
+                .vm_service
+                .get_instance(&selected.isolate_id, &object_id)
+                .await
+            {
+                Ok(instance) => instance,
+                Err(e) => {
+                    log::error!("Failed to get object {}: {:?}", object_id, e);
+                    return;
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetReplObjectChildren {
+                    session_id: selected.session_id,
+                    object_id,
+                    children: instance.children(),
+                })
+                .await;
+        });
+    }
+
+    fn browse_history(&mut self, older: bool) {
+        let index = match (self.history_index, older) {
+            (None, true) => self.history.len().checked_sub(1),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < self.history.len() => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        self.history_index = index;
+        match index {
+            Some(index) => self.input.set_value(&self.history[index]),
+            None => self.input.clear(),
+        }
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.list_state.select(Some(index));
+    }
+
+    fn handle_expression_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => {
+                self.focus = Focus::Transcript;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter
+                if key.modifiers.contains(KeyModifiers::ALT)
+                    || !is_complete(&self.input.value()) =>
+            {
+                self.input.insert_newline()
+            }
+            KeyCode::Enter => self.evaluate(store),
+            KeyCode::Up | KeyCode::Down if !self.input.handle_key(&key) => {
+                self.browse_history(key.code == KeyCode::Up)
+            }
+            _ => {
+                self.input.handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn handle_library_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Esc => {
+                self.focus = Focus::Transcript;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter => {
+                self.focus = Focus::Transcript;
+                self.choose_library(self.library_input.value().trim().to_string(), store);
+                return Ok(Some(Action::ExitInputMode));
+            }
+            _ => {
+                self.library_input.handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn push_children(
+        &self,
+        repl: &ReplState,
+        object_id: &str,
+        depth: usize,
+        ancestors: &mut Vec<String>,
+        rows: &mut Vec<ReplRow>,
+    ) {
+        let Some(children) = repl.objects.get(object_id) else {
+            return;
+        };
+        ancestors.push(object_id.to_string());
+        for (name, value) in children {
+            let indent = "  ".repeat(depth);
+            let child_id = value.id.clone().filter(|_| value.has_children());
+            let is_expanded = child_id
+                .as_ref()
+                .is_some_and(|id| self.expanded.contains(id));
+            let marker = match (&child_id, is_expanded) {
+                (None, _) => "  ",
+                (Some(_), true) => "▾ ",
+                (Some(_), false) => "▸ ",
+            };
+            rows.push(ReplRow {
+                object_id: child_id.clone(),
+                line: Line::from(vec![
+                    Span::raw(format!("{}{}", indent, marker)),
+                    Span::styled(format!("{}: ", name), Style::default().fg(Color::Cyan)),
+                    Span::raw(value.description()),
+                ]),
+            });
+            // Objects can reference themselves, so stop at the ones already shown above.
+            if let Some(child_id) = child_id.filter(|id| is_expanded && !ancestors.contains(id)) {
+                self.push_children(repl, &child_id, depth + 1, ancestors, rows);
+            }
+        }
+        ancestors.pop();
+    }
+
+    fn transcript(&self, repl: &ReplState) -> Vec<ReplRow> {
+        let mut rows = vec![];
+        for entry in &repl.entries {
+            for (index, line) in entry.expression.lines().enumerate() {
+                let prompt = if index == 0 { "› " } else { "  " };
+                rows.push(ReplRow {
+                    object_id: None,
+                    line: Line::from(format!("{}{}", prompt, line).dark_gray()),
+                });
+            }
+            match &entry.result {
+                Ok(value) if value.is_error() => rows.push(ReplRow {
+                    object_id: None,
+                    line: Line::from(value.description().red()),
+                }),
+                Ok(value) => {
+                    let object_id = value.id.clone().filter(|_| value.has_children());
+                    let is_expanded = object_id
+                        .as_ref()
+                        .is_some_and(|id| self.expanded.contains(id));
+                    let marker = match (&object_id, is_expanded) {
+                        (None, _) => "",
+                        (Some(_), true) => "▾ ",
+                        (Some(_), false) => "▸ ",
+                    };
+                    rows.push(ReplRow {
+                        object_id: object_id.clone(),
+                        line: Line::from(format!("{}{}", marker, value.description())),
+                    });
+                    if let Some(object_id) = object_id.filter(|_| is_expanded) {
+                        self.push_children(repl, &object_id, 1, &mut vec![], &mut rows);
+                    }
+                }
+                Err(e) => rows.push(ReplRow {
+                    object_id: None,
+                    line: Line::from(e.clone().red()),
+                }),
+            }
+        }
+        rows
+    }
+
+    fn draw_input(&self, f: &mut Frame<'_>, area: Rect) {
+        let block = Block::default().borders(Borders::TOP);
+        let inner = block.inner(area);
+        let (lines, cursor) = match self.focus {
+            Focus::Library => {
+                let prompt = "Library URI (empty for the root library): ";
+                (
+                    vec![Line::from(format!(
+                        "{}{}",
+                        prompt,
+                        self.library_input.value()
+                    ))],
+                    Some((prompt.len() + self.library_input.cursor(), 0)),
+                )
+            }
+            Focus::Transcript if self.input.value().is_empty() => (
+                vec![Line::from(
+                    "i: edit expression  l: choose library  c: clear  Enter: expand".dark_gray(),
+                )],
+                None,
+            ),
+            Focus::Transcript | Focus::Expression => {
+                let lines = self
+                    .input
+                    .lines()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, line)| {
+                        let prompt = if index == 0 { "› " } else { "  " };
+                        Line::from(format!("{}{}", prompt, line.value()))
+                    })
+                    .collect();
+                let (column, row) = self.input.cursor();
+                let cursor = (self.focus == Focus::Expression).then_some((column + 2, row));
+                (lines, cursor)
+            }
+        };
+        f.render_widget(Paragraph::new(lines).block(block), area);
+        if let Some((x, y)) = cursor {
+            f.set_cursor(inner.x + x as u16, inner.y + y as u16);
+        }
+    }
+}
+
+/// Whether every bracket of the expression is closed, ignoring those in string literals.
+fn is_complete(expression: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut chars = expression.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, _) => {}
+        }
+    }
+    depth <= 0 && quote.is_none()
+}
+
+impl Component for ReplComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match self.focus {
+            Focus::Expression => return self.handle_expression_key_events(key, store),
+            Focus::Library => return self.handle_library_key_events(key, store),
+            Focus::Transcript => {}
+        }
+        match key.code {
+            KeyCode::Char('i') => {
+                self.focus = Focus::Expression;
+                return Ok(Some(Action::EnterInputMode));
+            }
+            KeyCode::Char('l') => {
+                self.focus = Focus::Library;
+                return Ok(Some(Action::EnterInputMode));
+            }
+            KeyCode::Char('c') => self.clear(store),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_expanded(store),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let repl = state.selected_session().map(|session| &session.repl);
+        let library = repl
+            .and_then(|repl| repl.library.as_ref())
+            .map(|library| library.uri.as_str())
+            .unwrap_or("root library");
+        let block = Block::default()
+            .title(format!("REPL ({})", library))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(repl) = repl else {
+            self.rows = vec![];
+            let text = Paragraph::new(" Select a running app to evaluate expressions ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        self.rows = self.transcript(repl);
+        // Follow new results.
+        if repl.entries.len() != self.entry_count {
+            self.entry_count = repl.entries.len();
+            self.list_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let input_height = self.input.lines().len() as u16 + 1;
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(input_height)])
+            .split(inner);
+
+        let items = self
+            .rows
+            .iter()
+            .map(|row| ListItem::new(row.line.clone()))
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, layout[0], &mut self.list_state);
+        self.draw_input(f, layout[1]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_expressions() {
+        assert!(is_complete("items.map((i) => i * 2).toList()"));
+        assert!(!is_complete("items.where((i) {"));
+        assert!(is_complete("'(' + \"{\""));
+        assert!(!is_complete("'unterminated"));
+    }
+}
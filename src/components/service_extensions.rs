@@ -0,0 +1,365 @@
+use std::sync::Arc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    favorites::{self, Favorites},
+    session::session_manager::SessionManager,
+    store::{action::Action as StoreAction, state::ServiceExtensionCall, state::State, Store},
+    tui::Frame,
+};
+use color_eyre::eyre::Result;
+
+use super::{text_input::TextInput, Component};
+
+/// Key/value params of the extension about to be invoked.
+struct ParamsForm {
+    method: String,
+    fields: Vec<(TextInput, TextInput)>,
+    // Index into the key and value inputs of all fields, in order.
+    focus: usize,
+}
+
+impl ParamsForm {
+    fn new(method: String) -> Self {
+        Self {
+            method,
+            fields: vec![(TextInput::default(), TextInput::default())],
+            focus: 0,
+        }
+    }
+
+    fn focused_input(&mut self) -> &mut TextInput {
+        let (key, value) = &mut self.fields[self.focus / 2];
+        match self.focus % 2 {
+            0 => key,
+            _ => value,
+        }
+    }
+
+    fn focus_next(&mut self) {
+        self.focus += 1;
+        if self.focus == self.fields.len() * 2 {
+            self.fields
+                .push((TextInput::default(), TextInput::default()));
+        }
+    }
+
+    fn focus_previous(&mut self) {
+        self.focus = self.focus.saturating_sub(1);
+    }
+
+    fn params(&self) -> Map<String, Value> {
+        self.fields
+            .iter()
+            .filter(|(key, _)| !key.value().is_empty())
+            .map(|(key, value)| (key.value().to_string(), Value::from(value.value())))
+            .collect()
+    }
+}
+
+pub struct ServiceExtensionsComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    project: String,
+    favorites: Favorites,
+    is_selected: bool,
+    // Extensions in the order they were last drawn, favorites first.
+    extensions: Vec<String>,
+    form: Option<ParamsForm>,
+    list_state: ListState,
+}
+
+impl ServiceExtensionsComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>, project_root: Option<&str>) -> Self {
+        Self {
+            session_manager,
+            project: favorites::project_key(project_root),
+            favorites: Favorites::load_default(),
+            is_selected: false,
+            extensions: vec![],
+            form: None,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn selected_extension(&self) -> Option<&String> {
+        self.extensions.get(self.list_state.selected()?)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        if self.extensions.is_empty() {
+            return;
+        }
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.extensions.len() - 1);
+        self.list_state.select(Some(index));
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(extension) = self.selected_extension().cloned() else {
+            return;
+        };
+        if let Err(e) = self.favorites.toggle(&self.project, &extension) {
+            log::error!("Failed to save favorite extensions: {:?}", e);
+        }
+    }
+
+    fn invoke(&mut self, form: ParamsForm, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(session_id) = state.selected_session_id else {
+                return;
+            };
+            let Some(session) = session_manager.lock().await.session(&session_id) else {
+                return;
+            };
+            let result = session
+                .run
+                .call_service_extension(&form.method, Some(form.params()))
+                .await
+                .map_err(|e| e.to_string());
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetServiceExtensionCall {
+                    session_id,
+                    call: ServiceExtensionCall {
+                        method: form.method,
+                        result,
+                    },
+                })
+                .await;
+        });
+    }
+
+    fn handle_form_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        let Some(form) = &mut self.form else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.form = None;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter => {
+                if let Some(form) = self.form.take() {
+                    self.invoke(form, store);
+                }
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Tab => form.focus_next(),
+            KeyCode::BackTab => form.focus_previous(),
+            _ => {
+                form.focused_input().handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw_form(&self, f: &mut Frame<'_>, area: Rect, form: &ParamsForm) {
+        let mut lines = vec![
+            Line::from(form.method.clone().bold()),
+            Line::from(" Tab: next field  Enter: invoke  Esc: cancel ".dark_gray()),
+        ];
+        let mut cursor = None;
+        for (index, (key, value)) in form.fields.iter().enumerate() {
+            let style = |focus: usize| {
+                if form.focus == focus {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default().add_modifier(Modifier::UNDERLINED)
+                }
+            };
+            let key_text = format!("{:<16}", key.value());
+            if form.focus == index * 2 {
+                cursor = Some((key.cursor(), lines.len()));
+            } else if form.focus == index * 2 + 1 {
+                cursor = Some((key_text.chars().count() + 3 + value.cursor(), lines.len()));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(key_text, style(index * 2)),
+                Span::raw(" = "),
+                Span::styled(value.value().to_string(), style(index * 2 + 1)),
+            ]));
+        }
+        let block = Block::default().borders(Borders::BOTTOM);
+        let inner = block.inner(area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+        if let Some((x, y)) = cursor {
+            f.set_cursor(inner.x + x as u16, inner.y + y as u16);
+        }
+    }
+}
+
+fn favorites_first(extensions: &[String], favorites: &Favorites, project: &str) -> Vec<String> {
+    let (mut sorted, others): (Vec<_>, Vec<_>) = extensions
+        .iter()
+        .cloned()
+        .partition(|extension| favorites.contains(project, extension));
+    sorted.extend(others);
+    sorted
+}
+
+/// The index of the extension selected in `previous`, if it is still listed.
+fn moved_selection(
+    previous: &[String],
+    selected: Option<usize>,
+    extensions: &[String],
+) -> Option<usize> {
+    let extension = previous.get(selected?)?;
+    extensions.iter().position(|e| e == extension)
+}
+
+impl Component for ServiceExtensionsComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        if self.form.is_some() {
+            return self.handle_form_key_events(key, store);
+        }
+        match key.code {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('f') => self.toggle_favorite(),
+            KeyCode::Enter => {
+                if let Some(extension) = self.selected_extension() {
+                    self.form = Some(ParamsForm::new(extension.clone()));
+                    return Ok(Some(Action::EnterInputMode));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("Service extensions")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(session) = state.selected_session() else {
+            let text = Paragraph::new(" Select a running app to list its service extensions ")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+
+        let extensions =
+            favorites_first(&session.service_extensions, &self.favorites, &self.project);
+        // Follow the selected extension when toggling a favorite moves it.
+        if let Some(index) =
+            moved_selection(&self.extensions, self.list_state.selected(), &extensions)
+        {
+            self.list_state.select(Some(index));
+        }
+        self.extensions = extensions;
+        if self.list_state.selected().unwrap_or(0) >= self.extensions.len() {
+            self.list_state
+                .select(Some(self.extensions.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(inner);
+
+        let items = self
+            .extensions
+            .iter()
+            .map(|extension| {
+                let marker = if self.favorites.contains(&self.project, extension) {
+                    "★ "
+                } else {
+                    "  "
+                };
+                ListItem::new(format!("{}{}", marker, extension))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, layout[0], &mut self.list_state);
+
+        let details = Block::default().borders(Borders::LEFT);
+        let details_area = details.inner(layout[1]);
+        f.render_widget(details, layout[1]);
+        let form_height = self
+            .form
+            .as_ref()
+            .map(|form| form.fields.len() as u16 + 3)
+            .unwrap_or(0);
+        let details_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(form_height), Constraint::Min(0)])
+            .split(details_area);
+        if let Some(form) = &self.form {
+            self.draw_form(f, details_layout[0], form);
+        }
+
+        if let Some(call) = &session.service_extension_call {
+            let mut lines = vec![Line::from(call.method.clone().bold())];
+            match &call.result {
+                Ok(result) => {
+                    let result = serde_json::to_string_pretty(result).unwrap_or_default();
+                    lines.extend(result.lines().map(|line| Line::from(line.to_string())));
+                }
+                Err(e) => lines.push(Line::from(e.clone().red())),
+            }
+            let result = Paragraph::new(lines).wrap(Wrap { trim: false });
+            f.render_widget(result, details_layout[1]);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_follows_toggled_favorite() {
+        let path = std::env::temp_dir().join(format!(
+            "lazyflutter-extensions-{}.json",
+            std::process::id()
+        ));
+        let mut favorites = Favorites::load(&path);
+        let listed = ["ext.app.a", "ext.app.b", "ext.app.c"].map(String::from);
+        let before = favorites_first(&listed, &favorites, "/app");
+
+        favorites.toggle("/app", "ext.app.c").unwrap();
+        let after = favorites_first(&listed, &favorites, "/app");
+        assert_eq!(after, ["ext.app.c", "ext.app.a", "ext.app.b"]);
+        assert_eq!(moved_selection(&before, Some(2), &after), Some(0));
+
+        favorites.toggle("/app", "ext.app.c").unwrap();
+        let reverted = favorites_first(&listed, &favorites, "/app");
+        assert_eq!(moved_selection(&after, Some(0), &reverted), Some(2));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,702 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        Mutex,
+    },
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    action::Action,
+    pubspec::{self, Pubspec},
+    store::{action::Action as StoreAction, state::State, Store},
+    test_runner::{self, watch, TestCase, TestRun, TestStatus, TestTarget},
+    tui::Frame,
+};
+use color_eyre::eyre::{eyre, Result};
+
+use super::Component;
+
+const KEY_HINTS: &str = "t: run all  Enter: run selected  f: run failures  w: watch  \
+                         o: open in editor  x: stop  PageUp/PageDown: scroll details";
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+// Large suites report thousands of events, so they are dispatched in batches.
+const EVENT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run tests until they finish or are cancelled, dispatching the report as it comes.
+pub async fn run_tests(
+    project_root: &Path,
+    target: &TestTarget,
+    store: &Arc<Mutex<Store>>,
+    cancellation_token: &CancellationToken,
+) -> Result<()> {
+    store
+        .lock()
+        .await
+        .dispatch(StoreAction::StartTestRun {
+            command_line: target.command_line(),
+        })
+        .await;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let forward = async {
+        let mut interval = tokio::time::interval(EVENT_FLUSH_INTERVAL);
+        let mut events = vec![];
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+                    events.push(event);
+                }
+                _ = interval.tick() => {
+                    if events.is_empty() {
+                        continue;
+                    }
+                    store
+                        .lock()
+                        .await
+                        .dispatch(StoreAction::AddTestEvents {
+                            events: std::mem::take(&mut events),
+                        })
+                        .await;
+                }
+            }
+        }
+        if !events.is_empty() {
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::AddTestEvents { events })
+                .await;
+        }
+    };
+    let program = test_runner::flutter_executable();
+    // Dropping the child process kills it.
+    let result = tokio::select! {
+        (result, _) = async {
+            tokio::join!(test_runner::run(&program, project_root, target, tx), forward)
+        } => result,
+        _ = cancellation_token.cancelled() => Err(eyre!("Stopped")),
+    };
+    store
+        .lock()
+        .await
+        .dispatch(StoreAction::FinishTestRun {
+            error: result.as_ref().err().map(|e| e.to_string()),
+        })
+        .await;
+    result
+}
+
+/// Rerun the tests affected by changes under `lib/` and `test/` until cancelled.
+async fn watch_tests(
+    project_root: PathBuf,
+    store: Arc<Mutex<Store>>,
+    runner: Runner,
+    cancellation_token: CancellationToken,
+) {
+    let package = Pubspec::load(&project_root)
+        .map(|pubspec| pubspec.name)
+        .unwrap_or_default();
+    let root = project_root.clone();
+    let mut last_snapshot = tokio::task::spawn_blocking(move || watch::snapshot(&root))
+        .await
+        .unwrap_or_default();
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let root = project_root.clone();
+        let package = package.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let snapshot = watch::snapshot(&root);
+            let changed = watch::changed_files(&last_snapshot, &snapshot);
+            if changed.is_empty() {
+                return (snapshot, vec![]);
+            }
+            let graph = watch::import_graph(&root, &package, snapshot.keys().cloned());
+            let tests = watch::affected_tests(&graph, &changed);
+            (snapshot, tests)
+        })
+        .await;
+        let Ok((snapshot, tests)) = result else {
+            break;
+        };
+        last_snapshot = snapshot;
+        if tests.is_empty() {
+            continue;
+        }
+        let files = tests
+            .iter()
+            .map(|test| {
+                test.strip_prefix(&project_root)
+                    .unwrap_or(test)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        runner
+            .run(
+                &project_root,
+                &TestTarget::Files(files),
+                &store,
+                cancellation_token.child_token(),
+            )
+            .await;
+    }
+}
+
+/// Runs tests one at a time, so watched and manual runs take turns.
+#[derive(Clone, Default)]
+struct Runner {
+    lock: Arc<Mutex<()>>,
+    // The runs in progress or waiting for their turn.
+    runs: Arc<std::sync::Mutex<Vec<CancellationToken>>>,
+}
+
+impl Runner {
+    async fn run(
+        &self,
+        project_root: &Path,
+        target: &TestTarget,
+        store: &Arc<Mutex<Store>>,
+        cancellation_token: CancellationToken,
+    ) {
+        {
+            let mut runs = self.runs.lock().unwrap();
+            runs.retain(|run| !run.is_cancelled());
+            runs.push(cancellation_token.clone());
+        }
+        // Finished runs are cancelled too, to be dropped from `runs`.
+        let _finished = cancellation_token.clone().drop_guard();
+        let _running = tokio::select! {
+            running = self.lock.lock() => running,
+            _ = cancellation_token.cancelled() => return,
+        };
+        let _ = run_tests(project_root, target, store, &cancellation_token).await;
+    }
+
+    fn stop(&self) {
+        for run in self.runs.lock().unwrap().drain(..) {
+            run.cancel();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TestRow {
+    Suite {
+        id: u64,
+    },
+    Group {
+        id: u64,
+        suite_id: u64,
+        depth: usize,
+    },
+    Test {
+        id: u64,
+        depth: usize,
+    },
+}
+
+pub struct TestRunnerComponent {
+    project_root: PathBuf,
+    is_selected: bool,
+    task: Option<JoinHandle<()>>,
+    watch: Option<CancellationToken>,
+    runner: Runner,
+    action_tx: Option<UnboundedSender<Action>>,
+    // As of the last draw.
+    rows: Vec<TestRow>,
+    table_state: TableState,
+    details_scroll: u16,
+}
+
+impl TestRunnerComponent {
+    pub fn new(project_root: Option<&str>) -> Self {
+        Self {
+            project_root: pubspec::project_root(project_root),
+            is_selected: false,
+            task: None,
+            watch: None,
+            runner: Runner::default(),
+            action_tx: None,
+            rows: vec![],
+            table_state: TableState::default().with_selected(Some(0)),
+            details_scroll: 0,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    /// Run the tests `target` picks from the latest run.
+    fn run(
+        &mut self,
+        target: impl FnOnce(Option<&TestRun>) -> Option<TestTarget> + Send + 'static,
+        store: Arc<Mutex<Store>>,
+    ) {
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        let project_root = self.project_root.clone();
+        let runner = self.runner.clone();
+        self.task = Some(tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(target) = target(state.tests.run.as_ref()) else {
+                return;
+            };
+            runner
+                .run(&project_root, &target, &store, CancellationToken::new())
+                .await;
+        }));
+    }
+
+    fn toggle_watch(&mut self, store: Arc<Mutex<Store>>) {
+        let is_watching = match self.watch.take() {
+            // Stops the watched run in progress too.
+            Some(cancellation_token) => {
+                cancellation_token.cancel();
+                false
+            }
+            None => {
+                let cancellation_token = CancellationToken::new();
+                tokio::spawn(watch_tests(
+                    self.project_root.clone(),
+                    store.clone(),
+                    self.runner.clone(),
+                    cancellation_token.clone(),
+                ));
+                self.watch = Some(cancellation_token);
+                true
+            }
+        };
+        tokio::spawn(async move {
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetTestWatch { is_watching })
+                .await;
+        });
+    }
+
+    fn selected_row(&self) -> Option<&TestRow> {
+        self.rows.get(self.table_state.selected()?)
+    }
+
+    fn open_in_editor(&self, store: Arc<Mutex<Store>>) {
+        let (Some(row), Some(action_tx)) = (self.selected_row().cloned(), self.action_tx.clone())
+        else {
+            return;
+        };
+        let project_root = self.project_root.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let action = state
+                .tests
+                .run
+                .as_ref()
+                .and_then(|run| location(run, &row, &project_root));
+            if let Some(action) = action {
+                let _ = action_tx.send(action);
+            }
+        });
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.table_state.select(Some(index));
+        self.details_scroll = 0;
+    }
+}
+
+fn selected_target(run: &TestRun, row: &TestRow) -> Option<TestTarget> {
+    let target = match row {
+        TestRow::Suite { id } => TestTarget::Files(vec![run.suites.get(id)?.clone()]),
+        TestRow::Group { id, suite_id, .. } => TestTarget::Groups {
+            files: vec![run.suites.get(suite_id)?.clone()],
+            names: vec![run.groups.get(id)?.name.clone()],
+        },
+        TestRow::Test { id, .. } => {
+            let test = run.tests.get(id)?;
+            let file = run.suites.get(&test.info.suite_id)?.clone();
+            if test.hidden {
+                TestTarget::Files(vec![file])
+            } else {
+                TestTarget::Tests {
+                    files: vec![file],
+                    names: vec![test.info.name.clone()],
+                }
+            }
+        }
+    };
+    Some(target)
+}
+
+fn location(run: &TestRun, row: &TestRow, project_root: &Path) -> Option<Action> {
+    let (suite_id, line, column) = match row {
+        TestRow::Suite { id } => (*id, 1, 1),
+        TestRow::Group { suite_id, .. } => (*suite_id, 1, 1),
+        TestRow::Test { id, .. } => {
+            let info = &run.tests.get(id)?.info;
+            // `line` is in the helper defining the test when `root_line` is set.
+            let line = info.root_line.or(info.line).unwrap_or(1);
+            let column = info.root_column.or(info.column).unwrap_or(1);
+            (info.suite_id, line, column)
+        }
+    };
+    let path = project_root.join(run.suites.get(&suite_id)?);
+    Some(Action::OpenInEditor {
+        path: path.display().to_string(),
+        line,
+        column,
+    })
+}
+
+/// Errors and output of the selected test.
+fn details(run: &TestRun, row: Option<&TestRow>) -> Vec<Line<'static>> {
+    let Some(TestRow::Test { id, .. }) = row else {
+        return vec![];
+    };
+    let Some(test) = run.tests.get(id) else {
+        return vec![];
+    };
+    let mut lines = vec![];
+    for error in &test.errors {
+        lines.extend(
+            error
+                .message
+                .lines()
+                .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::Red))),
+        );
+        lines.extend(
+            error
+                .stack_trace
+                .lines()
+                .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::DarkGray))),
+        );
+    }
+    lines.extend(test.output.iter().map(|line| Line::from(line.clone())));
+    lines
+}
+
+fn rows(run: &TestRun) -> Vec<TestRow> {
+    let mut suites = run.suites.iter().collect::<Vec<_>>();
+    suites.sort_by_key(|(_, path)| path.as_str());
+    let mut rows = vec![];
+    for (suite_id, _) in suites {
+        rows.push(TestRow::Suite { id: *suite_id });
+        let mut shown_groups = BTreeSet::new();
+        for test in run
+            .visible_tests()
+            .filter(|test| test.info.suite_id == *suite_id)
+        {
+            // The root group of each suite has no name.
+            let groups = test
+                .info
+                .group_ids
+                .iter()
+                .filter(|id| run.groups.get(id).is_some_and(|g| !g.name.is_empty()))
+                .collect::<Vec<_>>();
+            for (depth, id) in groups.iter().enumerate() {
+                if shown_groups.insert(**id) {
+                    rows.push(TestRow::Group {
+                        id: **id,
+                        suite_id: *suite_id,
+                        depth: depth + 1,
+                    });
+                }
+            }
+            rows.push(TestRow::Test {
+                id: test.info.id,
+                depth: groups.len() + 1,
+            });
+        }
+    }
+    rows
+}
+
+/// The status of a suite or group, from the status of its tests.
+fn combined_status<'a>(tests: impl Iterator<Item = &'a TestCase>) -> Option<TestStatus> {
+    let statuses = tests.map(|test| test.status).collect::<Vec<_>>();
+    [TestStatus::Failed, TestStatus::Running, TestStatus::Passed]
+        .into_iter()
+        .find(|status| statuses.contains(status))
+        .or(statuses.first().copied())
+}
+
+fn status_symbol(status: Option<TestStatus>) -> Span<'static> {
+    match status {
+        Some(TestStatus::Running) => Span::styled("●", Style::default().fg(Color::Cyan)),
+        Some(TestStatus::Passed) => Span::styled("✓", Style::default().fg(Color::Green)),
+        Some(TestStatus::Failed) => Span::styled("✗", Style::default().fg(Color::Red)),
+        Some(TestStatus::Skipped) => Span::styled("-", Style::default().fg(Color::Yellow)),
+        None => Span::raw(" "),
+    }
+}
+
+/// Counts of the latest run for the status bar.
+pub fn status(state: &State) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    if state.tests.is_watching {
+        spans.push(Span::styled("watching ", Style::default().fg(Color::Cyan)));
+    }
+    let Some(run) = &state.tests.run else {
+        return spans;
+    };
+    spans.extend([
+        Span::styled(
+            format!("{} passed ", run.count(TestStatus::Passed)),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(
+            format!("{} failed ", run.count(TestStatus::Failed)),
+            Style::default().fg(if run.count(TestStatus::Failed) > 0 {
+                Color::Red
+            } else {
+                Color::DarkGray
+            }),
+        ),
+        Span::styled(
+            format!("{} skipped ", run.count(TestStatus::Skipped)),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    if !run.is_finished {
+        spans.push(Span::styled(
+            "running... ",
+            Style::default().fg(Color::Cyan),
+        ));
+    } else if let Some(error) = &state.tests.error {
+        spans.push(Span::styled(
+            format!("{} ", error.lines().next().unwrap_or_default()),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    spans
+}
+
+impl Component for TestRunnerComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('t') => self.run(|_| Some(TestTarget::All), store),
+            KeyCode::Enter => {
+                if let Some(row) = self.selected_row().cloned() {
+                    self.run(move |run| selected_target(run?, &row), store);
+                }
+            }
+            KeyCode::Char('f') => self.run(|run| run?.failures(), store),
+            KeyCode::Char('w') => self.toggle_watch(store),
+            KeyCode::Char('o') => self.open_in_editor(store),
+            KeyCode::Char('x') => self.runner.stop(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.details_scroll = self.details_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.details_scroll = self.details_scroll.saturating_add(10),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let block = Block::default()
+            .title("Tests")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let Some(run) = &state.tests.run else {
+            let text = if state.tests.is_watching {
+                " Watching lib/ and test/ for changes "
+            } else {
+                " Press t to run flutter test, or w to run tests as files change "
+            };
+            let text = Paragraph::new(text)
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+        self.rows = rows(run);
+        if self.table_state.selected().unwrap_or(0) >= self.rows.len() {
+            self.table_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Percentage(60),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let mut summary = vec![Span::styled(
+            format!("$ {}  ", run.command_line),
+            Style::default().fg(Color::DarkGray),
+        )];
+        summary.extend(status(state));
+        if let Some(duration) = run.duration {
+            summary.push(Span::styled(
+                format!("in {:.1}s", duration as f64 / 1000.0),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        f.render_widget(Paragraph::new(Line::from(summary)), layout[0]);
+
+        let project_root = self.project_root.display().to_string();
+        let table_rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let (depth, status, name, duration) = match row {
+                    TestRow::Suite { id } => {
+                        let path = run.suites.get(id).cloned().unwrap_or_default();
+                        let path = path
+                            .strip_prefix(&project_root)
+                            .map(|path| path.trim_start_matches('/').to_string())
+                            .unwrap_or(path);
+                        let tests = run.visible_tests().filter(|test| test.info.suite_id == *id);
+                        (0, combined_status(tests), path, String::new())
+                    }
+                    TestRow::Group { id, depth, .. } => {
+                        let tests = run
+                            .visible_tests()
+                            .filter(|test| test.info.group_ids.contains(id));
+                        let group = run.groups.get(id);
+                        let name = group.map(|g| g.name.clone()).unwrap_or_default();
+                        let parent = group
+                            .and_then(|g| g.parent_id)
+                            .and_then(|parent| run.groups.get(&parent))
+                            .filter(|parent| !parent.name.is_empty())
+                            .map(|parent| format!("{} ", parent.name));
+                        let name = match parent {
+                            Some(parent) => name.strip_prefix(&parent).unwrap_or(&name).to_string(),
+                            None => name,
+                        };
+                        (*depth, combined_status(tests), name, String::new())
+                    }
+                    TestRow::Test { id, depth } => {
+                        let Some(test) = run.tests.get(id) else {
+                            return Row::new(Vec::<Cell>::new());
+                        };
+                        let group = test
+                            .info
+                            .group_ids
+                            .last()
+                            .and_then(|id| run.groups.get(id))
+                            .filter(|group| !group.name.is_empty())
+                            .map(|group| format!("{} ", group.name));
+                        let name = match group {
+                            Some(group) => test
+                                .info
+                                .name
+                                .strip_prefix(&group)
+                                .unwrap_or(&test.info.name)
+                                .to_string(),
+                            None => test.info.name.clone(),
+                        };
+                        let duration = test
+                            .duration
+                            .map(|duration| format!("{}ms", duration))
+                            .unwrap_or_default();
+                        (*depth, Some(test.status), name, duration)
+                    }
+                };
+                Row::new(vec![
+                    Cell::from(Line::from(vec![
+                        Span::raw("  ".repeat(depth)),
+                        status_symbol(status),
+                        Span::raw(format!(" {}", name)),
+                    ])),
+                    Cell::from(duration).style(Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let table = Table::new(table_rows, [Constraint::Min(0), Constraint::Length(10)])
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, layout[1], &mut self.table_state);
+
+        let details = Paragraph::new(details(run, self.selected_row()))
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            )
+            .scroll((self.details_scroll, 0));
+        f.render_widget(details, layout[2]);
+        f.render_widget(
+            Paragraph::new(KEY_HINTS).style(Style::default().fg(Color::DarkGray)),
+            layout[3],
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::parse_event;
+
+    #[test]
+    fn rows_nest_tests_in_groups() {
+        let mut run = TestRun::default();
+        let events = r#"{"suite":{"id":0,"path":"test/a_test.dart"},"type":"suite","time":0}
+{"group":{"id":1,"suiteID":0,"parentID":null,"name":""},"type":"group","time":0}
+{"group":{"id":2,"suiteID":0,"parentID":1,"name":"math"},"type":"group","time":0}
+{"test":{"id":3,"name":"math adds","suiteID":0,"groupIDs":[1,2]},"type":"testStart","time":0}
+{"test":{"id":4,"name":"parses","suiteID":0,"groupIDs":[1]},"type":"testStart","time":0}"#;
+        for event in events.lines().filter_map(parse_event) {
+            run.apply(event);
+        }
+        assert_eq!(
+            rows(&run),
+            vec![
+                TestRow::Suite { id: 0 },
+                TestRow::Group {
+                    id: 2,
+                    suite_id: 0,
+                    depth: 1
+                },
+                TestRow::Test { id: 3, depth: 2 },
+                TestRow::Test { id: 4, depth: 1 },
+            ]
+        );
+    }
+}
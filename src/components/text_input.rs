@@ -0,0 +1,200 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A single-line text field edited with key events.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    // Cursor position counted in chars.
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            cursor: value.chars().count(),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn set_value(&mut self, value: &str) {
+        *self = Self::new(value);
+    }
+
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.value.chars().count());
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Apply an editing key. Returns `false` if the key is not an editing key.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.value.insert(self.byte_index(), c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.value.remove(self.byte_index());
+            }
+            KeyCode::Delete if self.cursor < self.value.chars().count() => {
+                self.value.remove(self.byte_index());
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.value.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.chars().count(),
+            KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map(|(index, _)| index)
+            .unwrap_or(self.value.len())
+    }
+}
+
+/// A multi-line text field made of one `TextInput` per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextArea {
+    lines: Vec<TextInput>,
+    row: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            lines: vec![TextInput::default()],
+            row: 0,
+        }
+    }
+}
+
+impl TextArea {
+    pub fn value(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.value())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replace the text, moving the cursor to its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.lines = value.split('\n').map(TextInput::new).collect();
+        self.row = self.lines.len() - 1;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn lines(&self) -> &[TextInput] {
+        &self.lines
+    }
+
+    /// The cursor position as (column, row).
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.lines[self.row].cursor(), self.row)
+    }
+
+    /// Split the current line at the cursor.
+    pub fn insert_newline(&mut self) {
+        let line = &self.lines[self.row];
+        let (before, after) = line.value().split_at(line.byte_index());
+        let mut next = TextInput::new(after);
+        next.set_cursor(0);
+        self.lines[self.row] = TextInput::new(before);
+        self.row += 1;
+        self.lines.insert(self.row, next);
+    }
+
+    /// Apply an editing key. Returns `false` if the key is not an editing key, or is
+    /// Up on the first line or Down on the last line.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let column = self.lines[self.row].cursor();
+        match key.code {
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.lines[self.row].set_cursor(column);
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.lines[self.row].set_cursor(column);
+            }
+            KeyCode::Backspace if column == 0 && self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                let previous = &mut self.lines[self.row];
+                let cursor = previous.value().chars().count();
+                *previous = TextInput::new(&format!("{}{}", previous.value(), line.value()));
+                previous.set_cursor(cursor);
+            }
+            KeyCode::Delete
+                if column == self.lines[self.row].value().chars().count()
+                    && self.row + 1 < self.lines.len() =>
+            {
+                let next = self.lines.remove(self.row + 1);
+                let line = &mut self.lines[self.row];
+                *line = TextInput::new(&format!("{}{}", line.value(), next.value()));
+                line.set_cursor(column);
+            }
+            _ => return self.lines[self.row].handle_key(key),
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn edit_text() {
+        let mut input = TextInput::default();
+        for c in "héllo".chars() {
+            input.handle_key(&key(KeyCode::Char(c)));
+        }
+        input.handle_key(&key(KeyCode::Left));
+        input.handle_key(&key(KeyCode::Backspace));
+        input.handle_key(&key(KeyCode::Home));
+        input.handle_key(&key(KeyCode::Delete));
+        assert_eq!(input.value(), "élo");
+        assert_eq!(input.cursor(), 0);
+        assert!(!input.handle_key(&key(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn edit_lines() {
+        let mut area = TextArea::default();
+        area.set_value("if (a) {}");
+        area.handle_key(&key(KeyCode::Left));
+        area.insert_newline();
+        assert_eq!(area.value(), "if (a) {\n}");
+        assert_eq!(area.cursor(), (0, 1));
+        assert!(!area.handle_key(&key(KeyCode::Down)));
+        area.handle_key(&key(KeyCode::Backspace));
+        assert_eq!(area.value(), "if (a) {}");
+        assert_eq!(area.cursor(), (8, 0));
+        assert!(!area.handle_key(&key(KeyCode::Up)));
+    }
+}
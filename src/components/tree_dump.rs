@@ -0,0 +1,361 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use strum::IntoEnumIterator;
+use tokio::sync::Mutex;
+
+use crate::{
+    action::Action,
+    session::session_manager::SessionManager,
+    store::{action::Action as StoreAction, state::State, Store},
+    tui::Frame,
+    vm_service::tree_dump::{parse_tree_dump, DumpNode, TreeDump},
+};
+use color_eyre::eyre::Result;
+
+use super::{text_input::TextInput, Component};
+
+pub struct TreeDumpComponent {
+    session_manager: Arc<Mutex<SessionManager>>,
+    is_selected: bool,
+    // Identifies the shown dump, to reset folding when another one is loaded.
+    dump_key: Option<(String, TreeDump, usize)>,
+    collapsed: HashSet<usize>,
+    // Indices of the visible nodes, in the order they are drawn.
+    rows: Vec<usize>,
+    list_state: ListState,
+    query: String,
+    // Set while the search query is being edited.
+    search_input: Option<TextInput>,
+    // Set by a search, which runs on the next draw as it needs the nodes. True searches forward.
+    pending_search: Option<bool>,
+    details_scroll: u16,
+}
+
+impl TreeDumpComponent {
+    pub fn new(session_manager: Arc<Mutex<SessionManager>>) -> Self {
+        Self {
+            session_manager,
+            is_selected: false,
+            dump_key: None,
+            collapsed: HashSet::new(),
+            rows: vec![],
+            list_state: ListState::default().with_selected(Some(0)),
+            query: String::new(),
+            search_input: None,
+            pending_search: None,
+            details_scroll: 0,
+        }
+    }
+
+    pub fn set_selected(&mut self, is_selected: bool) {
+        self.is_selected = is_selected;
+    }
+
+    fn dump(&self, kind: TreeDump, store: Arc<Mutex<Store>>) {
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let state = store.lock().await.get_state().await;
+            let Some(selected) = session_manager.lock().await.selected_isolate(&state).await else {
+                return;
+            };
+            let dump = match selected
+                .vm_service
+                .debug_dump(&selected.isolate_id, kind)
+                .await
+            {
+                Ok(dump) => dump,
+                Err(e) => {
+                    log::error!("Failed to dump the {}: {:?}", kind, e);
+                    return;
+                }
+            };
+            store
+                .lock()
+                .await
+                .dispatch(StoreAction::SetTreeDump {
+                    session_id: selected.session_id,
+                    kind,
+                    nodes: parse_tree_dump(&dump),
+                })
+                .await;
+        });
+    }
+
+    fn selected_node(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.rows.get(index))
+            .copied()
+    }
+
+    fn toggle_collapsed(&mut self) {
+        if let Some(index) = self.selected_node() {
+            if !self.collapsed.remove(&index) {
+                self.collapsed.insert(index);
+            }
+        }
+    }
+
+    /// Find the next node matching the query from the selected one, unfolding its ancestors.
+    fn find_match(&mut self, nodes: &[DumpNode], forward: bool) -> Option<usize> {
+        if self.query.is_empty() || nodes.is_empty() {
+            return None;
+        }
+        let query = self.query.to_lowercase();
+        let start = self.selected_node().unwrap_or(0);
+        let len = nodes.len();
+        let found = (1..=len)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % len
+                } else {
+                    (start + len - offset) % len
+                }
+            })
+            .find(|index| matches(&nodes[*index], &query))?;
+        let mut depth = nodes[found].depth;
+        for index in (0..found).rev() {
+            if nodes[index].depth < depth {
+                self.collapsed.remove(&index);
+                depth = nodes[index].depth;
+            }
+        }
+        Some(found)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let index = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(self.rows.len().saturating_sub(1));
+        self.list_state.select(Some(index));
+        self.details_scroll = 0;
+    }
+
+    fn handle_search_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let Some(input) = self.search_input.as_mut() else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.search_input = None;
+                return Ok(Some(Action::ExitInputMode));
+            }
+            KeyCode::Enter => {
+                self.query = input.value().trim().to_string();
+                self.search_input = None;
+                self.pending_search = Some(true);
+                return Ok(Some(Action::ExitInputMode));
+            }
+            _ => {
+                input.handle_key(&key);
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn matches(node: &DumpNode, query: &str) -> bool {
+    node.text.to_lowercase().contains(query)
+        || node
+            .properties
+            .iter()
+            .any(|property| property.to_lowercase().contains(query))
+}
+
+/// Indices of the nodes that are not folded into a collapsed ancestor.
+fn visible_rows(nodes: &[DumpNode], collapsed: &HashSet<usize>) -> Vec<usize> {
+    let mut rows = vec![];
+    let mut hidden_below = None;
+    for (index, node) in nodes.iter().enumerate() {
+        if hidden_below.is_some_and(|depth| node.depth > depth) {
+            continue;
+        }
+        hidden_below = collapsed.contains(&index).then_some(node.depth);
+        rows.push(index);
+    }
+    rows
+}
+
+impl Component for TreeDumpComponent {
+    fn handle_key_events(
+        &mut self,
+        key: KeyEvent,
+        store: Arc<Mutex<Store>>,
+    ) -> Result<Option<Action>> {
+        if self.search_input.is_some() {
+            return self.handle_search_key_events(key);
+        }
+        match key.code {
+            KeyCode::Char(c @ '1'..='5') => {
+                let index = c as usize - '1' as usize;
+                if let Some(kind) = TreeDump::iter().nth(index) {
+                    self.dump(kind, store);
+                }
+            }
+            KeyCode::Char('/') => {
+                self.search_input = Some(TextInput::new(&self.query));
+                return Ok(Some(Action::EnterInputMode));
+            }
+            KeyCode::Char('n') => self.pending_search = Some(true),
+            KeyCode::Char('N') => self.pending_search = Some(false),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle_collapsed(),
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.details_scroll = self.details_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.details_scroll = self.details_scroll.saturating_add(10),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect, state: &State) -> Result<()> {
+        let default_color = if self.is_selected {
+            Color::White
+        } else {
+            Color::DarkGray
+        };
+        let session = state.selected_session();
+        let dump = session.map(|session| &session.tree_dump);
+        let mut title = vec![Span::raw("Tree dumps")];
+        if let Some(kind) = dump.and_then(|dump| dump.kind) {
+            title.push(Span::raw(" "));
+            title.push(kind.to_string().bold());
+        }
+        if !self.query.is_empty() {
+            title.push(format!(" /{}", self.query).yellow());
+        }
+        let block = Block::default()
+            .title(Line::from(title))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(default_color));
+
+        let hints = TreeDump::iter()
+            .enumerate()
+            .map(|(index, kind)| format!("{}: {}", index + 1, kind))
+            .collect::<Vec<_>>()
+            .join("  ");
+        let (Some(session), Some(dump)) = (session, dump.filter(|dump| !dump.nodes.is_empty()))
+        else {
+            self.rows = vec![];
+            let text = Paragraph::new(format!(" Dump a tree of the selected app. {} ", hints))
+                .style(Style::default().fg(Color::DarkGray))
+                .wrap(Wrap { trim: false })
+                .block(block);
+            f.render_widget(text, area);
+            return Ok(());
+        };
+        let nodes = &dump.nodes;
+
+        let key = dump
+            .kind
+            .map(|kind| (session.id.clone(), kind, nodes.len()));
+        if key != self.dump_key {
+            self.dump_key = key;
+            self.collapsed.clear();
+            self.list_state.select(Some(0));
+            self.details_scroll = 0;
+        }
+        let found = match self.pending_search.take() {
+            Some(forward) => self.find_match(nodes, forward),
+            None => None,
+        };
+        self.rows = visible_rows(nodes, &self.collapsed);
+        if let Some(row) = found.and_then(|found| self.rows.iter().position(|i| *i == found)) {
+            self.list_state.select(Some(row));
+            self.details_scroll = 0;
+        }
+        if self.list_state.selected().unwrap_or(0) >= self.rows.len() {
+            self.list_state
+                .select(Some(self.rows.len().saturating_sub(1)));
+        }
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[0]);
+
+        let query = self.query.to_lowercase();
+        let items = self
+            .rows
+            .iter()
+            .map(|index| {
+                let node = &nodes[*index];
+                let has_children = nodes
+                    .get(index + 1)
+                    .is_some_and(|next| next.depth > node.depth);
+                let marker = match (has_children, self.collapsed.contains(index)) {
+                    (false, _) => "  ",
+                    (true, false) => "▾ ",
+                    (true, true) => "▸ ",
+                };
+                let text = if !query.is_empty() && matches(node, &query) {
+                    node.text.clone().yellow()
+                } else {
+                    Span::raw(node.text.clone())
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}{}", "  ".repeat(node.depth), marker)),
+                    text,
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        if let Some(node) = self.selected_node().map(|index| &nodes[index]) {
+            let mut lines = vec![Line::from(node.text.clone().bold())];
+            lines.extend(
+                node.properties
+                    .iter()
+                    .map(|property| Line::from(property.clone())),
+            );
+            let details = Paragraph::new(lines)
+                .block(Block::default().borders(Borders::LEFT))
+                .wrap(Wrap { trim: false })
+                .scroll((self.details_scroll, 0));
+            f.render_widget(details, columns[1]);
+        }
+
+        match &self.search_input {
+            Some(input) => {
+                f.render_widget(Paragraph::new(format!("/{}", input.value())), rows[1]);
+                f.set_cursor(rows[1].x + 1 + input.cursor() as u16, rows[1].y);
+            }
+            None => f.render_widget(
+                Paragraph::new(format!("{}  /: search  n/N: next/previous match", hints))
+                    .style(Style::default().fg(Color::DarkGray)),
+                rows[1],
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapsed_nodes_hide_their_descendants() {
+        let node = |depth: usize| DumpNode {
+            depth,
+            text: String::new(),
+            properties: vec![],
+        };
+        let nodes = vec![node(0), node(1), node(2), node(1), node(0)];
+        assert_eq!(visible_rows(&nodes, &HashSet::new()), vec![0, 1, 2, 3, 4]);
+        assert_eq!(visible_rows(&nodes, &HashSet::from([1])), vec![0, 1, 3, 4]);
+        assert_eq!(visible_rows(&nodes, &HashSet::from([0])), vec![0, 4]);
+    }
+}
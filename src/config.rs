@@ -31,6 +31,24 @@ pub struct Config {
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    #[serde(default)]
+    pub triggers: Vec<TriggerConfig>,
+}
+
+/// A rule that runs an action or a shell command when an app log line matches `pattern`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TriggerConfig {
+    pub pattern: String,
+    #[serde(default)]
+    pub action: Option<Action>,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default = "default_trigger_cooldown_ms")]
+    pub cooldown_ms: u64,
+}
+
+fn default_trigger_cooldown_ms() -> u64 {
+    1000
 }
 
 impl Config {
@@ -479,6 +497,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_triggers() {
+        let c: Config = json5::from_str(
+            r#"{
+              "triggers": [
+                { "pattern": "Lost connection to device", "action": "RestartSession" },
+                { "pattern": "Reloaded .* libraries", "command": "say reloaded", "cooldown_ms": 5000 },
+              ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            c.triggers,
+            vec![
+                TriggerConfig {
+                    pattern: "Lost connection to device".to_string(),
+                    action: Some(Action::RestartSession),
+                    command: None,
+                    cooldown_ms: 1000,
+                },
+                TriggerConfig {
+                    pattern: "Reloaded .* libraries".to_string(),
+                    action: None,
+                    command: Some("say reloaded".to_string()),
+                    cooldown_ms: 5000,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
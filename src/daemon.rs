@@ -1,3 +1,4 @@
 pub mod flutter;
 pub mod io;
 pub mod run;
+pub mod traffic;
@@ -1,5 +1,7 @@
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde_json::Value;
 
 use super::device::{Device, DeviceCapabilities};
 use super::emulator::Emulator;
@@ -18,8 +20,31 @@ pub struct GetSupportedPlatformsResult {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ServeDevToolsResult {
-    host: Option<String>,
-    port: Option<String>,
+    pub host: Option<String>,
+    #[serde(default, deserialize_with = "optional_string_or_number")]
+    pub port: Option<String>,
+}
+
+impl ServeDevToolsResult {
+    pub fn url(&self) -> Option<String> {
+        Some(format!(
+            "http://{}:{}",
+            self.host.as_ref()?,
+            self.port.as_ref()?
+        ))
+    }
+}
+
+// The daemon reports the DevTools port as a number.
+fn optional_string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(Value::Null) | None => Ok(None),
+        Some(value) => Ok(Some(value.to_string())),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,6 +83,8 @@ pub type StopAppResponse = FlutterDaemonResponse<bool>;
 
 pub type DetachAppResponse = FlutterDaemonResponse<bool>;
 
+pub type CallServiceExtensionResponse = FlutterDaemonResponse<Value>;
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -256,6 +283,16 @@ mod test {
         )
     }
 
+    #[test]
+    fn serve_dev_tools_response_with_numeric_port() {
+        let json = r#"{"id":1,"result":{"host":"127.0.0.1","port":9100}}"#;
+        let response: ServeDevToolsResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response.result.unwrap().url(),
+            Some("http://127.0.0.1:9100".to_string())
+        );
+    }
+
     #[test]
     fn restart_app_response() {
         let json = r#"{"id":1,"result":{"code":0,"message":"Success"}}"#;
@@ -2,31 +2,42 @@ use std::{process::Stdio, sync::Arc};
 
 use color_eyre::{eyre::eyre, Result};
 use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     process::{ChildStdin, Command},
     sync::{broadcast, Mutex},
 };
 
-use super::io::{
-    event::{
-        AppDebugPortEventParams, AppLogEventParams, AppProgressEventParams, AppStartEventParams,
-        AppStartedEventParams, AppStopEventParams, ConnectedEventParams, FlutterDaemonEvent,
-        LogEventParams, LogMessageEventParams,
-    },
-    parse_event, parse_response,
-    request::{DetachAppParams, FlutterDaemonRequest, RestartAppParams, StopAppParams},
-    response::{
-        DetachAppResponse, FlutterDaemonResponse, RestartAppResponse, RestartAppResult,
-        ShutdownResponse, StopAppResponse, VersionResponse,
+use super::{
+    io::{
+        event::{
+            AppDebugPortEventParams, AppLogEventParams, AppProgressEventParams,
+            AppStartEventParams, AppStartedEventParams, AppStopEventParams, ConnectedEventParams,
+            FlutterDaemonEvent, LogEventParams, LogMessageEventParams,
+        },
+        parse_event, parse_response,
+        request::{
+            CallServiceExtensionParams, DetachAppParams, FlutterDaemonRequest, RestartAppParams,
+            StopAppParams,
+        },
+        response::{
+            CallServiceExtensionResponse, DetachAppResponse, FlutterDaemonResponse,
+            RestartAppResponse, RestartAppResult, ShutdownResponse, StopAppResponse,
+            VersionResponse,
+        },
     },
+    traffic::{TrafficDirection, TrafficEntry, TrafficLog},
 };
 
+const TRAFFIC_LOG_CAPACITY: usize = 500;
+
 pub struct FlutterRun {
     app_id: Arc<Mutex<Option<String>>>,
     tx: broadcast::Sender<String>,
     stdin: Arc<Mutex<ChildStdin>>,
     request_count: Arc<Mutex<u32>>,
+    traffic: TrafficLog,
 }
 
 impl FlutterRun {
@@ -49,12 +60,15 @@ impl FlutterRun {
             .ok_or(eyre!("Stdout is not available"))?;
 
         let app_id = Arc::new(Mutex::new(None::<String>));
-        let (tx, _) = broadcast::channel::<String>(16);
+        let (tx, _) = broadcast::channel::<String>(256);
+        let traffic = TrafficLog::new(TRAFFIC_LOG_CAPACITY);
 
         let _tx = tx.clone();
+        let _traffic = traffic.clone();
         tokio::spawn(async move {
             let mut lines = BufReader::new(stdout).lines();
             while let Ok(Some(line)) = lines.next_line().await {
+                _traffic.record(TrafficDirection::Received, &line).await;
                 let _ = _tx.send(line);
             }
         });
@@ -76,9 +90,20 @@ impl FlutterRun {
             stdin: Arc::new(Mutex::new(process.stdin.take().unwrap())),
             tx,
             request_count: Arc::new(Mutex::new(0)),
+            traffic,
         })
     }
 
+    /// Subscribe to every line written by `flutter run`, so that no event is missed
+    /// between two `receive_*` calls.
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.tx.subscribe()
+    }
+
+    pub async fn traffic(&self) -> Vec<TrafficEntry> {
+        self.traffic.entries().await
+    }
+
     pub async fn version(&self) -> Result<String> {
         let request_id = self.request_id().await;
         let request = FlutterDaemonRequest::Version { id: request_id };
@@ -103,6 +128,34 @@ impl FlutterRun {
         self.restart(true).await
     }
 
+    /// Invoke a service extension such as `ext.flutter.debugPaint` through the daemon.
+    pub async fn call_service_extension(
+        &self,
+        method_name: &str,
+        params: Option<Map<String, Value>>,
+    ) -> Result<Value> {
+        let request_id = self.request_id().await;
+        let app_id = self
+            .app_id
+            .lock()
+            .await
+            .clone()
+            .ok_or(eyre!("App id is not set"))?;
+        let request = FlutterDaemonRequest::CallServiceExtension {
+            id: request_id,
+            params: CallServiceExtensionParams {
+                app_id,
+                method_name: method_name.to_string(),
+                params,
+            },
+        };
+        self.send_request(&request).await?;
+        let response: CallServiceExtensionResponse = self.receive_response(request_id).await?;
+        response
+            .result
+            .ok_or(eyre!("Could not call service extension {}", method_name))
+    }
+
     pub async fn detach(&self) -> Result<()> {
         let request_id = self.request_id().await;
         let app_id = self
@@ -256,6 +309,7 @@ impl FlutterRun {
     async fn send_request(&self, request: &FlutterDaemonRequest) -> Result<()> {
         let message = serde_json::to_string(request)?;
         let message = format!("[{}]\n", message);
+        self.traffic.record(TrafficDirection::Sent, &message).await;
         let mut stdin = self.stdin.lock().await;
         stdin.write_all(message.as_bytes()).await?;
         Ok(())
@@ -0,0 +1,76 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficDirection {
+    #[serde(rename = "sent")]
+    Sent,
+    #[serde(rename = "received")]
+    Received,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TrafficEntry {
+    pub direction: TrafficDirection,
+    pub message: String,
+}
+
+/// Keeps the most recent messages exchanged with a flutter process so they can be
+/// attached to bug reports.
+#[derive(Debug, Clone)]
+pub struct TrafficLog {
+    entries: Arc<Mutex<VecDeque<TrafficEntry>>>,
+    capacity: usize,
+}
+
+impl TrafficLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub async fn record(&self, direction: TrafficDirection, message: &str) {
+        let mut entries = self.entries.lock().await;
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(TrafficEntry {
+            direction,
+            message: message.trim_end().to_string(),
+        });
+    }
+
+    pub async fn entries(&self) -> Vec<TrafficEntry> {
+        self.entries.lock().await.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn keeps_only_recent_entries() {
+        let traffic = TrafficLog::new(2);
+        traffic.record(TrafficDirection::Sent, "first\n").await;
+        traffic.record(TrafficDirection::Received, "second").await;
+        traffic.record(TrafficDirection::Received, "third").await;
+        assert_eq!(
+            traffic.entries().await,
+            vec![
+                TrafficEntry {
+                    direction: TrafficDirection::Received,
+                    message: "second".to_string(),
+                },
+                TrafficEntry {
+                    direction: TrafficDirection::Received,
+                    message: "third".to_string(),
+                },
+            ]
+        );
+    }
+}
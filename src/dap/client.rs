@@ -0,0 +1,422 @@
+use std::{collections::BTreeMap, process::Stdio, sync::Arc, time::Duration};
+
+use color_eyre::{eyre::eyre, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    process::{Child, Command},
+    sync::{
+        broadcast::{self, error::RecvError},
+        Mutex,
+    },
+};
+
+use super::io::{
+    encode_message, parse_message, read_message, Breakpoint, DapEvent, DapMessage, DapRequest,
+    Scope, SourceBreakpoint, StackFrame, Variable,
+};
+
+type Writer = Box<dyn AsyncWrite + Send + Unpin>;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// The launch response can wait for the first build of the app.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A Debug Adapter Protocol client, usually talking to `flutter debug_adapter` over stdio.
+pub struct DebugAdapter {
+    // Only the reader task holds the sender, so receivers are closed once the adapter exits.
+    rx: broadcast::Receiver<String>,
+    writer: Arc<Mutex<Writer>>,
+    seq: Arc<Mutex<i64>>,
+    // The adapter process is killed along with the client.
+    process: Option<Child>,
+}
+
+impl DebugAdapter {
+    pub fn spawn(project_root: Option<&str>) -> Result<Self> {
+        let mut process = Command::new("flutter")
+            .arg("debug_adapter")
+            .current_dir(project_root.unwrap_or("."))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = process
+            .stdout
+            .take()
+            .ok_or(eyre!("Stdout is not available"))?;
+        let stdin = process
+            .stdin
+            .take()
+            .ok_or(eyre!("Stdin is not available"))?;
+        let mut adapter = Self::connect(stdout, stdin);
+        adapter.process = Some(process);
+        Ok(adapter)
+    }
+
+    pub fn connect<R, W>(reader: R, writer: W) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let (tx, rx) = broadcast::channel::<String>(256);
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(reader);
+            while let Ok(Some(message)) = read_message(&mut reader).await {
+                let _ = tx.send(message);
+            }
+        });
+        Self {
+            rx,
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            seq: Arc::new(Mutex::new(0)),
+            process: None,
+        }
+    }
+
+    /// Initialize the adapter and launch the app, setting breakpoints and exception
+    /// filters before it starts running. Returns the breakpoints set for each file.
+    pub async fn start(
+        &self,
+        arguments: Value,
+        breakpoints: &BTreeMap<String, Vec<i64>>,
+        exception_filters: &[&str],
+    ) -> Result<Vec<(String, Vec<Breakpoint>)>> {
+        // Subscribe first, as the adapter may send `initialized` right after initializing.
+        let mut events = self.events();
+        let _: Value = self
+            .request(
+                "initialize",
+                Some(json!({
+                    "clientID": "lazyflutter",
+                    "adapterID": "dart",
+                    "linesStartAt1": true,
+                    "columnsStartAt1": true,
+                    "pathFormat": "path",
+                    "supportsVariableType": true,
+                })),
+            )
+            .await?;
+        // The launch response only arrives once the configuration is done.
+        let launch = self.request_within::<Value>("launch", Some(arguments), LAUNCH_TIMEOUT);
+        let configure = async {
+            loop {
+                match events.recv().await {
+                    Some(event) if event.event == "initialized" => break,
+                    Some(_) => continue,
+                    None => return Err(eyre!("The debug adapter exited before initializing")),
+                }
+            }
+            let mut set = vec![];
+            for (path, lines) in breakpoints {
+                set.push((path.clone(), self.set_breakpoints(path, lines).await?));
+            }
+            self.set_exception_breakpoints(exception_filters).await?;
+            self.request::<Value>("configurationDone", None).await?;
+            Ok(set)
+        };
+        let (_, set) = tokio::try_join!(launch, configure)?;
+        Ok(set)
+    }
+
+    /// Replace every breakpoint of a file.
+    pub async fn set_breakpoints(&self, path: &str, lines: &[i64]) -> Result<Vec<Breakpoint>> {
+        let breakpoints = lines
+            .iter()
+            .map(|line| SourceBreakpoint { line: *line })
+            .collect::<Vec<_>>();
+        let body: Value = self
+            .request(
+                "setBreakpoints",
+                Some(json!({"source": {"path": path}, "breakpoints": breakpoints})),
+            )
+            .await?;
+        Ok(serde_json::from_value(body["breakpoints"].clone())?)
+    }
+
+    /// Pause on exceptions matching the filters, `All` or `Unhandled` for Dart.
+    pub async fn set_exception_breakpoints(&self, filters: &[&str]) -> Result<()> {
+        let _: Value = self
+            .request("setExceptionBreakpoints", Some(json!({"filters": filters})))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn resume(&self, thread_id: i64) -> Result<()> {
+        self.thread_request("continue", thread_id).await
+    }
+
+    pub async fn pause(&self, thread_id: i64) -> Result<()> {
+        self.thread_request("pause", thread_id).await
+    }
+
+    pub async fn step_over(&self, thread_id: i64) -> Result<()> {
+        self.thread_request("next", thread_id).await
+    }
+
+    pub async fn step_in(&self, thread_id: i64) -> Result<()> {
+        self.thread_request("stepIn", thread_id).await
+    }
+
+    pub async fn step_out(&self, thread_id: i64) -> Result<()> {
+        self.thread_request("stepOut", thread_id).await
+    }
+
+    /// The id of the first thread, to pause while the app is running.
+    pub async fn main_thread_id(&self) -> Result<i64> {
+        let body: Value = self.request("threads", None).await?;
+        body["threads"][0]["id"]
+            .as_i64()
+            .ok_or(eyre!("The app has no threads"))
+    }
+
+    pub async fn stack_trace(&self, thread_id: i64) -> Result<Vec<StackFrame>> {
+        let body: Value = self
+            .request("stackTrace", Some(json!({"threadId": thread_id})))
+            .await?;
+        Ok(serde_json::from_value(body["stackFrames"].clone())?)
+    }
+
+    pub async fn scopes(&self, frame_id: i64) -> Result<Vec<Scope>> {
+        let body: Value = self
+            .request("scopes", Some(json!({"frameId": frame_id})))
+            .await?;
+        Ok(serde_json::from_value(body["scopes"].clone())?)
+    }
+
+    pub async fn variables(&self, variables_reference: i64) -> Result<Vec<Variable>> {
+        let body: Value = self
+            .request(
+                "variables",
+                Some(json!({"variablesReference": variables_reference})),
+            )
+            .await?;
+        Ok(serde_json::from_value(body["variables"].clone())?)
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        let _: Value = self
+            .request("disconnect", Some(json!({"terminateDebuggee": true})))
+            .await?;
+        Ok(())
+    }
+
+    /// Send a request and wait for its body, turning an unsuccessful response into an `Err`.
+    pub async fn request<R>(&self, command: &str, arguments: Option<Value>) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        self.request_within(command, arguments, REQUEST_TIMEOUT)
+            .await
+    }
+
+    async fn request_within<R>(
+        &self,
+        command: &str,
+        arguments: Option<Value>,
+        timeout: Duration,
+    ) -> Result<R>
+    where
+        R: DeserializeOwned,
+    {
+        let seq = self.seq().await;
+        let request = DapRequest::new(seq, command, arguments);
+        // Subscribe before sending so that a fast response can not be missed.
+        let mut rx = self.rx.resubscribe();
+        let message = encode_message(&serde_json::to_string(&request)?);
+        {
+            let mut writer = self.writer.lock().await;
+            writer.write_all(message.as_bytes()).await?;
+            writer.flush().await?;
+        }
+        let response = async {
+            loop {
+                match rx.recv().await {
+                    Ok(message) => match parse_message(&message) {
+                        Some(DapMessage::Response(response)) if response.request_seq == seq => {
+                            if !response.success {
+                                return Err(eyre!(
+                                    "{} failed: {}",
+                                    command,
+                                    response.message.unwrap_or_default()
+                                ));
+                            }
+                            return Ok(serde_json::from_value(
+                                response.body.unwrap_or(Value::Null),
+                            )?);
+                        }
+                        _ => {}
+                    },
+                    // The response may have been one of the dropped messages.
+                    Err(RecvError::Lagged(count)) => {
+                        return Err(eyre!(
+                            "Missed {} debug adapter messages waiting for {}",
+                            count,
+                            command
+                        ))
+                    }
+                    Err(RecvError::Closed) => {
+                        return Err(eyre!("Could not receive debug adapter response"))
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(timeout, response)
+            .await
+            .map_err(|_| eyre!("{} timed out", command))?
+    }
+
+    pub fn events(&self) -> DapEvents {
+        DapEvents {
+            rx: self.rx.resubscribe(),
+        }
+    }
+
+    async fn thread_request(&self, command: &str, thread_id: i64) -> Result<()> {
+        let _: Value = self
+            .request(command, Some(json!({"threadId": thread_id})))
+            .await?;
+        Ok(())
+    }
+
+    async fn seq(&self) -> i64 {
+        let mut seq = self.seq.lock().await;
+        *seq += 1;
+        *seq
+    }
+}
+
+pub struct DapEvents {
+    rx: broadcast::Receiver<String>,
+}
+
+impl DapEvents {
+    /// Wait for the next event. Returns `None` once the adapter is gone.
+    pub async fn recv(&mut self) -> Option<DapEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(message) => {
+                    if let Some(DapMessage::Event(event)) = parse_message(&message) {
+                        return Some(event);
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::sync::Mutex as StdMutex;
+
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncWriteExt, BufReader};
+
+    use super::*;
+
+    /// Connect to an in-memory debug adapter replying to each request with `handler`.
+    pub fn fake_debug_adapter<F>(handler: F) -> DebugAdapter
+    where
+        F: Fn(DapRequest) -> Vec<Value> + Send + 'static,
+    {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (server_reader, mut server_writer) = tokio::io::split(server);
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(server_reader);
+            while let Ok(Some(message)) = read_message(&mut reader).await {
+                let request: DapRequest = serde_json::from_str(&message).unwrap();
+                for reply in handler(request) {
+                    let reply = encode_message(&reply.to_string());
+                    server_writer.write_all(reply.as_bytes()).await.unwrap();
+                }
+            }
+        });
+        let (client_reader, client_writer) = tokio::io::split(client);
+        DebugAdapter::connect(client_reader, client_writer)
+    }
+
+    pub fn response(request: &DapRequest, body: Value) -> Value {
+        json!({
+            "seq": 0,
+            "type": "response",
+            "request_seq": request.seq,
+            "success": true,
+            "command": request.command,
+            "body": body,
+        })
+    }
+
+    pub fn event(event: &str, body: Value) -> Value {
+        json!({"seq": 0, "type": "event", "event": event, "body": body})
+    }
+
+    #[tokio::test]
+    async fn start_configures_before_launching() {
+        let commands = Arc::new(StdMutex::new(vec![]));
+        let _commands = commands.clone();
+        let adapter = fake_debug_adapter(move |request| {
+            _commands.lock().unwrap().push(request.command.clone());
+            match request.command.as_str() {
+                "initialize" => vec![
+                    response(&request, json!({"supportsConfigurationDoneRequest": true})),
+                    event("initialized", json!({})),
+                ],
+                // Answered with the configurationDone response, like the Dart adapter.
+                "launch" => vec![],
+                "setBreakpoints" => {
+                    let line =
+                        request.arguments.as_ref().unwrap()["breakpoints"][0]["line"].clone();
+                    vec![response(
+                        &request,
+                        json!({"breakpoints": [{"id": 1, "verified": true, "line": line}]}),
+                    )]
+                }
+                "configurationDone" => vec![
+                    response(&request, json!({})),
+                    json!({
+                        "seq": 0, "type": "response", "request_seq": 2,
+                        "success": true, "command": "launch"
+                    }),
+                ],
+                _ => vec![response(&request, json!({}))],
+            }
+        });
+        let breakpoints = BTreeMap::from([("/app/lib/main.dart".to_string(), vec![12])]);
+        let set = adapter
+            .start(
+                json!({"program": "lib/main.dart"}),
+                &breakpoints,
+                &["Unhandled"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(set[0].0, "/app/lib/main.dart");
+        assert_eq!(set[0].1[0].line, Some(12));
+        assert!(set[0].1[0].verified);
+        assert_eq!(
+            *commands.lock().unwrap(),
+            vec![
+                "initialize",
+                "launch",
+                "setBreakpoints",
+                "setExceptionBreakpoints",
+                "configurationDone"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_request_is_an_error() {
+        let adapter = fake_debug_adapter(|request| {
+            vec![json!({
+                "seq": 0, "type": "response", "request_seq": request.seq,
+                "success": false, "command": request.command, "message": "Thread is not paused"
+            })]
+        });
+        let error = adapter.step_over(1).await.unwrap_err();
+        assert_eq!(error.to_string(), "next failed: Thread is not paused");
+    }
+}
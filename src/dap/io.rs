@@ -0,0 +1,203 @@
+use color_eyre::{eyre::eyre, Result};
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DapRequest {
+    pub seq: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+}
+
+impl DapRequest {
+    pub fn new(seq: i64, command: &str, arguments: Option<Value>) -> Self {
+        Self {
+            seq,
+            kind: "request".to_string(),
+            command: command.to_string(),
+            arguments,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DapResponse {
+    pub request_seq: i64,
+    pub success: bool,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DapEvent {
+    pub event: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A message sent by the debug adapter. Requests from the adapter, such as
+/// `runInTerminal`, are not supported.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DapMessage {
+    Response(DapResponse),
+    Event(DapEvent),
+    Request(DapRequest),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SourceBreakpoint {
+    pub line: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    pub line: i64,
+    pub column: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    pub name: String,
+    #[serde(rename = "variablesReference")]
+    pub variables_reference: i64,
+    #[serde(default)]
+    pub expensive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Non-zero if the variable has children to fetch with `variables`.
+    #[serde(rename = "variablesReference")]
+    #[serde(default)]
+    pub variables_reference: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StoppedEventBody {
+    pub reason: String,
+    #[serde(rename = "threadId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OutputEventBody {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    pub output: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BreakpointEventBody {
+    pub reason: String,
+    pub breakpoint: Breakpoint,
+}
+
+pub fn parse_message(s: &str) -> Option<DapMessage> {
+    serde_json::from_str::<DapMessage>(s).ok()
+}
+
+/// Frame a message with the `Content-Length` header used by DAP over stdio.
+pub fn encode_message(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+/// Read the next framed message. Returns `None` at the end of the stream.
+pub async fn read_message<R>(reader: &mut R) -> Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            content_length = Some(length.trim().parse::<usize>()?);
+        }
+    }
+    let content_length = content_length.ok_or(eyre!("Missing Content-Length header"))?;
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(String::from_utf8(body)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::BufReader;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn read_framed_messages() {
+        let stream = format!(
+            "{}{}",
+            encode_message(r#"{"seq":1,"type":"event","event":"initialized"}"#),
+            encode_message(
+                r#"{"seq":2,"type":"response","request_seq":1,"success":true,"command":"initialize","body":{}}"#
+            )
+        );
+        let mut reader = BufReader::new(stream.as_bytes());
+        let first = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(
+            parse_message(&first),
+            Some(DapMessage::Event(DapEvent {
+                event: "initialized".to_string(),
+                body: None,
+            }))
+        );
+        let second = read_message(&mut reader).await.unwrap().unwrap();
+        let Some(DapMessage::Response(response)) = parse_message(&second) else {
+            panic!("not a response");
+        };
+        assert_eq!(response.request_seq, 1);
+        assert!(response.success);
+        assert_eq!(read_message(&mut reader).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,145 @@
+use color_eyre::eyre::{eyre, Result};
+use serde_json::{Map, Value};
+use strum::{Display, EnumIter};
+
+use crate::daemon::run::FlutterRun;
+
+/// Debug settings of a running Flutter app that are toggled through service extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter, Display)]
+pub enum DebugFlag {
+    #[strum(to_string = "Debug paint")]
+    DebugPaint,
+    #[strum(to_string = "Repaint rainbow")]
+    RepaintRainbow,
+    #[strum(to_string = "Performance overlay")]
+    PerformanceOverlay,
+    #[strum(to_string = "Slow animations")]
+    SlowAnimations,
+    #[strum(to_string = "Baseline painting")]
+    BaselinePainting,
+    #[strum(to_string = "Brightness")]
+    Brightness,
+    #[strum(to_string = "Platform")]
+    Platform,
+    #[strum(to_string = "Debug banner")]
+    DebugBanner,
+}
+
+impl DebugFlag {
+    pub fn method(&self) -> &'static str {
+        match self {
+            DebugFlag::DebugPaint => "ext.flutter.debugPaint",
+            DebugFlag::RepaintRainbow => "ext.flutter.repaintRainbow",
+            DebugFlag::PerformanceOverlay => "ext.flutter.showPerformanceOverlay",
+            DebugFlag::SlowAnimations => "ext.flutter.timeDilation",
+            DebugFlag::BaselinePainting => "ext.flutter.debugPaintBaselinesEnabled",
+            DebugFlag::Brightness => "ext.flutter.brightnessOverride",
+            DebugFlag::Platform => "ext.flutter.platformOverride",
+            DebugFlag::DebugBanner => "ext.flutter.debugAllowBanner",
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            DebugFlag::SlowAnimations => "timeDilation",
+            DebugFlag::Brightness | DebugFlag::Platform => "value",
+            _ => "enabled",
+        }
+    }
+
+    /// The values the flag cycles through, starting with the default one.
+    fn values(&self) -> &'static [&'static str] {
+        match self {
+            DebugFlag::SlowAnimations => &["1.0", "5.0"],
+            DebugFlag::Brightness => &["default", "Brightness.light", "Brightness.dark"],
+            DebugFlag::Platform => &[
+                "default", "android", "iOS", "fuchsia", "linux", "macOS", "windows",
+            ],
+            DebugFlag::DebugBanner => &["true", "false"],
+            _ => &["false", "true"],
+        }
+    }
+
+    /// The value set when the flag is toggled from `current`.
+    pub fn next_value(&self, current: Option<&str>) -> String {
+        let values = self.values();
+        let index = current
+            .and_then(|current| values.iter().position(|value| *value == current))
+            .unwrap_or(0);
+        let index = (index + 1) % values.len();
+        values[index].to_string()
+    }
+
+    /// Read the current value back from the app.
+    pub async fn read(&self, run: &FlutterRun) -> Result<String> {
+        let result = run.call_service_extension(self.method(), None).await?;
+        self.parse(&result)
+    }
+
+    /// Set the value and return the one the app reports afterwards.
+    pub async fn write(&self, run: &FlutterRun, value: &str) -> Result<String> {
+        let params = Map::from_iter([(self.key().to_string(), Value::from(value))]);
+        let result = run
+            .call_service_extension(self.method(), Some(params))
+            .await?;
+        self.parse(&result)
+    }
+
+    fn parse(&self, result: &Value) -> Result<String> {
+        match &result[self.key()] {
+            Value::String(value) => Ok(value.clone()),
+            Value::Bool(value) => Ok(value.to_string()),
+            Value::Number(value) => Ok(value.to_string()),
+            _ => Err(eyre!("Unexpected result of {}: {}", self.method(), result)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn next_value() {
+        assert_eq!(DebugFlag::DebugPaint.next_value(None), "true");
+        assert_eq!(DebugFlag::DebugPaint.next_value(Some("false")), "true");
+        assert_eq!(DebugFlag::DebugPaint.next_value(Some("true")), "false");
+        assert_eq!(DebugFlag::SlowAnimations.next_value(Some("1.0")), "5.0");
+        assert_eq!(DebugFlag::Platform.next_value(Some("default")), "android");
+        assert_eq!(DebugFlag::Platform.next_value(Some("windows")), "default");
+        assert_eq!(DebugFlag::Platform.next_value(Some("unknown")), "android");
+        assert_eq!(
+            DebugFlag::Brightness.next_value(Some("default")),
+            "Brightness.light"
+        );
+        assert_eq!(
+            DebugFlag::Brightness.next_value(Some("Brightness.dark")),
+            "default"
+        );
+    }
+
+    #[test]
+    fn parse_result() {
+        assert_eq!(
+            DebugFlag::DebugPaint
+                .parse(&json!({"enabled": "true", "type": "_extensionType"}))
+                .unwrap(),
+            "true"
+        );
+        assert_eq!(
+            DebugFlag::SlowAnimations
+                .parse(&json!({"timeDilation": "5.0"}))
+                .unwrap(),
+            "5.0"
+        );
+        assert_eq!(
+            DebugFlag::Brightness
+                .parse(&json!({"value": "Brightness.dark"}))
+                .unwrap(),
+            "Brightness.dark"
+        );
+        assert!(DebugFlag::Platform.parse(&json!({})).is_err());
+    }
+}
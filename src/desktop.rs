@@ -0,0 +1,58 @@
+use std::process::Stdio;
+
+use color_eyre::eyre::{eyre, Result};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Open a URL with the default browser of the platform.
+pub async fn open_url(url: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut process = Command::new("open");
+    #[cfg(windows)]
+    let mut process = Command::new("cmd");
+    #[cfg(windows)]
+    process.args(["/C", "start", ""]);
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut process = Command::new("xdg-open");
+
+    let status = process
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await?;
+    if !status.success() {
+        return Err(eyre!("Could not open {}: {}", url, status));
+    }
+    Ok(())
+}
+
+/// Copy text to the system clipboard through the platform's clipboard command.
+pub async fn copy_to_clipboard(text: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut process = Command::new("pbcopy");
+    #[cfg(windows)]
+    let mut process = Command::new("clip");
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut process = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-copy")
+    } else {
+        let mut process = Command::new("xclip");
+        process.args(["-selection", "clipboard"]);
+        process
+    };
+
+    let mut child = process
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut stdin = child.stdin.take().ok_or(eyre!("Stdin is not available"))?;
+    stdin.write_all(text.as_bytes()).await?;
+    drop(stdin);
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(eyre!("Could not copy to the clipboard: {}", status));
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use std::path::Path;
+
+/// The editor set in `$VISUAL` or `$EDITOR`, `vi` otherwise.
+pub fn editor() -> String {
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or("vi".to_string())
+}
+
+/// The command line opening `path` at a one-based line and column in `editor`, which may carry
+/// arguments of its own like `code --wait`.
+pub fn command_line(editor: &str, path: &Path, line: usize, column: usize) -> Vec<String> {
+    let mut command = editor
+        .split_whitespace()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    let path = path.display().to_string();
+    let position = format!("{}:{}:{}", path, line, column);
+    match program_name(&command).as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => {
+            command.extend(["--goto".to_string(), position])
+        }
+        "subl" | "zed" | "hx" | "helix" => command.push(position),
+        "idea" | "studio" => command.extend(["--line".to_string(), line.to_string(), path]),
+        // vi, vim, nvim, nano, emacs, micro and most other terminal editors.
+        _ => command.extend([format!("+{}", line), path]),
+    }
+    command
+}
+
+/// Whether the command opens a window of its own, so the terminal can be left to the TUI.
+pub fn is_gui(command_line: &[String]) -> bool {
+    matches!(
+        program_name(command_line).as_str(),
+        "code" | "code-insiders" | "codium" | "cursor" | "subl" | "zed" | "idea" | "studio"
+    )
+}
+
+// The program without its directory and extension, e.g. `studio` for `/opt/bin/studio.sh`.
+fn program_name(command_line: &[String]) -> String {
+    command_line
+        .first()
+        .and_then(|program| Path::new(program).file_stem())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_for_editors() {
+        let path = Path::new("lib/main.dart");
+        assert_eq!(
+            command_line("nvim", path, 12, 3),
+            vec!["nvim", "+12", "lib/main.dart"]
+        );
+        assert_eq!(
+            command_line("/usr/bin/code --wait", path, 12, 3),
+            vec!["/usr/bin/code", "--wait", "--goto", "lib/main.dart:12:3"]
+        );
+        assert_eq!(
+            command_line("/opt/android-studio/bin/studio.sh", path, 12, 3),
+            vec![
+                "/opt/android-studio/bin/studio.sh",
+                "--line",
+                "12",
+                "lib/main.dart"
+            ]
+        );
+    }
+
+    #[test]
+    fn gui_editors() {
+        let path = Path::new("lib/main.dart");
+        assert!(is_gui(&command_line("code --wait", path, 1, 1)));
+        assert!(is_gui(&command_line("studio.sh", path, 1, 1)));
+        assert!(!is_gui(&command_line("nvim", path, 1, 1)));
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::Result;
+
+use crate::utils::get_data_dir;
+
+/// Favorite service extensions, saved per project.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Favorites {
+    path: PathBuf,
+    projects: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Favorites {
+    pub fn load_default() -> Self {
+        Self::load(&get_data_dir().join("favorite_extensions.json"))
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let projects = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            projects,
+        }
+    }
+
+    pub fn contains(&self, project: &str, extension: &str) -> bool {
+        self.projects
+            .get(project)
+            .map(|extensions| extensions.contains(extension))
+            .unwrap_or_default()
+    }
+
+    pub fn toggle(&mut self, project: &str, extension: &str) -> Result<()> {
+        let extensions = self.projects.entry(project.to_string()).or_default();
+        if !extensions.remove(extension) {
+            extensions.insert(extension.to_string());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.projects)?)?;
+        Ok(())
+    }
+}
+
+/// The key favorites of the project at `project_root` are saved under.
+pub fn project_key(project_root: Option<&str>) -> String {
+    let root = project_root.unwrap_or(".");
+    std::fs::canonicalize(root)
+        .map(|path| path.display().to_string())
+        .unwrap_or(root.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_and_reload() {
+        let path =
+            std::env::temp_dir().join(format!("lazyflutter-favorites-{}.json", std::process::id()));
+        let mut favorites = Favorites::load(&path);
+        favorites.toggle("/app", "ext.app.reset").unwrap();
+        favorites.toggle("/app", "ext.app.login").unwrap();
+        favorites.toggle("/app", "ext.app.login").unwrap();
+
+        let favorites = Favorites::load(&path);
+        assert!(favorites.contains("/app", "ext.app.reset"));
+        assert!(!favorites.contains("/app", "ext.app.login"));
+        assert!(!favorites.contains("/other", "ext.app.reset"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
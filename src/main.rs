@@ -3,17 +3,29 @@
 #![allow(unused_variables)]
 
 pub mod action;
+pub mod analysis;
 pub mod app;
+pub mod bug_report;
 pub mod cli;
 pub mod components;
 pub mod config;
 pub mod daemon;
+pub mod dap;
+pub mod debug_flags;
+pub mod desktop;
+pub mod editor;
 pub mod event;
+pub mod favorites;
 pub mod mode;
+pub mod packages;
+pub mod pubspec;
 pub mod session;
 pub mod store;
+pub mod test_runner;
+pub mod trigger;
 pub mod tui;
 pub mod utils;
+pub mod vm_service;
 
 use clap::Parser;
 use cli::Cli;
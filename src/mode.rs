@@ -4,4 +4,6 @@ use serde::{Deserialize, Serialize};
 pub enum Mode {
     #[default]
     Home,
+    /// A text field has focus, so key bindings are disabled.
+    Input,
 }
@@ -0,0 +1,366 @@
+pub mod cache;
+pub mod graph;
+pub mod license;
+pub mod overrides;
+pub mod version;
+
+use std::{collections::BTreeMap, path::Path, process::Stdio, time::SystemTime};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+    sync::mpsc::UnboundedSender,
+};
+
+use crate::pubspec::Pubspec;
+
+use self::version::{Version, VersionConstraint};
+
+/// The resolved packages of `pubspec.lock`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct PubspecLock {
+    #[serde(default)]
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LockedPackage {
+    /// `direct main`, `direct dev`, `direct overridden` or `transitive`.
+    pub dependency: String,
+    pub source: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: serde_yaml::Value,
+}
+
+impl PubspecLock {
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join("pubspec.lock");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| eyre!("Could not read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+}
+
+/// Why `pub get` has to run before the project builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaleReason {
+    MissingLock,
+    MissingPackageConfig,
+    /// `pubspec.yaml` or `pubspec.lock` changed after packages were last fetched.
+    Modified(&'static str),
+    NotLocked(String),
+    /// A locked version outside the constraint of `pubspec.yaml`.
+    Unsatisfied {
+        package: String,
+        constraint: String,
+        version: String,
+    },
+}
+
+impl StaleReason {
+    pub fn message(&self) -> String {
+        match self {
+            StaleReason::MissingLock => "pubspec.lock is missing".to_string(),
+            StaleReason::MissingPackageConfig => "packages have not been fetched".to_string(),
+            StaleReason::Modified(file) => format!("{} changed since the last pub get", file),
+            StaleReason::NotLocked(package) => format!("{} is not in pubspec.lock", package),
+            StaleReason::Unsatisfied {
+                package,
+                constraint,
+                version,
+            } => format!("{} {} does not satisfy {}", package, version, constraint),
+        }
+    }
+}
+
+/// Check whether the packages of the project are out of date with `pubspec.yaml`.
+pub fn stale_reason(project_root: &Path) -> Option<StaleReason> {
+    let modified = |file: &str| {
+        std::fs::metadata(project_root.join(file))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let pubspec_modified = modified("pubspec.yaml")?;
+    let Some(lock_modified) = modified("pubspec.lock") else {
+        return Some(StaleReason::MissingLock);
+    };
+    let Some(package_config_modified) = modified(".dart_tool/package_config.json") else {
+        return Some(StaleReason::MissingPackageConfig);
+    };
+    // pub get always rewrites package_config.json but leaves an unchanged lock file alone,
+    // so both files are compared with it. A newer lock file usually comes from a checkout.
+    let newer = |modified: SystemTime| modified > package_config_modified;
+    if newer(pubspec_modified) {
+        return Some(StaleReason::Modified("pubspec.yaml"));
+    }
+    if newer(lock_modified) {
+        return Some(StaleReason::Modified("pubspec.lock"));
+    }
+    let pubspec = Pubspec::load(project_root).ok()?;
+    let lock = PubspecLock::load(project_root).ok()?;
+    unsatisfied_constraint(&pubspec, &lock)
+}
+
+/// The first dependency of `pubspec.yaml` the lock file does not satisfy.
+pub fn unsatisfied_constraint(pubspec: &Pubspec, lock: &PubspecLock) -> Option<StaleReason> {
+    pubspec
+        .dependencies
+        .iter()
+        .chain(&pubspec.dev_dependencies)
+        .filter(|(name, _)| !pubspec.dependency_overrides.contains_key(*name))
+        .find_map(|(name, source)| {
+            let Some(locked) = lock.packages.get(name) else {
+                return Some(StaleReason::NotLocked(name.clone()));
+            };
+            // Only hosted packages have a version constraint.
+            let constraint = match source {
+                serde_yaml::Value::String(constraint) => constraint,
+                serde_yaml::Value::Mapping(source) => source.get("version")?.as_str()?,
+                _ => return None,
+            };
+            let allowed =
+                VersionConstraint::parse(constraint)?.allows(&Version::parse(&locked.version)?);
+            (!allowed).then(|| StaleReason::Unsatisfied {
+                package: name.clone(),
+                constraint: constraint.to_string(),
+                version: locked.version.clone(),
+            })
+        })
+}
+
+/// A package reported by `flutter pub outdated --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutdatedPackage {
+    pub package: String,
+    /// `direct`, `dev` or `transitive`.
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub is_discontinued: bool,
+    pub current: Option<PackageVersion>,
+    pub upgradable: Option<PackageVersion>,
+    pub resolvable: Option<PackageVersion>,
+    pub latest: Option<PackageVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PackageVersion {
+    pub version: String,
+}
+
+#[derive(Deserialize)]
+struct OutdatedReport {
+    packages: Vec<OutdatedPackage>,
+}
+
+pub fn parse_outdated(output: &str) -> Result<Vec<OutdatedPackage>> {
+    // Flutter may print notices like "Resolving dependencies..." before the report.
+    let start = output
+        .find('{')
+        .ok_or(eyre!("pub outdated did not print a report"))?;
+    let report: OutdatedReport = serde_json::from_str(&output[start..])?;
+    Ok(report.packages)
+}
+
+/// Get the current, upgradable, resolvable and latest versions of every package.
+pub async fn outdated(project_root: &Path) -> Result<Vec<OutdatedPackage>> {
+    let output = Command::new("flutter")
+        .args(["pub", "outdated", "--json", "--show-all"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "flutter pub outdated exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_outdated(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PubCommand {
+    Get,
+    /// Upgrade one package, or all of them without a name.
+    Upgrade(Option<String>),
+    UpgradeMajorVersions,
+}
+
+impl PubCommand {
+    pub fn args(&self) -> Vec<String> {
+        let args = match self {
+            PubCommand::Get => vec!["pub", "get"],
+            PubCommand::Upgrade(None) => vec!["pub", "upgrade"],
+            PubCommand::Upgrade(Some(package)) => vec!["pub", "upgrade", package.as_str()],
+            PubCommand::UpgradeMajorVersions => vec!["pub", "upgrade", "--major-versions"],
+        };
+        args.into_iter().map(|arg| arg.to_string()).collect()
+    }
+
+    pub fn command_line(&self) -> String {
+        format!("flutter {}", self.args().join(" "))
+    }
+}
+
+/// Run a pub command in the project, sending each line it prints to `output`.
+pub async fn run(
+    project_root: &Path,
+    command: &PubCommand,
+    output: UnboundedSender<String>,
+) -> Result<()> {
+    let mut child = Command::new("flutter")
+        .args(command.args())
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or(eyre!("Stdout is not available"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or(eyre!("Stderr is not available"))?;
+    tokio::join!(
+        forward_lines(stdout, output.clone()),
+        forward_lines(stderr, output)
+    );
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(eyre!("{} exited with {}", command.command_line(), status));
+    }
+    Ok(())
+}
+
+async fn forward_lines(reader: impl AsyncRead + Unpin, output: UnboundedSender<String>) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if output.send(line).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lock_and_outdated_report() {
+        let lock = PubspecLock::parse(
+            r#"
+packages:
+  collection:
+    dependency: transitive
+    description:
+      name: collection
+      sha256: "ee67cb0715911d28db6bf4af1026078bd6f0128b07a5f66fb2ed94ec6783c09a"
+      url: "https://pub.dev"
+    source: hosted
+    version: "1.18.0"
+  flutter:
+    dependency: "direct main"
+    description: flutter
+    source: sdk
+    version: "0.0.0"
+sdks:
+  dart: ">=3.2.0 <4.0.0"
+"#,
+        )
+        .unwrap();
+        assert_eq!(lock.packages["collection"].version, "1.18.0");
+        assert_eq!(lock.packages["flutter"].dependency, "direct main");
+
+        let outdated = parse_outdated(
+            r#"Resolving dependencies...
+{
+  "packages": [
+    {
+      "package": "collection",
+      "kind": "transitive",
+      "isDiscontinued": false,
+      "current": {"version": "1.18.0"},
+      "upgradable": {"version": "1.18.0"},
+      "resolvable": {"version": "1.18.0"},
+      "latest": {"version": "1.19.0"}
+    },
+    {
+      "package": "removed",
+      "kind": "direct",
+      "current": null,
+      "upgradable": null,
+      "resolvable": null,
+      "latest": {"version": "2.0.0"}
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+        assert_eq!(outdated.len(), 2);
+        assert_eq!(outdated[0].latest.as_ref().unwrap().version, "1.19.0");
+        assert_eq!(outdated[1].current, None);
+    }
+
+    #[test]
+    fn lock_outside_constraints_is_stale() {
+        let pubspec = Pubspec::parse(
+            "name: app\ndependencies:\n  http: ^1.1.0\n  provider:\n    version: '>=6.0.0 <7.0.0'\n",
+        )
+        .unwrap();
+        let lock = PubspecLock::parse(
+            r#"
+packages:
+  http:
+    dependency: "direct main"
+    source: hosted
+    version: "0.13.6"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            unsatisfied_constraint(&pubspec, &lock),
+            Some(StaleReason::Unsatisfied {
+                package: "http".to_string(),
+                constraint: "^1.1.0".to_string(),
+                version: "0.13.6".to_string(),
+            })
+        );
+        let lock = PubspecLock::parse(
+            r#"
+packages:
+  http:
+    dependency: "direct main"
+    source: hosted
+    version: "1.2.0"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            unsatisfied_constraint(&pubspec, &lock),
+            Some(StaleReason::NotLocked("provider".to_string()))
+        );
+    }
+
+    #[test]
+    fn pub_command_args() {
+        assert_eq!(
+            PubCommand::Upgrade(Some("http".to_string())).command_line(),
+            "flutter pub upgrade http"
+        );
+        assert_eq!(
+            PubCommand::UpgradeMajorVersions.args(),
+            vec!["pub", "upgrade", "--major-versions"]
+        );
+    }
+}
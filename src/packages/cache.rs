@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use super::LockedPackage;
+
+/// The directory pub downloads hosted and git packages to.
+pub fn pub_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("PUB_CACHE") {
+        return Some(PathBuf::from(dir));
+    }
+    if cfg!(windows) {
+        return std::env::var_os("LOCALAPPDATA")
+            .map(|dir| PathBuf::from(dir).join("Pub").join("Cache"));
+    }
+    std::env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".pub-cache"))
+}
+
+/// The root of the Flutter SDK, which holds the `sdk` packages like `flutter`.
+pub fn flutter_root() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("FLUTTER_ROOT") {
+        return Some(PathBuf::from(dir));
+    }
+    let executable = if cfg!(windows) {
+        "flutter.bat"
+    } else {
+        "flutter"
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(executable))
+        .find(|path| path.is_file())
+        .and_then(|path| std::fs::canonicalize(path).ok())
+        .and_then(|path| Some(path.parent()?.parent()?.to_path_buf()))
+}
+
+/// Where the sources of a locked package are, if they can be found on this machine.
+pub fn package_dir(project_root: &Path, name: &str, package: &LockedPackage) -> Option<PathBuf> {
+    let description = &package.description;
+    let dir = match package.source.as_str() {
+        "hosted" => {
+            let url = description.get("url")?.as_str()?;
+            pub_cache_dir()?
+                .join("hosted")
+                .join(url_to_directory(url))
+                .join(format!("{}-{}", name, package.version))
+        }
+        "git" => {
+            let url = description.get("url")?.as_str()?;
+            let revision = description.get("resolved-ref")?.as_str()?;
+            let repository = url
+                .trim_end_matches('/')
+                .trim_end_matches(".git")
+                .rsplit('/')
+                .next()?;
+            let dir = pub_cache_dir()?
+                .join("git")
+                .join(format!("{}-{}", repository, revision));
+            match description.get("path").and_then(|path| path.as_str()) {
+                Some(path) => dir.join(path),
+                None => dir,
+            }
+        }
+        "path" => {
+            let path = Path::new(description.get("path")?.as_str()?);
+            project_root.join(path)
+        }
+        "sdk" => {
+            let sdk = description.as_str()?;
+            match sdk {
+                "flutter" => flutter_root()?.join("packages").join(name),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    dir.is_dir().then_some(dir)
+}
+
+// Pub names the directory of a hosted repository after its URL with unsafe characters escaped.
+fn url_to_directory(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    // Pub drops the scheme of `https://` and loopback hosts, and names every loopback host
+    // `localhost`.
+    let url = match url.split_once("://") {
+        Some((scheme @ ("http" | "https"), rest)) => {
+            let loopback = ["127.0.0.1", "[::1]", "localhost"]
+                .into_iter()
+                .find(|host| rest.starts_with(host));
+            match loopback {
+                Some(host) => format!("localhost{}", &rest[host.len()..]),
+                None if scheme == "https" => rest.to_string(),
+                None => url.to_string(),
+            }
+        }
+        _ => url.to_string(),
+    };
+    url.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '\\' | '/' | '|' | '?' | '*' | '%' => format!("%{}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_to_directory_escapes_unsafe_characters() {
+        assert_eq!(url_to_directory("https://pub.dev"), "pub.dev");
+        assert_eq!(
+            url_to_directory("https://pub.example.com:8080/dart/"),
+            "pub.example.com%588080%47dart"
+        );
+        assert_eq!(
+            url_to_directory("http://localhost:8080"),
+            "localhost%588080"
+        );
+        assert_eq!(
+            url_to_directory("http://127.0.0.1:8080/"),
+            "localhost%588080"
+        );
+        assert_eq!(
+            url_to_directory("http://pub.example.com"),
+            "http%58%47%47pub.example.com"
+        );
+    }
+}
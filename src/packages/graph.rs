@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::pubspec::Pubspec;
+
+use super::PubspecLock;
+
+/// Which locked packages depend on which, starting from the project itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    /// Direct dependencies of the project.
+    pub roots: Vec<String>,
+    /// Dependencies of each locked package. Packages without readable sources have none.
+    pub dependencies: BTreeMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph, reading the pubspec of each locked package through `package_pubspec`.
+    pub fn build(
+        pubspec: &Pubspec,
+        lock: &PubspecLock,
+        package_pubspec: impl Fn(&str) -> Option<Pubspec>,
+    ) -> Self {
+        let locked = |name: &&String| lock.packages.contains_key(*name);
+        let roots = pubspec
+            .dependencies
+            .keys()
+            .chain(pubspec.dev_dependencies.keys())
+            .filter(locked)
+            .cloned()
+            .collect();
+        let dependencies = lock
+            .packages
+            .keys()
+            .map(|name| {
+                let dependencies = package_pubspec(name)
+                    .map(|pubspec| {
+                        pubspec
+                            .dependencies
+                            .keys()
+                            .filter(locked)
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (name.clone(), dependencies)
+            })
+            .collect();
+        Self {
+            roots,
+            dependencies,
+        }
+    }
+
+    /// Packages depending on `package` directly.
+    pub fn dependents(&self, package: &str) -> Vec<String> {
+        self.dependencies
+            .iter()
+            .filter(|(_, dependencies)| dependencies.iter().any(|d| d == package))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// The shortest chain of dependencies from the project to `package`.
+    pub fn why(&self, package: &str) -> Option<Vec<String>> {
+        let mut queue = self
+            .roots
+            .iter()
+            .map(|root| vec![root.clone()])
+            .collect::<VecDeque<_>>();
+        let mut visited = self.roots.iter().collect::<BTreeSet<_>>();
+        while let Some(chain) = queue.pop_front() {
+            let last = chain.last()?;
+            if last == package {
+                return Some(chain);
+            }
+            for dependency in self.dependencies.get(last).into_iter().flatten() {
+                if visited.insert(dependency) {
+                    queue.push_back([chain.clone(), vec![dependency.clone()]].concat());
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn why_finds_the_shortest_chain() {
+        let pubspec = Pubspec::parse("name: app\ndependencies:\n  http:\n  provider:\n").unwrap();
+        let lock = PubspecLock::parse(
+            r#"
+packages:
+  http: {dependency: "direct main", source: hosted, version: "1.1.0"}
+  provider: {dependency: "direct main", source: hosted, version: "6.1.1"}
+  nested: {dependency: transitive, source: hosted, version: "1.0.0"}
+  meta: {dependency: transitive, source: hosted, version: "1.11.0"}
+"#,
+        )
+        .unwrap();
+        let graph = DependencyGraph::build(&pubspec, &lock, |name| {
+            let dependencies = match name {
+                "http" => "meta:",
+                "provider" => "nested:",
+                "nested" => "meta:",
+                _ => "",
+            };
+            Pubspec::parse(&format!(
+                "name: {}\ndependencies:\n  {}\n",
+                name, dependencies
+            ))
+            .ok()
+        });
+        assert_eq!(graph.roots, vec!["http", "provider"]);
+        assert_eq!(graph.dependents("meta"), vec!["http", "nested"]);
+        assert_eq!(
+            graph.why("meta"),
+            Some(vec!["http".to_string(), "meta".to_string()])
+        );
+        assert_eq!(
+            graph.why("nested"),
+            Some(vec!["provider".to_string(), "nested".to_string()])
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use super::PubspecLock;
+
+const LICENSE_FILES: [&str; 4] = ["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"];
+
+/// The license of a package, identified from the LICENSE file in its sources.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+    pub package: String,
+    pub version: String,
+    pub dependency: String,
+    pub source: String,
+    /// SPDX identifier, `Unknown` if unrecognized or `Not found` without sources or file.
+    pub license: String,
+    pub file: Option<PathBuf>,
+}
+
+/// The license of every locked package, read through `package_dir`.
+pub fn audit(
+    lock: &PubspecLock,
+    package_dir: impl Fn(&str) -> Option<PathBuf>,
+) -> Vec<PackageLicense> {
+    lock.packages
+        .iter()
+        .map(|(name, package)| {
+            let file = package_dir(name).and_then(|dir| license_file(&dir));
+            let license = match &file {
+                Some(file) => std::fs::read_to_string(file)
+                    .map(|text| identify(&text).to_string())
+                    .unwrap_or("Unknown".to_string()),
+                None => "Not found".to_string(),
+            };
+            PackageLicense {
+                package: name.clone(),
+                version: package.version.clone(),
+                dependency: package.dependency.clone(),
+                source: package.source.clone(),
+                license,
+                file,
+            }
+        })
+        .collect()
+}
+
+fn license_file(dir: &Path) -> Option<PathBuf> {
+    LICENSE_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Identify a license by phrases of its text.
+pub fn identify(text: &str) -> &'static str {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let contains = |phrase: &str| text.contains(phrase);
+    if contains("Apache License") && contains("Version 2.0") {
+        "Apache-2.0"
+    } else if contains("Permission is hereby granted, free of charge") {
+        "MIT"
+    } else if contains("Redistribution and use in source and binary forms") {
+        if contains("Neither the name") || contains("names of its contributors") {
+            "BSD-3-Clause"
+        } else {
+            "BSD-2-Clause"
+        }
+    } else if contains("Mozilla Public License Version 2.0") {
+        "MPL-2.0"
+    } else if contains("GNU LESSER GENERAL PUBLIC LICENSE") {
+        "LGPL"
+    } else if contains("GNU GENERAL PUBLIC LICENSE") {
+        if contains("Version 3") {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        }
+    } else if contains("Permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if contains("This is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else {
+        "Unknown"
+    }
+}
+
+/// The license report as CSV, one row per package.
+pub fn to_csv(licenses: &[PackageLicense]) -> String {
+    let mut csv = "package,version,dependency,source,license,license_file\n".to_string();
+    for license in licenses {
+        let file = license
+            .file
+            .as_ref()
+            .map(|file| file.display().to_string())
+            .unwrap_or_default();
+        let row = [
+            license.package.as_str(),
+            &license.version,
+            &license.dependency,
+            &license.source,
+            &license.license,
+            &file,
+        ]
+        .map(escape_csv_field)
+        .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identify_common_licenses() {
+        assert_eq!(
+            identify("Copyright 2014, the Dart project authors.\n\nRedistribution and use in source and binary forms, with or without\nmodification, are permitted provided that ... * Neither the name of Google LLC nor"),
+            "BSD-3-Clause"
+        );
+        assert_eq!(
+            identify(
+                "MIT License\n\nPermission is hereby granted, free of\n  charge, to any person"
+            ),
+            "MIT"
+        );
+        assert_eq!(identify("All rights reserved."), "Unknown");
+    }
+
+    #[test]
+    fn csv_escapes_fields() {
+        let csv = to_csv(&[PackageLicense {
+            package: "http".to_string(),
+            version: "1.1.0".to_string(),
+            dependency: "direct main".to_string(),
+            source: "hosted".to_string(),
+            license: "BSD-3-Clause".to_string(),
+            file: Some(PathBuf::from("/cache/http, v1/LICENSE")),
+        }]);
+        assert_eq!(
+            csv,
+            "package,version,dependency,source,license,license_file\n\
+             http,1.1.0,direct main,hosted,BSD-3-Clause,\"/cache/http, v1/LICENSE\"\n"
+        );
+    }
+}
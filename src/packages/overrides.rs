@@ -0,0 +1,298 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::pubspec::Pubspec;
+
+const SECTION: &str = "dependency_overrides";
+
+/// Override `package` with a local checkout, editing `pubspec.yaml` as text so the rest of the
+/// file keeps its formatting and comments.
+pub fn set_path_override(content: &str, package: &str, path: &str) -> Result<String> {
+    let indent = " ".repeat(indent_width(&split_lines(content)));
+    let entry = vec![
+        format!("{}{}:", indent, package),
+        format!("{}{}path: {}", indent, indent, path),
+    ];
+    set_entry(content, package, entry)
+}
+
+/// The lines of the override of `package`, to put back later with [`restore_override`].
+pub fn override_entry(content: &str, package: &str) -> Option<Vec<String>> {
+    let lines = split_lines(content);
+    let section = find_section(&lines)?;
+    let (_, span) = section.entries.iter().find(|(name, _)| name == package)?;
+    Some(lines[span.clone()].to_vec())
+}
+
+/// Put back the override of `package` as it was, or remove it if there was none.
+pub fn restore_override(content: &str, package: &str, entry: Option<&[String]>) -> Result<String> {
+    match entry {
+        Some(entry) => set_entry(content, package, entry.to_vec()),
+        None => Ok(remove_override(content, package)),
+    }
+}
+
+fn split_lines(content: &str) -> Vec<String> {
+    content.lines().map(|line| line.to_string()).collect()
+}
+
+fn set_entry(content: &str, package: &str, entry: Vec<String>) -> Result<String> {
+    let mut lines = split_lines(content);
+    match find_section(&lines) {
+        Some(section) => {
+            lines[section.header] = block_header(&lines[section.header])?;
+            match section.entries.iter().find(|(name, _)| name == package) {
+                Some((_, span)) => {
+                    lines.splice(span.clone(), entry);
+                }
+                None => {
+                    lines.splice(section.end..section.end, entry);
+                }
+            }
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(format!("{}:", SECTION));
+            lines.extend(entry);
+        }
+    }
+    Ok(join_lines(lines))
+}
+
+// An empty inline value like `dependency_overrides: {}` has to become a block. Other inline maps
+// would have to be rewritten, losing their formatting.
+fn block_header(header: &str) -> Result<String> {
+    let rest = &header[SECTION.len() + 1..];
+    let (value, comment) = match rest.find('#') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+    match value.trim() {
+        "" => Ok(header.to_string()),
+        "{}" | "~" | "null" if comment.is_empty() => Ok(format!("{}:", SECTION)),
+        "{}" | "~" | "null" => Ok(format!("{}: {}", SECTION, comment)),
+        _ => Err(eyre!(
+            "{} is written inline, move it to a block to edit it here",
+            SECTION
+        )),
+    }
+}
+
+/// Remove the override of `package`, and the section with it once it has no entries.
+pub fn remove_override(content: &str, package: &str) -> String {
+    let mut lines = split_lines(content);
+    let Some(section) = find_section(&lines) else {
+        return content.to_string();
+    };
+    let Some((_, span)) = section.entries.iter().find(|(name, _)| name == package) else {
+        return content.to_string();
+    };
+    if section.entries.len() == 1 {
+        // Keep comments in the section, they may be notes about other overrides.
+        let has_comments = lines[section.header + 1..section.end]
+            .iter()
+            .enumerate()
+            .any(|(index, line)| {
+                !span.contains(&(section.header + 1 + index)) && line.trim().starts_with('#')
+            });
+        if !has_comments {
+            let start = if section.header > 0 && lines[section.header - 1].trim().is_empty() {
+                section.header - 1
+            } else {
+                section.header
+            };
+            lines.drain(start..section.end);
+            return join_lines(lines);
+        }
+    }
+    lines.drain(span.clone());
+    join_lines(lines)
+}
+
+struct Section {
+    header: usize,
+    /// The index after the last entry.
+    end: usize,
+    entries: Vec<(String, std::ops::Range<usize>)>,
+}
+
+fn find_section(lines: &[String]) -> Option<Section> {
+    let header = lines.iter().position(|line| {
+        line.strip_prefix(SECTION)
+            .is_some_and(|rest| rest.starts_with(':'))
+    })?;
+    let mut section = Section {
+        header,
+        end: header + 1,
+        entries: vec![],
+    };
+    let mut entry_indent = None;
+    for (index, line) in lines.iter().enumerate().skip(header + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            break;
+        }
+        let entry_indent = *entry_indent.get_or_insert(indent);
+        if indent == entry_indent {
+            let name = trimmed.split(':').next().unwrap_or_default().trim();
+            section.entries.push((name.to_string(), index..index + 1));
+        } else if let Some((_, span)) = section.entries.last_mut() {
+            span.end = index + 1;
+        }
+        section.end = index + 1;
+    }
+    Some(section)
+}
+
+// The indentation the file uses for the entries of `dependencies`, two spaces by default.
+fn indent_width(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .skip_while(|line| !line.starts_with("dependencies:"))
+        .skip(1)
+        .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| line.len() - line.trim_start().len())
+        .filter(|width| *width > 0)
+        .unwrap_or(2)
+}
+
+fn join_lines(lines: Vec<String>) -> String {
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Local checkouts of `packages`, found next to the project and in `packages/` directories.
+pub fn find_candidates(project_root: &Path, packages: &[String]) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut search_dirs = vec![project_root.join("packages")];
+    if let Some(parent) = project_root.parent() {
+        search_dirs.push(parent.to_path_buf());
+        search_dirs.push(parent.join("packages"));
+    }
+    let mut candidates = BTreeMap::<String, Vec<PathBuf>>::new();
+    for dir in search_dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path == project_root || !path.is_dir() {
+                continue;
+            }
+            let Ok(pubspec) = Pubspec::load(&path) else {
+                continue;
+            };
+            if packages.contains(&pubspec.name) {
+                candidates.entry(pubspec.name).or_default().push(path);
+            }
+        }
+    }
+    candidates
+}
+
+/// `path` relative to `base`, as written in `path:` dependencies.
+pub fn relative_path(base: &Path, path: &Path) -> PathBuf {
+    let base = base.components().collect::<Vec<_>>();
+    let path_components = path.components().collect::<Vec<_>>();
+    let common = base
+        .iter()
+        .zip(&path_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path.to_path_buf();
+    }
+    base[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .chain(path_components[common..].iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBSPEC: &str = "name: app
+# Packages of the app.
+dependencies:
+    http: ^1.1.0 # keep in sync with the server
+    shared:
+        path: ../shared
+
+dependency_overrides:
+    # Until the fix is released.
+    meta: 1.10.0
+
+flutter:
+    uses-material-design: true
+";
+
+    #[test]
+    fn set_and_remove_path_override_keeps_formatting() {
+        let added = set_path_override(PUBSPEC, "http", "../http").unwrap();
+        assert_eq!(
+            added,
+            PUBSPEC.replace(
+                "    meta: 1.10.0\n",
+                "    meta: 1.10.0\n    http:\n        path: ../http\n"
+            )
+        );
+        let replaced = set_path_override(&added, "http", "../../http").unwrap();
+        assert!(replaced.contains("    http:\n        path: ../../http\n\nflutter:"));
+        assert_eq!(remove_override(&added, "http"), PUBSPEC);
+
+        let meta = override_entry(PUBSPEC, "meta").unwrap();
+        let replaced = set_path_override(PUBSPEC, "meta", "../meta").unwrap();
+        assert_eq!(
+            restore_override(&replaced, "meta", Some(&meta)).unwrap(),
+            PUBSPEC
+        );
+        assert_eq!(restore_override(&added, "http", None).unwrap(), PUBSPEC);
+    }
+
+    #[test]
+    fn override_section_is_added_and_removed() {
+        let pubspec = "name: app\ndependencies:\n  http: ^1.1.0\n";
+        let added = set_path_override(pubspec, "http", "../http").unwrap();
+        assert_eq!(
+            added,
+            "name: app\ndependencies:\n  http: ^1.1.0\n\ndependency_overrides:\n  http:\n    path: ../http\n"
+        );
+        assert_eq!(remove_override(&added, "http"), pubspec);
+    }
+
+    #[test]
+    fn override_header_keeps_comments_and_inline_maps() {
+        let pubspec = "name: app\ndependency_overrides: {} # local hacks\n";
+        assert_eq!(
+            set_path_override(pubspec, "http", "../http").unwrap(),
+            "name: app\ndependency_overrides: # local hacks\n  http:\n    path: ../http\n"
+        );
+        let pubspec = "name: app\ndependency_overrides: # local hacks\n  meta: 1.10.0\n";
+        assert_eq!(
+            set_path_override(pubspec, "http", "../http").unwrap(),
+            format!("{}  http:\n    path: ../http\n", pubspec)
+        );
+        let pubspec = "name: app\ndependency_overrides: {meta: 1.10.0}\n";
+        assert!(set_path_override(pubspec, "http", "../http").is_err());
+    }
+
+    #[test]
+    fn relative_path_between_directories() {
+        assert_eq!(
+            relative_path(Path::new("/work/app"), Path::new("/work/packages/http")),
+            PathBuf::from("../packages/http")
+        );
+    }
+}
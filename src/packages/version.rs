@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+
+/// A semantic version as used by pub, like `1.2.3-dev.1+4`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Dot separated pre-release identifiers. Build metadata is ignored.
+    pub pre_release: Vec<String>,
+}
+
+impl Version {
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.split('+').next()?;
+        let (release, pre_release) = match version.split_once('-') {
+            Some((release, pre_release)) => (
+                release,
+                pre_release.split('.').map(|s| s.to_string()).collect(),
+            ),
+            None => (version, vec![]),
+        };
+        let mut parts = release.split('.').map(|part| part.parse::<u64>().ok());
+        let version = Self {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+            pre_release,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(version)
+    }
+
+    /// The first version that is not compatible with this one, the upper bound of `^`.
+    fn next_breaking(&self) -> Self {
+        let (major, minor) = if self.major > 0 {
+            (self.major + 1, 0)
+        } else {
+            (0, self.minor + 1)
+        };
+        Self {
+            major,
+            minor,
+            patch: 0,
+            pre_release: vec![],
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| {
+                // A pre-release comes before the release itself.
+                match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => compare_identifiers(&self.pre_release, &other.pre_release),
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn compare_identifiers(a: &[String], b: &[String]) -> Ordering {
+    for (a, b) in a.iter().zip(b) {
+        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// A version constraint of `pubspec.yaml`, like `^1.2.0`, `>=1.0.0 <2.0.0` or `any`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConstraint {
+    bounds: Vec<(Comparison, Version)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Exact,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl VersionConstraint {
+    pub fn parse(constraint: &str) -> Option<Self> {
+        let constraint = constraint.trim();
+        if constraint == "any" {
+            return Some(Self { bounds: vec![] });
+        }
+        if let Some(version) = constraint.strip_prefix('^') {
+            let version = Version::parse(version.trim())?;
+            return Some(Self {
+                bounds: vec![
+                    (Comparison::Less, version.next_breaking()),
+                    (Comparison::GreaterOrEqual, version),
+                ],
+            });
+        }
+        let bounds = constraint
+            .split_whitespace()
+            .map(|bound| {
+                let (comparison, version) = [
+                    (">=", Comparison::GreaterOrEqual),
+                    ("<=", Comparison::LessOrEqual),
+                    (">", Comparison::Greater),
+                    ("<", Comparison::Less),
+                ]
+                .iter()
+                .find_map(|(prefix, comparison)| {
+                    bound
+                        .strip_prefix(prefix)
+                        .map(|version| (*comparison, version))
+                })
+                .unwrap_or((Comparison::Exact, bound));
+                Some((comparison, Version::parse(version)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { bounds })
+    }
+
+    pub fn allows(&self, version: &Version) -> bool {
+        self.bounds
+            .iter()
+            .all(|(comparison, bound)| match comparison {
+                Comparison::Exact => version == bound,
+                Comparison::Greater => version > bound,
+                Comparison::GreaterOrEqual => version >= bound,
+                Comparison::Less => version < bound,
+                Comparison::LessOrEqual => version <= bound,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(constraint: &str, version: &str) -> bool {
+        VersionConstraint::parse(constraint)
+            .unwrap()
+            .allows(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn constraints_allow_versions() {
+        assert!(allows("^1.2.0", "1.9.3"));
+        assert!(!allows("^1.2.0", "2.0.0"));
+        assert!(!allows("^1.2.0", "1.1.9"));
+        assert!(allows("^0.13.5", "0.13.6"));
+        assert!(!allows("^0.13.5", "0.14.0"));
+        assert!(allows(">=3.2.0 <4.0.0", "3.4.1"));
+        assert!(!allows(">=3.2.0 <4.0.0", "4.0.0"));
+        assert!(allows("any", "0.0.1"));
+        assert!(allows("1.0.0", "1.0.0+2"));
+        assert!(!allows("^1.0.0", "1.0.0-dev.1"));
+    }
+}
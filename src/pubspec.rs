@@ -0,0 +1,102 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Deserializer};
+
+/// Platforms a Flutter project can have a host directory for.
+pub const PLATFORMS: [&str; 6] = ["android", "ios", "linux", "macos", "web", "windows"];
+
+/// The parts of `pubspec.yaml` lazyflutter cares about.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct Pubspec {
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub environment: BTreeMap<String, String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub dependencies: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub dev_dependencies: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub dependency_overrides: BTreeMap<String, serde_yaml::Value>,
+}
+
+impl Pubspec {
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join("pubspec.yaml");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| eyre!("Could not read {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+}
+
+/// The platforms the project has a host directory for, such as `android/` or `web/`.
+pub fn platform_directories(project_root: &Path) -> Vec<String> {
+    PLATFORMS
+        .iter()
+        .filter(|platform| project_root.join(platform).is_dir())
+        .map(|platform| platform.to_string())
+        .collect()
+}
+
+/// The directory of the project, defaulting to the working directory.
+pub fn project_root(project_root: Option<&str>) -> PathBuf {
+    let root = PathBuf::from(project_root.unwrap_or("."));
+    std::fs::canonicalize(&root).unwrap_or(root)
+}
+
+// Keys written without a value, like `dependencies:`, are null in YAML.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pubspec() {
+        let pubspec = Pubspec::parse(
+            r#"
+name: counter
+description: A new Flutter project.
+publish_to: 'none'
+version: 1.0.0+1
+
+environment:
+  sdk: '>=3.2.0 <4.0.0'
+
+dependencies:
+  flutter:
+    sdk: flutter
+  cupertino_icons: ^1.0.2
+
+dev_dependencies:
+
+flutter:
+  uses-material-design: true
+"#,
+        )
+        .unwrap();
+        assert_eq!(pubspec.name, "counter");
+        assert_eq!(pubspec.version.as_deref(), Some("1.0.0+1"));
+        assert_eq!(pubspec.environment["sdk"], ">=3.2.0 <4.0.0");
+        assert_eq!(
+            pubspec.dependencies.keys().collect::<Vec<_>>(),
+            vec!["cupertino_icons", "flutter"]
+        );
+        assert!(pubspec.dev_dependencies.is_empty());
+    }
+}
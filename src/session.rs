@@ -1,2 +1,3 @@
+pub mod launch_configuration;
 pub mod session;
 pub mod session_manager;
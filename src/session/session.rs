@@ -1,12 +1,54 @@
-use crate::daemon::run::FlutterRun;
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use tokio::sync::RwLock;
+
+use super::launch_configuration::LaunchConfiguration;
+use crate::{
+    daemon::run::FlutterRun,
+    vm_service::{client::VmService, io::request::StreamId},
+};
 
 pub struct Session {
+    pub id: String,
+    pub launch_configuration: LaunchConfiguration,
     pub run: FlutterRun,
+    vm_service: RwLock<Option<Arc<VmService>>>,
 }
 
 impl Session {
-    pub fn new(project_root: Option<&str>, flavor: Option<&str>) -> Self {
-        let run = FlutterRun::new(project_root, flavor).unwrap();
-        Self { run }
+    pub fn new(id: String, launch_configuration: LaunchConfiguration) -> Result<Self> {
+        let run = FlutterRun::new(
+            launch_configuration.project_root.as_deref(),
+            launch_configuration.flavor.as_deref(),
+        )?;
+        Ok(Self {
+            id,
+            launch_configuration,
+            run,
+            vm_service: RwLock::new(None),
+        })
+    }
+
+    /// Connect to the VM service of the running app and listen to the streams shared by
+    /// all components.
+    pub async fn connect_vm_service(&self, ws_uri: &str) -> Result<Arc<VmService>> {
+        let vm_service = Arc::new(VmService::connect(ws_uri).await?);
+        for stream_id in [
+            StreamId::Isolate,
+            StreamId::Debug,
+            StreamId::Stdout,
+            StreamId::Logging,
+            StreamId::Extension,
+            StreamId::Gc,
+        ] {
+            vm_service.stream_listen(stream_id).await?;
+        }
+        *self.vm_service.write().await = Some(vm_service.clone());
+        Ok(vm_service)
+    }
+
+    pub async fn vm_service(&self) -> Option<Arc<VmService>> {
+        self.vm_service.read().await.clone()
     }
 }
@@ -1,12 +1,23 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
-use super::session::Session;
-use crate::daemon::flutter::FlutterDaemon;
-use color_eyre::eyre::Result;
+use super::{launch_configuration::LaunchConfiguration, session::Session};
+use crate::{dap::client::DebugAdapter, store::state::State, vm_service::client::VmService};
+use color_eyre::eyre::{eyre, Result};
+
+/// The VM service connection of the selected session along with its main isolate.
+#[derive(Clone)]
+pub struct SelectedIsolate {
+    pub session_id: String,
+    pub isolate_id: String,
+    pub vm_service: Arc<VmService>,
+}
 
 pub struct SessionManager {
     project_root: Option<String>,
-    pub sessions: Vec<Session>,
+    pub sessions: Vec<Arc<Session>>,
+    session_count: u32,
+    /// The app launched through `flutter debug_adapter` by the debugger panel.
+    pub debugger: Option<Arc<DebugAdapter>>,
 }
 
 impl SessionManager {
@@ -14,15 +25,58 @@ impl SessionManager {
         Self {
             sessions: vec![],
             project_root,
+            session_count: 0,
+            debugger: None,
         }
     }
 
-    pub fn run_new_app(&mut self) -> Result<()> {
-        let app = Session::new(
-            self.project_root.as_ref().map(|path| -> &str { &path }),
-            None,
-        );
-        self.sessions.push(app);
-        Ok(())
+    pub fn project_root(&self) -> Option<&str> {
+        self.project_root.as_deref()
+    }
+
+    pub fn run_new_app(&mut self) -> Result<Arc<Session>> {
+        self.session_count += 1;
+        let launch_configuration = LaunchConfiguration {
+            project_root: self.project_root.clone(),
+            flavor: None,
+        };
+        let session = Arc::new(Session::new(
+            format!("session-{}", self.session_count),
+            launch_configuration,
+        )?);
+        self.sessions.push(session.clone());
+        Ok(session)
+    }
+
+    /// Launch a fresh `flutter run` with the same launch configuration, replacing the
+    /// session with the same id. The previous session is returned so it can be stopped.
+    pub fn restart_session(&mut self, id: &str) -> Result<(Arc<Session>, Arc<Session>)> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or(eyre!("Session {} is not found", id))?;
+        let previous = self.sessions[index].clone();
+        let session = Arc::new(Session::new(
+            previous.id.clone(),
+            previous.launch_configuration.clone(),
+        )?);
+        self.sessions[index] = session.clone();
+        Ok((previous, session))
+    }
+
+    pub fn session(&self, id: &str) -> Option<Arc<Session>> {
+        self.sessions.iter().find(|s| s.id == id).cloned()
+    }
+
+    pub async fn selected_isolate(&self, state: &State) -> Option<SelectedIsolate> {
+        let session_state = state.selected_session()?;
+        let isolate = session_state.main_isolate()?;
+        let vm_service = self.session(&session_state.id)?.vm_service().await?;
+        Some(SelectedIsolate {
+            session_id: session_state.id.clone(),
+            isolate_id: isolate.id.clone(),
+            vm_service,
+        })
     }
 }
@@ -1,6 +1,271 @@
-use crate::daemon::io::device::Device;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{
+    analysis::Diagnostic,
+    daemon::io::device::Device,
+    dap::io::{Breakpoint, Scope, StackFrame, Variable},
+    debug_flags::DebugFlag,
+    packages::{
+        graph::DependencyGraph, license::PackageLicense, OutdatedPackage, PubspecLock, StaleReason,
+    },
+    pubspec::Pubspec,
+    store::state::{
+        DebuggerStatus, ExceptionPauseMode, LineBreakpoint, ReplEntry, ServiceExtensionCall,
+    },
+    test_runner::TestEvent,
+    vm_service::{
+        frames::FrameTiming,
+        io::{
+            diagnostics::DiagnosticsNode,
+            http_profile::{HttpProfile, HttpProfileRequest},
+            instance::InstanceRef,
+            response::{CpuSamples, IsolateRef, LibraryRef},
+        },
+        isolates::IsolateStatus,
+        memory::{AllocationSnapshot, MemorySample},
+        rebuilds::RebuildStats,
+        tree_dump::{DumpNode, TreeDump},
+    },
+};
 
 pub enum Action {
-    AddDevice { device: Device },
-    RemoveDevice { device: Device },
+    AddDevice {
+        device: Device,
+    },
+    RemoveDevice {
+        device: Device,
+    },
+    AddSession {
+        session_id: String,
+    },
+    RestartSession {
+        session_id: String,
+    },
+    NextSession,
+    PreviousSession,
+    StartApp {
+        session_id: String,
+        app_id: String,
+        device_id: String,
+    },
+    StartedApp {
+        session_id: String,
+    },
+    AppendLog {
+        session_id: String,
+        line: String,
+    },
+    ConnectVmService {
+        session_id: String,
+        ws_uri: String,
+    },
+    SetIsolates {
+        session_id: String,
+        isolates: Vec<IsolateRef>,
+    },
+    SetIsolateStatus {
+        session_id: String,
+        isolate_id: String,
+        status: IsolateStatus,
+    },
+    SetInspectorTree {
+        session_id: String,
+        root: DiagnosticsNode,
+    },
+    ClearInspectorTree {
+        session_id: String,
+    },
+    SelectInspectorNode {
+        session_id: String,
+        value_id: String,
+    },
+    SetInspectorProperties {
+        session_id: String,
+        properties: Vec<DiagnosticsNode>,
+    },
+    SetInspectorSelectMode {
+        session_id: String,
+        enabled: bool,
+    },
+    SetDebugFlag {
+        session_id: String,
+        flag: DebugFlag,
+        value: String,
+    },
+    SetServiceExtensions {
+        session_id: String,
+        extensions: Vec<String>,
+    },
+    AddServiceExtension {
+        session_id: String,
+        extension: String,
+    },
+    SetServiceExtensionCall {
+        session_id: String,
+        call: ServiceExtensionCall,
+    },
+    AppendFrames {
+        session_id: String,
+        frames: Vec<FrameTiming>,
+    },
+    ClearFrames {
+        session_id: String,
+    },
+    AddMemorySample {
+        session_id: String,
+        sample: MemorySample,
+    },
+    AddAllocationSnapshot {
+        session_id: String,
+        snapshot: AllocationSnapshot,
+    },
+    StartCpuRecording {
+        session_id: String,
+        since: i64,
+    },
+    SetCpuSamples {
+        session_id: String,
+        samples: CpuSamples,
+    },
+    StartTimelineRecording {
+        session_id: String,
+        since: i64,
+        previous_streams: Vec<String>,
+    },
+    StopTimelineRecording {
+        session_id: String,
+        until: i64,
+    },
+    SetHttpLogging {
+        session_id: String,
+        enabled: bool,
+    },
+    UpdateHttpProfile {
+        session_id: String,
+        profile: HttpProfile,
+    },
+    SetHttpRequestDetails {
+        session_id: String,
+        request: HttpProfileRequest,
+    },
+    ClearHttpRequests {
+        session_id: String,
+    },
+    SetReplLibrary {
+        session_id: String,
+        library: Option<LibraryRef>,
+    },
+    AddReplEntry {
+        session_id: String,
+        entry: ReplEntry,
+    },
+    SetReplObjectChildren {
+        session_id: String,
+        object_id: String,
+        children: Vec<(String, InstanceRef)>,
+    },
+    ClearRepl {
+        session_id: String,
+    },
+    SetTreeDump {
+        session_id: String,
+        kind: TreeDump,
+        nodes: Vec<DumpNode>,
+    },
+    SetRebuildTracking {
+        session_id: String,
+        enabled: bool,
+    },
+    AddRebuildStats {
+        session_id: String,
+        stats: Vec<RebuildStats>,
+    },
+    ClearRebuildCounts {
+        session_id: String,
+    },
+    SetDevToolsUrl {
+        url: String,
+    },
+    SetPubspec {
+        pubspec: Result<Pubspec, String>,
+        platform_directories: Vec<String>,
+    },
+    SetSupportedPlatforms {
+        platforms: Vec<String>,
+    },
+    SetStaleLock {
+        reason: Option<StaleReason>,
+    },
+    SetPubspecLock {
+        lock: Option<PubspecLock>,
+    },
+    SetOutdatedPackages {
+        packages: Option<Vec<OutdatedPackage>>,
+    },
+    SetDependencyGraph {
+        graph: DependencyGraph,
+        licenses: Vec<PackageLicense>,
+    },
+    SetLicenseReport {
+        path: PathBuf,
+    },
+    SetOverrideCandidates {
+        candidates: BTreeMap<String, Vec<PathBuf>>,
+    },
+    StartAnalysis,
+    FinishAnalysis {
+        result: Result<Vec<Diagnostic>, String>,
+    },
+    StartTestRun {
+        command_line: String,
+    },
+    AddTestEvents {
+        events: Vec<TestEvent>,
+    },
+    FinishTestRun {
+        error: Option<String>,
+    },
+    SetTestWatch {
+        is_watching: bool,
+    },
+    StartPubTask {
+        command_line: String,
+    },
+    AddPubTaskOutput {
+        line: String,
+    },
+    FinishPubTask {
+        error: Option<String>,
+    },
+    SetDebuggerStatus {
+        status: DebuggerStatus,
+    },
+    /// Replace the breakpoints of a file, removing it if there are none left.
+    SetBreakpoints {
+        path: String,
+        breakpoints: Vec<LineBreakpoint>,
+    },
+    UpdateBreakpoint {
+        breakpoint: Breakpoint,
+    },
+    SetExceptionPauseMode {
+        mode: ExceptionPauseMode,
+    },
+    SetStackFrames {
+        frames: Vec<StackFrame>,
+    },
+    SelectStackFrame {
+        frame_id: i64,
+        scopes: Vec<Scope>,
+    },
+    SetVariables {
+        variables_reference: i64,
+        variables: Vec<Variable>,
+    },
+    CollapseVariables {
+        variables_reference: i64,
+    },
+    AddDebuggerOutput {
+        output: String,
+    },
 }
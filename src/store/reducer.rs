@@ -1,6 +1,20 @@
-use crate::store::action::Action;
+use crate::{store::action::Action, test_runner::TestRun};
 
-use super::state::State;
+use super::state::{
+    AnalysisState, CpuProfileState, DebuggerState, DebuggerStatus, DependencyState, InspectorState,
+    LineBreakpoint, NetworkState, ProjectState, RebuildState, ReplState, SessionState, State,
+    TestState, TimelineState, TreeDumpState,
+};
+
+const MAX_SESSION_LOG_LINES: usize = 1000;
+const MAX_SESSION_FRAMES: usize = 600;
+const MAX_MEMORY_SAMPLES: usize = 300;
+// Two snapshots are enough to diff.
+const MAX_ALLOCATION_SNAPSHOTS: usize = 2;
+const MAX_HTTP_REQUESTS: usize = 500;
+const MAX_REPL_ENTRIES: usize = 200;
+const MAX_DEBUGGER_OUTPUT_LINES: usize = 1000;
+const MAX_PUB_TASK_LOG_LINES: usize = 1000;
 
 pub fn reducer(state: State, action: Action) -> State {
     match action {
@@ -12,5 +26,896 @@ pub fn reducer(state: State, action: Action) -> State {
             devices: state.devices.into_iter().filter(|d| d != &device).collect(),
             ..state
         },
+        Action::AddSession { session_id } => State {
+            sessions: [
+                state.sessions,
+                vec![SessionState {
+                    id: session_id.clone(),
+                    ..SessionState::default()
+                }],
+            ]
+            .concat(),
+            selected_session_id: Some(session_id),
+            ..state
+        },
+        Action::RestartSession { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                id: session.id,
+                logs: session.logs,
+                ..SessionState::default()
+            })
+        }
+        Action::NextSession => {
+            // Moving past the last session selects the "Run new app" entry.
+            let selected_session_id = selected_session_index(&state)
+                .and_then(|i| state.sessions.get(i + 1))
+                .map(|s| s.id.clone());
+            State {
+                selected_session_id,
+                ..state
+            }
+        }
+        Action::PreviousSession => {
+            let selected_session_id = match selected_session_index(&state) {
+                Some(i) => state.sessions.get(i.saturating_sub(1)),
+                None => state.sessions.last(),
+            }
+            .map(|s| s.id.clone());
+            State {
+                selected_session_id,
+                ..state
+            }
+        }
+        Action::StartApp {
+            session_id,
+            app_id,
+            device_id,
+        } => update_session(state, &session_id, |session| SessionState {
+            app_id: Some(app_id),
+            device_id: Some(device_id),
+            ..session
+        }),
+        Action::StartedApp { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                started: true,
+                ..session
+            })
+        }
+        Action::AppendLog { session_id, line } => update_session(state, &session_id, |session| {
+            let mut logs = session.logs;
+            logs.push(line);
+            if logs.len() > MAX_SESSION_LOG_LINES {
+                logs.drain(..logs.len() - MAX_SESSION_LOG_LINES);
+            }
+            SessionState { logs, ..session }
+        }),
+        Action::ConnectVmService { session_id, ws_uri } => {
+            update_session(state, &session_id, |session| SessionState {
+                vm_service_uri: Some(ws_uri),
+                ..session
+            })
+        }
+        Action::SetIsolates {
+            session_id,
+            isolates,
+        } => update_session(state, &session_id, |session| SessionState {
+            isolates,
+            ..session
+        }),
+        Action::SetIsolateStatus {
+            session_id,
+            isolate_id,
+            status,
+        } => update_session(state, &session_id, |mut session| {
+            session.isolate_statuses.insert(isolate_id, status);
+            session
+        }),
+        Action::SetInspectorTree { session_id, root } => {
+            update_session(state, &session_id, |session| SessionState {
+                inspector: InspectorState {
+                    root: Some(root),
+                    ..session.inspector
+                },
+                ..session
+            })
+        }
+        Action::SelectInspectorNode {
+            session_id,
+            value_id,
+        } => update_session(state, &session_id, |session| SessionState {
+            inspector: InspectorState {
+                selected_value_id: Some(value_id),
+                properties: vec![],
+                ..session.inspector
+            },
+            ..session
+        }),
+        Action::SetInspectorProperties {
+            session_id,
+            properties,
+        } => update_session(state, &session_id, |session| SessionState {
+            inspector: InspectorState {
+                properties,
+                ..session.inspector
+            },
+            ..session
+        }),
+        Action::ClearInspectorTree { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                inspector: InspectorState {
+                    select_mode: session.inspector.select_mode,
+                    ..InspectorState::default()
+                },
+                ..session
+            })
+        }
+        Action::SetInspectorSelectMode {
+            session_id,
+            enabled,
+        } => update_session(state, &session_id, |session| SessionState {
+            inspector: InspectorState {
+                select_mode: enabled,
+                ..session.inspector
+            },
+            ..session
+        }),
+        Action::SetDebugFlag {
+            session_id,
+            flag,
+            value,
+        } => update_session(state, &session_id, |mut session| {
+            session.debug_flags.insert(flag, value);
+            session
+        }),
+        Action::SetServiceExtensions {
+            session_id,
+            mut extensions,
+        } => update_session(state, &session_id, |session| {
+            extensions.sort();
+            extensions.dedup();
+            SessionState {
+                service_extensions: extensions,
+                ..session
+            }
+        }),
+        Action::AddServiceExtension {
+            session_id,
+            extension,
+        } => update_session(state, &session_id, |mut session| {
+            if let Err(index) = session.service_extensions.binary_search(&extension) {
+                session.service_extensions.insert(index, extension);
+            }
+            session
+        }),
+        Action::SetServiceExtensionCall { session_id, call } => {
+            update_session(state, &session_id, |session| SessionState {
+                service_extension_call: Some(call),
+                ..session
+            })
+        }
+        Action::AppendFrames { session_id, frames } => {
+            update_session(state, &session_id, |mut session| {
+                session.frames.extend(frames);
+                let overflow = session.frames.len().saturating_sub(MAX_SESSION_FRAMES);
+                session.frames.drain(..overflow);
+                session
+            })
+        }
+        Action::ClearFrames { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                frames: vec![],
+                ..session
+            })
+        }
+        Action::AddMemorySample { session_id, sample } => {
+            update_session(state, &session_id, |mut session| {
+                session.memory.samples.push(sample);
+                let overflow = session
+                    .memory
+                    .samples
+                    .len()
+                    .saturating_sub(MAX_MEMORY_SAMPLES);
+                session.memory.samples.drain(..overflow);
+                session
+            })
+        }
+        Action::AddAllocationSnapshot {
+            session_id,
+            snapshot,
+        } => update_session(state, &session_id, |mut session| {
+            session.memory.snapshots.push(snapshot);
+            let overflow = session
+                .memory
+                .snapshots
+                .len()
+                .saturating_sub(MAX_ALLOCATION_SNAPSHOTS);
+            session.memory.snapshots.drain(..overflow);
+            session
+        }),
+        Action::StartCpuRecording { session_id, since } => {
+            update_session(state, &session_id, |session| SessionState {
+                cpu_profile: CpuProfileState {
+                    recording_since: Some(since),
+                    ..session.cpu_profile
+                },
+                ..session
+            })
+        }
+        Action::SetCpuSamples {
+            session_id,
+            samples,
+        } => update_session(state, &session_id, |session| SessionState {
+            cpu_profile: CpuProfileState {
+                recording_since: None,
+                samples: Some(samples),
+            },
+            ..session
+        }),
+        Action::StartTimelineRecording {
+            session_id,
+            since,
+            previous_streams,
+        } => update_session(state, &session_id, |session| SessionState {
+            timeline: TimelineState {
+                recording_since: Some(since),
+                previous_streams,
+                ..session.timeline
+            },
+            ..session
+        }),
+        Action::StopTimelineRecording { session_id, until } => {
+            update_session(state, &session_id, |session| SessionState {
+                timeline: TimelineState {
+                    recording_since: None,
+                    previous_streams: vec![],
+                    recorded: session.timeline.recording_since.map(|since| (since, until)),
+                },
+                ..session
+            })
+        }
+        Action::SetHttpLogging {
+            session_id,
+            enabled,
+        } => update_session(state, &session_id, |session| SessionState {
+            network: NetworkState {
+                logging: enabled,
+                ..session.network
+            },
+            ..session
+        }),
+        Action::UpdateHttpProfile {
+            session_id,
+            profile,
+        } => update_session(state, &session_id, |mut session| {
+            let requests = &mut session.network.requests;
+            for request in profile.requests {
+                match requests.iter_mut().find(|r| r.id == request.id) {
+                    Some(existing) => *existing = request,
+                    None => requests.push(request),
+                }
+            }
+            requests.sort_by_key(|request| request.start_time);
+            let overflow = requests.len().saturating_sub(MAX_HTTP_REQUESTS);
+            requests.drain(..overflow);
+            session.network.updated_since = Some(profile.timestamp);
+            session
+        }),
+        Action::SetHttpRequestDetails {
+            session_id,
+            request,
+        } => update_session(state, &session_id, |session| SessionState {
+            network: NetworkState {
+                selected: Some(request),
+                ..session.network
+            },
+            ..session
+        }),
+        Action::ClearHttpRequests { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                network: NetworkState {
+                    logging: session.network.logging,
+                    updated_since: session.network.updated_since,
+                    ..NetworkState::default()
+                },
+                ..session
+            })
+        }
+        Action::SetReplLibrary {
+            session_id,
+            library,
+        } => update_session(state, &session_id, |session| SessionState {
+            repl: ReplState {
+                library,
+                ..session.repl
+            },
+            ..session
+        }),
+        Action::AddReplEntry { session_id, entry } => {
+            update_session(state, &session_id, |mut session| {
+                session.repl.entries.push(entry);
+                let overflow = session.repl.entries.len().saturating_sub(MAX_REPL_ENTRIES);
+                session.repl.entries.drain(..overflow);
+                session
+            })
+        }
+        Action::SetReplObjectChildren {
+            session_id,
+            object_id,
+            children,
+        } => update_session(state, &session_id, |mut session| {
+            session.repl.objects.insert(object_id, children);
+            session
+        }),
+        Action::ClearRepl { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                repl: ReplState {
+                    library: session.repl.library,
+                    ..ReplState::default()
+                },
+                ..session
+            })
+        }
+        Action::SetTreeDump {
+            session_id,
+            kind,
+            nodes,
+        } => update_session(state, &session_id, |session| SessionState {
+            tree_dump: TreeDumpState {
+                kind: Some(kind),
+                nodes,
+            },
+            ..session
+        }),
+        Action::SetRebuildTracking {
+            session_id,
+            enabled,
+        } => update_session(state, &session_id, |session| SessionState {
+            rebuilds: RebuildState {
+                tracking: enabled,
+                locations: session.rebuilds.locations,
+                ..RebuildState::default()
+            },
+            ..session
+        }),
+        Action::AddRebuildStats { session_id, stats } => {
+            update_session(state, &session_id, |mut session| {
+                let rebuilds = &mut session.rebuilds;
+                for frame in stats {
+                    rebuilds.locations.extend(frame.new_locations);
+                    for (id, count) in &frame.counts {
+                        *rebuilds.totals.entry(*id).or_default() += count;
+                    }
+                    rebuilds.last_frame = frame.counts.into_iter().collect();
+                    rebuilds.frames += 1;
+                }
+                session
+            })
+        }
+        Action::ClearRebuildCounts { session_id } => {
+            update_session(state, &session_id, |session| SessionState {
+                rebuilds: RebuildState {
+                    tracking: session.rebuilds.tracking,
+                    locations: session.rebuilds.locations,
+                    ..RebuildState::default()
+                },
+                ..session
+            })
+        }
+        Action::SetDevToolsUrl { url } => State {
+            devtools_url: Some(url),
+            ..state
+        },
+        Action::SetPubspec {
+            pubspec,
+            platform_directories,
+        } => {
+            let (pubspec, pubspec_error) = match pubspec {
+                Ok(pubspec) => (Some(pubspec), None),
+                Err(error) => (None, Some(error)),
+            };
+            State {
+                project: ProjectState {
+                    pubspec,
+                    pubspec_error,
+                    platform_directories,
+                    ..state.project
+                },
+                ..state
+            }
+        }
+        Action::SetSupportedPlatforms { platforms } => State {
+            project: ProjectState {
+                supported_platforms: Some(platforms),
+                ..state.project
+            },
+            ..state
+        },
+        Action::SetStaleLock { reason } => State {
+            project: ProjectState {
+                stale_lock: reason,
+                ..state.project
+            },
+            ..state
+        },
+        Action::SetPubspecLock { lock } => State {
+            dependencies: DependencyState {
+                lock,
+                ..state.dependencies
+            },
+            ..state
+        },
+        Action::SetOutdatedPackages { packages } => State {
+            dependencies: DependencyState {
+                outdated: packages,
+                ..state.dependencies
+            },
+            ..state
+        },
+        Action::SetDependencyGraph { graph, licenses } => State {
+            dependencies: DependencyState {
+                graph: Some(graph),
+                licenses,
+                ..state.dependencies
+            },
+            ..state
+        },
+        Action::SetLicenseReport { path } => State {
+            dependencies: DependencyState {
+                license_report: Some(path),
+                ..state.dependencies
+            },
+            ..state
+        },
+        Action::SetOverrideCandidates { candidates } => State {
+            dependencies: DependencyState {
+                override_candidates: candidates,
+                ..state.dependencies
+            },
+            ..state
+        },
+        Action::StartAnalysis => State {
+            analysis: AnalysisState {
+                is_running: true,
+                ..state.analysis
+            },
+            ..state
+        },
+        Action::FinishAnalysis { result } => {
+            let analysis = match result {
+                Ok(diagnostics) => AnalysisState {
+                    diagnostics: Some(diagnostics),
+                    is_running: false,
+                    error: None,
+                },
+                Err(error) => AnalysisState {
+                    is_running: false,
+                    error: Some(error),
+                    ..state.analysis
+                },
+            };
+            State { analysis, ..state }
+        }
+        Action::StartTestRun { command_line } => State {
+            tests: TestState {
+                run: Some(TestRun::new(command_line)),
+                error: None,
+                ..state.tests
+            },
+            ..state
+        },
+        Action::AddTestEvents { events } => {
+            let mut state = state;
+            if let Some(run) = &mut state.tests.run {
+                for event in events {
+                    run.apply(event);
+                }
+            }
+            state
+        }
+        Action::FinishTestRun { error } => {
+            let mut state = state;
+            if let Some(run) = &mut state.tests.run {
+                run.is_finished = true;
+            }
+            state.tests.error = error;
+            state
+        }
+        Action::SetTestWatch { is_watching } => State {
+            tests: TestState {
+                is_watching,
+                ..state.tests
+            },
+            ..state
+        },
+        Action::StartPubTask { command_line } => {
+            let mut state = state;
+            let dependencies = &mut state.dependencies;
+            dependencies.task_log.push(format!("$ {}", command_line));
+            dependencies.task = Some(command_line);
+            let overflow = dependencies
+                .task_log
+                .len()
+                .saturating_sub(MAX_PUB_TASK_LOG_LINES);
+            dependencies.task_log.drain(..overflow);
+            state
+        }
+        Action::AddPubTaskOutput { line } => {
+            let mut state = state;
+            let lines = &mut state.dependencies.task_log;
+            lines.push(line);
+            let overflow = lines.len().saturating_sub(MAX_PUB_TASK_LOG_LINES);
+            lines.drain(..overflow);
+            state
+        }
+        Action::FinishPubTask { error } => {
+            let mut state = state;
+            let dependencies = &mut state.dependencies;
+            dependencies.task = None;
+            dependencies
+                .task_log
+                .push(error.unwrap_or("Done".to_string()));
+            state
+        }
+        Action::SetDebuggerStatus { status } => {
+            let debugger = if matches!(status, DebuggerStatus::Paused { .. }) {
+                DebuggerState {
+                    status,
+                    ..state.debugger
+                }
+            } else {
+                // Frames and variables are only valid while paused.
+                DebuggerState {
+                    status,
+                    breakpoints: state.debugger.breakpoints,
+                    exception_pause_mode: state.debugger.exception_pause_mode,
+                    output: state.debugger.output,
+                    ..DebuggerState::default()
+                }
+            };
+            State { debugger, ..state }
+        }
+        Action::SetBreakpoints { path, breakpoints } => {
+            let mut state = state;
+            if breakpoints.is_empty() {
+                state.debugger.breakpoints.remove(&path);
+            } else {
+                state.debugger.breakpoints.insert(path, breakpoints);
+            }
+            state
+        }
+        Action::UpdateBreakpoint { breakpoint } => {
+            let mut state = state;
+            let existing = state
+                .debugger
+                .breakpoints
+                .values_mut()
+                .flatten()
+                .find(|b| b.id.is_some() && b.id == breakpoint.id);
+            if let Some(existing) = existing {
+                *existing = LineBreakpoint {
+                    line: breakpoint.line.unwrap_or(existing.line),
+                    id: breakpoint.id,
+                    verified: breakpoint.verified,
+                };
+            }
+            state
+        }
+        Action::SetExceptionPauseMode { mode } => State {
+            debugger: DebuggerState {
+                exception_pause_mode: mode,
+                ..state.debugger
+            },
+            ..state
+        },
+        Action::SetStackFrames { frames } => State {
+            debugger: DebuggerState {
+                stack_frames: frames,
+                ..state.debugger
+            },
+            ..state
+        },
+        Action::SelectStackFrame { frame_id, scopes } => State {
+            debugger: DebuggerState {
+                selected_frame_id: Some(frame_id),
+                scopes,
+                variables: Default::default(),
+                ..state.debugger
+            },
+            ..state
+        },
+        Action::SetVariables {
+            variables_reference,
+            variables,
+        } => {
+            let mut state = state;
+            state
+                .debugger
+                .variables
+                .insert(variables_reference, variables);
+            state
+        }
+        Action::CollapseVariables {
+            variables_reference,
+        } => {
+            let mut state = state;
+            state.debugger.variables.remove(&variables_reference);
+            state
+        }
+        Action::AddDebuggerOutput { output } => {
+            let mut state = state;
+            let lines = &mut state.debugger.output;
+            lines.extend(output.lines().map(|line| line.to_string()));
+            let overflow = lines.len().saturating_sub(MAX_DEBUGGER_OUTPUT_LINES);
+            lines.drain(..overflow);
+            state
+        }
+    }
+}
+
+fn selected_session_index(state: &State) -> Option<usize> {
+    let id = state.selected_session_id.as_ref()?;
+    state.sessions.iter().position(|s| &s.id == id)
+}
+
+fn update_session<F>(mut state: State, session_id: &str, f: F) -> State
+where
+    F: FnOnce(SessionState) -> SessionState,
+{
+    if let Some(index) = state.sessions.iter().position(|s| s.id == session_id) {
+        let session = state.sessions.remove(index);
+        state.sessions.insert(index, f(session));
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_service::io::http_profile::{HttpProfile, HttpProfileRequest};
+
+    fn state_with_sessions(ids: &[&str]) -> State {
+        ids.iter().fold(State::default(), |state, id| {
+            reducer(
+                state,
+                Action::AddSession {
+                    session_id: id.to_string(),
+                },
+            )
+        })
+    }
+
+    #[test]
+    fn add_session_selects_it() {
+        let state = state_with_sessions(&["session-1", "session-2"]);
+        assert_eq!(state.sessions.len(), 2);
+        assert_eq!(state.selected_session_id, Some("session-2".to_string()));
+    }
+
+    #[test]
+    fn navigate_sessions() {
+        let state = state_with_sessions(&["session-1", "session-2"]);
+        let state = reducer(state, Action::NextSession);
+        assert_eq!(state.selected_session_id, None);
+        let state = reducer(state, Action::PreviousSession);
+        assert_eq!(state.selected_session_id, Some("session-2".to_string()));
+        let state = reducer(state, Action::PreviousSession);
+        assert_eq!(state.selected_session_id, Some("session-1".to_string()));
+        let state = reducer(state, Action::PreviousSession);
+        assert_eq!(state.selected_session_id, Some("session-1".to_string()));
+        let state = reducer(state, Action::NextSession);
+        assert_eq!(state.selected_session_id, Some("session-2".to_string()));
+    }
+
+    #[test]
+    fn append_log_is_bounded() {
+        let mut state = state_with_sessions(&["session-1"]);
+        for i in 0..MAX_SESSION_LOG_LINES + 1 {
+            state = reducer(
+                state,
+                Action::AppendLog {
+                    session_id: "session-1".to_string(),
+                    line: i.to_string(),
+                },
+            );
+        }
+        let logs = &state.sessions[0].logs;
+        assert_eq!(logs.len(), MAX_SESSION_LOG_LINES);
+        assert_eq!(logs.first(), Some(&"1".to_string()));
+    }
+
+    #[test]
+    fn timeline_recording_keeps_recorded_range() {
+        let state = state_with_sessions(&["session-1"]);
+        let state = reducer(
+            state,
+            Action::StartTimelineRecording {
+                session_id: "session-1".to_string(),
+                since: 100,
+                previous_streams: vec!["Dart".to_string()],
+            },
+        );
+        assert_eq!(state.sessions[0].timeline.recording_since, Some(100));
+        assert_eq!(state.sessions[0].timeline.previous_streams, vec!["Dart"]);
+        let state = reducer(
+            state,
+            Action::StopTimelineRecording {
+                session_id: "session-1".to_string(),
+                until: 250,
+            },
+        );
+        assert_eq!(state.sessions[0].timeline.recording_since, None);
+        assert!(state.sessions[0].timeline.previous_streams.is_empty());
+        assert_eq!(state.sessions[0].timeline.recorded, Some((100, 250)));
+    }
+
+    #[test]
+    fn http_profile_updates_are_merged() {
+        let request = |id: &str, start_time: i64, end_time: Option<i64>| HttpProfileRequest {
+            id: id.to_string(),
+            method: "GET".to_string(),
+            uri: format!("https://example.com/{}", id),
+            start_time,
+            end_time,
+            request: None,
+            response: None,
+            request_body: None,
+            response_body: None,
+        };
+        let update = |state, timestamp, requests| {
+            reducer(
+                state,
+                Action::UpdateHttpProfile {
+                    session_id: "session-1".to_string(),
+                    profile: HttpProfile {
+                        timestamp,
+                        requests,
+                    },
+                },
+            )
+        };
+        let state = state_with_sessions(&["session-1"]);
+        let state = update(
+            state,
+            10,
+            vec![request("2", 5, None), request("1", 1, None)],
+        );
+        let state = update(state, 20, vec![request("2", 5, Some(15))]);
+        let network = &state.sessions[0].network;
+        assert_eq!(network.updated_since, Some(20));
+        let requests = network
+            .requests
+            .iter()
+            .map(|r| (r.id.as_str(), r.end_time))
+            .collect::<Vec<_>>();
+        assert_eq!(requests, vec![("1", None), ("2", Some(15))]);
+    }
+
+    #[test]
+    fn service_extensions_are_sorted_and_unique() {
+        let state = state_with_sessions(&["session-1"]);
+        let state = reducer(
+            state,
+            Action::SetServiceExtensions {
+                session_id: "session-1".to_string(),
+                extensions: vec![
+                    "ext.flutter.reassemble".to_string(),
+                    "ext.flutter.debugPaint".to_string(),
+                    "ext.flutter.reassemble".to_string(),
+                ],
+            },
+        );
+        let state = reducer(
+            state,
+            Action::AddServiceExtension {
+                session_id: "session-1".to_string(),
+                extension: "ext.app.reset".to_string(),
+            },
+        );
+        let state = reducer(
+            state,
+            Action::AddServiceExtension {
+                session_id: "session-1".to_string(),
+                extension: "ext.app.reset".to_string(),
+            },
+        );
+        assert_eq!(
+            state.sessions[0].service_extensions,
+            vec![
+                "ext.app.reset".to_string(),
+                "ext.flutter.debugPaint".to_string(),
+                "ext.flutter.reassemble".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn debugger_resume_keeps_breakpoints_only() {
+        use crate::dap::io::StackFrame;
+
+        let state = reducer(
+            State::default(),
+            Action::SetBreakpoints {
+                path: "/app/lib/main.dart".to_string(),
+                breakpoints: vec![LineBreakpoint {
+                    line: 12,
+                    id: Some(1),
+                    verified: false,
+                }],
+            },
+        );
+        let state = reducer(
+            state,
+            Action::SetDebuggerStatus {
+                status: DebuggerStatus::Paused {
+                    thread_id: 1,
+                    reason: "breakpoint".to_string(),
+                },
+            },
+        );
+        let state = reducer(
+            state,
+            Action::SetStackFrames {
+                frames: vec![StackFrame {
+                    id: 1,
+                    name: "main".to_string(),
+                    source: None,
+                    line: 12,
+                    column: 1,
+                }],
+            },
+        );
+        assert_eq!(state.debugger.stack_frames.len(), 1);
+        let state = reducer(
+            state,
+            Action::SetDebuggerStatus {
+                status: DebuggerStatus::Running,
+            },
+        );
+        assert!(state.debugger.stack_frames.is_empty());
+        assert_eq!(state.debugger.breakpoints["/app/lib/main.dart"][0].line, 12);
+    }
+
+    #[test]
+    fn rebuild_counts_accumulate_across_frames() {
+        use crate::vm_service::rebuilds::{RebuildStats, WidgetLocation};
+
+        let state = state_with_sessions(&["session-1"]);
+        let location = WidgetLocation {
+            file: "file:///app/lib/main.dart".to_string(),
+            line: 10,
+            column: 5,
+            name: Some("Text".to_string()),
+        };
+        let state = reducer(
+            state,
+            Action::AddRebuildStats {
+                session_id: "session-1".to_string(),
+                stats: vec![
+                    RebuildStats {
+                        counts: vec![(1, 2)],
+                        new_locations: vec![(1, location.clone())],
+                    },
+                    RebuildStats {
+                        counts: vec![(1, 3)],
+                        new_locations: vec![],
+                    },
+                ],
+            },
+        );
+        let rebuilds = &state.sessions[0].rebuilds;
+        assert_eq!(rebuilds.frames, 2);
+        assert_eq!(rebuilds.totals[&1], 5);
+        assert_eq!(rebuilds.last_frame[&1], 3);
+
+        // Locations are only sent once, so they outlive the counts.
+        let state = reducer(
+            state,
+            Action::ClearRebuildCounts {
+                session_id: "session-1".to_string(),
+            },
+        );
+        let rebuilds = &state.sessions[0].rebuilds;
+        assert!(rebuilds.totals.is_empty());
+        assert_eq!(rebuilds.locations[&1], location);
     }
 }
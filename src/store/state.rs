@@ -1,6 +1,279 @@
-use crate::daemon::io::device::Device;
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde_json::Value;
+
+use crate::{
+    analysis::Diagnostic,
+    daemon::io::device::Device,
+    dap::io::{Scope, StackFrame, Variable},
+    debug_flags::DebugFlag,
+    packages::{
+        graph::DependencyGraph, license::PackageLicense, OutdatedPackage, PubspecLock, StaleReason,
+    },
+    pubspec::Pubspec,
+    test_runner::TestRun,
+    vm_service::{
+        frames::FrameTiming,
+        io::{
+            diagnostics::DiagnosticsNode,
+            http_profile::HttpProfileRequest,
+            instance::InstanceRef,
+            response::{CpuSamples, IsolateRef, LibraryRef},
+        },
+        isolates::IsolateStatus,
+        memory::{AllocationSnapshot, MemorySample},
+        rebuilds::WidgetLocation,
+        tree_dump::{DumpNode, TreeDump},
+    },
+};
 
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct State {
     pub devices: Vec<Device>,
+    pub sessions: Vec<SessionState>,
+    pub selected_session_id: Option<String>,
+    /// Address of the DevTools server started by the daemon.
+    pub devtools_url: Option<String>,
+    pub debugger: DebuggerState,
+    pub project: ProjectState,
+    pub dependencies: DependencyState,
+    pub analysis: AnalysisState,
+    pub tests: TestState,
+}
+
+impl State {
+    pub fn selected_session(&self) -> Option<&SessionState> {
+        let id = self.selected_session_id.as_ref()?;
+        self.sessions.iter().find(|s| &s.id == id)
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct ProjectState {
+    pub pubspec: Option<Pubspec>,
+    /// Why `pubspec.yaml` could not be read.
+    pub pubspec_error: Option<String>,
+    pub platform_directories: Vec<String>,
+    /// Platforms reported by the daemon, `None` until it answers.
+    pub supported_platforms: Option<Vec<String>>,
+    /// Why `pub get` has to run before launching, if it has to.
+    pub stale_lock: Option<StaleReason>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct DependencyState {
+    pub lock: Option<PubspecLock>,
+    /// The report of `flutter pub outdated`, `None` until it finishes.
+    pub outdated: Option<Vec<OutdatedPackage>>,
+    /// The command line of the running pub command.
+    pub task: Option<String>,
+    pub task_log: Vec<String>,
+    pub graph: Option<DependencyGraph>,
+    pub licenses: Vec<PackageLicense>,
+    /// Where the license report was last exported to.
+    pub license_report: Option<PathBuf>,
+    /// Local checkouts that can override each package.
+    pub override_candidates: BTreeMap<String, Vec<PathBuf>>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct AnalysisState {
+    /// The result of the last `dart analyze`, `None` until it finishes.
+    pub diagnostics: Option<Vec<Diagnostic>>,
+    pub is_running: bool,
+    /// Why the last run failed. The diagnostics of the run before are kept.
+    pub error: Option<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct TestState {
+    /// The latest `flutter test` run, kept after it finishes.
+    pub run: Option<TestRun>,
+    /// Why the latest run ended without a report.
+    pub error: Option<String>,
+    /// Whether tests rerun when files under `lib/` or `test/` change.
+    pub is_watching: bool,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct SessionState {
+    pub id: String,
+    pub app_id: Option<String>,
+    pub device_id: Option<String>,
+    pub started: bool,
+    pub logs: Vec<String>,
+    pub vm_service_uri: Option<String>,
+    pub isolates: Vec<IsolateRef>,
+    /// Whether each isolate is running or paused, by isolate id.
+    pub isolate_statuses: BTreeMap<String, IsolateStatus>,
+    pub inspector: InspectorState,
+    pub debug_flags: BTreeMap<DebugFlag, String>,
+    pub service_extensions: Vec<String>,
+    pub service_extension_call: Option<ServiceExtensionCall>,
+    pub frames: Vec<FrameTiming>,
+    pub memory: MemoryState,
+    pub cpu_profile: CpuProfileState,
+    pub timeline: TimelineState,
+    pub network: NetworkState,
+    pub repl: ReplState,
+    pub tree_dump: TreeDumpState,
+    pub rebuilds: RebuildState,
+}
+
+impl SessionState {
+    /// The isolate running the Flutter app, which service extensions are registered on.
+    pub fn main_isolate(&self) -> Option<&IsolateRef> {
+        self.isolates.iter().find(|i| !i.is_system_isolate)
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct InspectorState {
+    pub root: Option<DiagnosticsNode>,
+    pub selected_value_id: Option<String>,
+    pub properties: Vec<DiagnosticsNode>,
+    pub select_mode: bool,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct MemoryState {
+    pub samples: Vec<MemorySample>,
+    /// The latest allocation snapshots, oldest first.
+    pub snapshots: Vec<AllocationSnapshot>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct CpuProfileState {
+    /// VM timeline timestamp the running recording started at.
+    pub recording_since: Option<i64>,
+    pub samples: Option<CpuSamples>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct TimelineState {
+    /// VM timeline timestamp the running recording started at.
+    pub recording_since: Option<i64>,
+    /// Streams recorded before the running recording, restored when it stops.
+    pub previous_streams: Vec<String>,
+    /// Start and end of the last finished recording.
+    pub recorded: Option<(i64, i64)>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct NetworkState {
+    pub logging: bool,
+    /// Requests ordered by start time.
+    pub requests: Vec<HttpProfileRequest>,
+    /// Timestamp of the latest HTTP profile, to only fetch requests updated since.
+    pub updated_since: Option<i64>,
+    /// The selected request with its bodies.
+    pub selected: Option<HttpProfileRequest>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct ReplState {
+    /// The library expressions are evaluated in, or the root library if not set.
+    pub library: Option<LibraryRef>,
+    pub entries: Vec<ReplEntry>,
+    /// Labelled fields, elements and entries of inspected objects by object id.
+    pub objects: BTreeMap<String, Vec<(String, InstanceRef)>>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct TreeDumpState {
+    pub kind: Option<TreeDump>,
+    pub nodes: Vec<DumpNode>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct RebuildState {
+    pub tracking: bool,
+    /// Source locations of rebuilt widgets by location id, which the app only sends once.
+    pub locations: BTreeMap<i64, WidgetLocation>,
+    /// Rebuilds during the latest frame by location id.
+    pub last_frame: BTreeMap<i64, i64>,
+    /// Rebuilds since tracking started by location id.
+    pub totals: BTreeMap<i64, i64>,
+    pub frames: u64,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReplEntry {
+    pub expression: String,
+    pub result: Result<InstanceRef, String>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ServiceExtensionCall {
+    pub method: String,
+    pub result: Result<Value, String>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct DebuggerState {
+    pub status: DebuggerStatus,
+    /// Line breakpoints by absolute file path, kept across debug sessions.
+    pub breakpoints: BTreeMap<String, Vec<LineBreakpoint>>,
+    pub exception_pause_mode: ExceptionPauseMode,
+    pub stack_frames: Vec<StackFrame>,
+    pub selected_frame_id: Option<i64>,
+    pub scopes: Vec<Scope>,
+    /// Children of expanded scopes and variables by variables reference.
+    pub variables: BTreeMap<i64, Vec<Variable>>,
+    pub output: Vec<String>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq)]
+pub enum DebuggerStatus {
+    #[default]
+    NotStarted,
+    Running,
+    Paused {
+        thread_id: i64,
+        reason: String,
+    },
+    Terminated,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct LineBreakpoint {
+    pub line: i64,
+    /// Set by the debug adapter, to match breakpoint events.
+    pub id: Option<i64>,
+    pub verified: bool,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionPauseMode {
+    None,
+    #[default]
+    Unhandled,
+    All,
+}
+
+impl ExceptionPauseMode {
+    /// The `setExceptionBreakpoints` filters of the Dart debug adapter.
+    pub fn filters(&self) -> &'static [&'static str] {
+        match self {
+            ExceptionPauseMode::None => &[],
+            ExceptionPauseMode::Unhandled => &["Unhandled"],
+            ExceptionPauseMode::All => &["All"],
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ExceptionPauseMode::None => ExceptionPauseMode::Unhandled,
+            ExceptionPauseMode::Unhandled => ExceptionPauseMode::All,
+            ExceptionPauseMode::All => ExceptionPauseMode::None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExceptionPauseMode::None => "none",
+            ExceptionPauseMode::Unhandled => "unhandled",
+            ExceptionPauseMode::All => "all",
+        }
+    }
 }
@@ -0,0 +1,460 @@
+pub mod watch;
+
+use std::{collections::BTreeMap, path::Path, process::Stdio};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    sync::mpsc::UnboundedSender,
+};
+
+/// The Flutter executable tests run with. `LAZYFLUTTER_FLUTTER` can point to another one, like
+/// a script replaying a recorded report.
+pub fn flutter_executable() -> String {
+    std::env::var("LAZYFLUTTER_FLUTTER").unwrap_or("flutter".to_string())
+}
+
+/// An event of the JSON reporter of `flutter test --machine`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TestEvent {
+    Suite {
+        suite: SuiteInfo,
+    },
+    Group {
+        group: GroupInfo,
+    },
+    TestStart {
+        test: TestInfo,
+        time: u64,
+    },
+    Print {
+        #[serde(rename = "testID")]
+        test_id: u64,
+        message: String,
+    },
+    Error {
+        #[serde(rename = "testID")]
+        test_id: u64,
+        error: String,
+        #[serde(rename = "stackTrace")]
+        stack_trace: String,
+        #[serde(rename = "isFailure")]
+        is_failure: bool,
+    },
+    TestDone {
+        #[serde(rename = "testID")]
+        test_id: u64,
+        result: String,
+        hidden: bool,
+        skipped: bool,
+        time: u64,
+    },
+    Done {
+        success: Option<bool>,
+        time: u64,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SuiteInfo {
+    pub id: u64,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GroupInfo {
+    pub id: u64,
+    #[serde(rename = "suiteID")]
+    pub suite_id: u64,
+    #[serde(rename = "parentID")]
+    pub parent_id: Option<u64>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TestInfo {
+    pub id: u64,
+    pub name: String,
+    #[serde(rename = "suiteID")]
+    pub suite_id: u64,
+    #[serde(rename = "groupIDs", default)]
+    pub group_ids: Vec<u64>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Where the test is declared when it is defined by a helper in another file.
+    pub root_line: Option<usize>,
+    pub root_column: Option<usize>,
+}
+
+/// Flutter prints a few lines that are not events, like "Running "flutter pub get"...".
+pub fn parse_event(line: &str) -> Option<TestEvent> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    serde_json::from_str(line).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestStatus {
+    Running,
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestError {
+    pub message: String,
+    pub stack_trace: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub info: TestInfo,
+    pub status: TestStatus,
+    /// Whether the reporter hides it, like the test loading a suite when the suite loads.
+    pub hidden: bool,
+    pub start_time: u64,
+    pub duration: Option<u64>,
+    pub errors: Vec<TestError>,
+    pub output: Vec<String>,
+}
+
+/// The suites, groups and tests of one `flutter test` run, as reported so far.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TestRun {
+    pub command_line: String,
+    /// Paths of the suites, by id.
+    pub suites: BTreeMap<u64, String>,
+    pub groups: BTreeMap<u64, GroupInfo>,
+    pub tests: BTreeMap<u64, TestCase>,
+    /// Whether every test passed, `None` until the run is done.
+    pub success: Option<bool>,
+    pub duration: Option<u64>,
+    pub is_finished: bool,
+}
+
+impl TestRun {
+    pub fn new(command_line: String) -> Self {
+        Self {
+            command_line,
+            ..Self::default()
+        }
+    }
+
+    pub fn apply(&mut self, event: TestEvent) {
+        match event {
+            TestEvent::Suite { suite } => {
+                self.suites.insert(suite.id, suite.path.unwrap_or_default());
+            }
+            TestEvent::Group { group } => {
+                self.groups.insert(group.id, group);
+            }
+            TestEvent::TestStart { test, time } => {
+                let test = TestCase {
+                    info: test,
+                    status: TestStatus::Running,
+                    hidden: false,
+                    start_time: time,
+                    duration: None,
+                    errors: vec![],
+                    output: vec![],
+                };
+                self.tests.insert(test.info.id, test);
+            }
+            TestEvent::Print { test_id, message } => {
+                if let Some(test) = self.tests.get_mut(&test_id) {
+                    test.output.push(message);
+                }
+            }
+            TestEvent::Error {
+                test_id,
+                error,
+                stack_trace,
+                ..
+            } => {
+                if let Some(test) = self.tests.get_mut(&test_id) {
+                    test.errors.push(TestError {
+                        message: error,
+                        stack_trace,
+                    });
+                }
+            }
+            TestEvent::TestDone {
+                test_id,
+                result,
+                hidden,
+                skipped,
+                time,
+            } => {
+                if let Some(test) = self.tests.get_mut(&test_id) {
+                    test.status = match result.as_str() {
+                        "success" if skipped => TestStatus::Skipped,
+                        "success" => TestStatus::Passed,
+                        _ => TestStatus::Failed,
+                    };
+                    test.hidden = hidden;
+                    test.duration = Some(time.saturating_sub(test.start_time));
+                }
+            }
+            TestEvent::Done { success, time } => {
+                self.success = success;
+                self.duration = Some(time);
+            }
+            TestEvent::Other => {}
+        }
+    }
+
+    /// Tests worth showing, leaving out the hidden ones unless they failed.
+    pub fn visible_tests(&self) -> impl Iterator<Item = &TestCase> {
+        self.tests
+            .values()
+            .filter(|test| !test.hidden || test.status == TestStatus::Failed)
+    }
+
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.visible_tests()
+            .filter(|test| test.status == status)
+            .count()
+    }
+
+    /// The failed tests, as a target to run them again.
+    pub fn failures(&self) -> Option<TestTarget> {
+        let failed = self
+            .visible_tests()
+            .filter(|test| test.status == TestStatus::Failed)
+            .collect::<Vec<_>>();
+        if failed.is_empty() {
+            return None;
+        }
+        let mut files = vec![];
+        let mut names = vec![];
+        for test in failed {
+            let path = self.suites.get(&test.info.suite_id).cloned();
+            if let Some(path) = path.filter(|path| !files.contains(path)) {
+                files.push(path);
+            }
+            // A suite that failed to load has to run as a whole.
+            if test.hidden {
+                continue;
+            }
+            names.push(test.info.name.clone());
+        }
+        if names.is_empty() {
+            return Some(TestTarget::Files(files));
+        }
+        Some(TestTarget::Tests { files, names })
+    }
+}
+
+/// Which tests to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestTarget {
+    All,
+    Files(Vec<String>),
+    /// Tests of `files` named exactly one of `names`.
+    Tests {
+        files: Vec<String>,
+        names: Vec<String>,
+    },
+    /// Tests of `files` in a group named one of `names`.
+    Groups {
+        files: Vec<String>,
+        names: Vec<String>,
+    },
+}
+
+impl TestTarget {
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec!["test".to_string(), "--machine".to_string()];
+        match self {
+            TestTarget::All => {}
+            TestTarget::Files(files) => args.extend(files.iter().cloned()),
+            TestTarget::Tests { files, names } => {
+                args.push("--name".to_string());
+                args.push(format!("^(?:{})$", name_pattern(names)));
+                args.extend(files.iter().cloned());
+            }
+            // Test names start with the names of their groups and a space.
+            TestTarget::Groups { files, names } => {
+                args.push("--name".to_string());
+                args.push(format!("^(?:{})( |$)", name_pattern(names)));
+                args.extend(files.iter().cloned());
+            }
+        }
+        args
+    }
+
+    pub fn command_line(&self) -> String {
+        format!("flutter {}", self.args().join(" "))
+    }
+}
+
+fn name_pattern(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| regex::escape(name))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Run tests with `program`, sending every reporter event. Failing tests are not an error, only
+/// a run that ends without a report is.
+pub async fn run(
+    program: &str,
+    project_root: &Path,
+    target: &TestTarget,
+    events: UnboundedSender<TestEvent>,
+) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(target.args())
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or(eyre!("Stdout is not available"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or(eyre!("Stderr is not available"))?;
+    let stderr = tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        let mut output = vec![];
+        while let Ok(Some(line)) = lines.next_line().await {
+            output.push(line);
+        }
+        output.join("\n")
+    });
+    let mut is_done = false;
+    let mut lines = BufReader::new(stdout).lines();
+    while let Some(line) = lines.next_line().await? {
+        if let Some(event) = parse_event(&line) {
+            is_done |= matches!(event, TestEvent::Done { .. });
+            let _ = events.send(event);
+        }
+    }
+    let status = child.wait().await?;
+    let stderr = stderr.await.unwrap_or_default();
+    if !is_done {
+        return Err(eyre!(
+            "{} exited with {}: {}",
+            target.command_line(),
+            status,
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = r#"{"protocolVersion":"0.1.1","runnerVersion":"1.24.9","pid":1,"type":"start","time":0}
+{"suite":{"id":0,"platform":"vm","path":"/app/test/counter_test.dart"},"type":"suite","time":0}
+{"test":{"id":1,"name":"loading /app/test/counter_test.dart","suiteID":0,"groupIDs":[],"metadata":{"skip":false,"skipReason":null},"line":null,"column":null,"url":null},"type":"testStart","time":1}
+{"testID":1,"result":"success","skipped":false,"hidden":true,"type":"testDone","time":400}
+{"group":{"id":2,"suiteID":0,"parentID":null,"name":"","metadata":{"skip":false,"skipReason":null},"testCount":3,"line":null,"column":null,"url":null},"type":"group","time":401}
+{"group":{"id":3,"suiteID":0,"parentID":2,"name":"Counter","metadata":{"skip":false,"skipReason":null},"testCount":3,"line":5,"column":3,"url":"file:///app/test/counter_test.dart"},"type":"group","time":401}
+{"test":{"id":4,"name":"Counter increments","suiteID":0,"groupIDs":[2,3],"metadata":{"skip":false,"skipReason":null},"line":6,"column":5,"url":"file:///app/test/counter_test.dart"},"type":"testStart","time":402}
+{"testID":4,"result":"success","skipped":false,"hidden":false,"type":"testDone","time":420}
+{"test":{"id":5,"name":"Counter decrements","suiteID":0,"groupIDs":[2,3],"metadata":{"skip":false,"skipReason":null},"line":12,"column":5,"url":"file:///app/test/counter_test.dart"},"type":"testStart","time":421}
+{"testID":5,"messageType":"print","message":"value: 1","type":"print","time":425}
+{"testID":5,"error":"Expected: <0>\n  Actual: <1>\n","stackTrace":"package:test_api  expect\ntest/counter_test.dart 15:7  main.<fn>.<fn>\n","isFailure":true,"type":"error","time":430}
+{"testID":5,"result":"failure","skipped":false,"hidden":false,"type":"testDone","time":431}
+{"test":{"id":6,"name":"Counter (resets)","suiteID":0,"groupIDs":[2,3],"metadata":{"skip":true,"skipReason":"flaky"},"line":18,"column":5,"url":"file:///app/test/counter_test.dart"},"type":"testStart","time":432}
+{"testID":6,"result":"success","skipped":true,"hidden":false,"type":"testDone","time":432}
+{"success":false,"type":"done","time":450}"#;
+
+    fn report_run() -> TestRun {
+        let mut run = TestRun::new("flutter test --machine".to_string());
+        for event in REPORT.lines().filter_map(parse_event) {
+            run.apply(event);
+        }
+        run
+    }
+
+    #[test]
+    fn apply_report() {
+        let run = report_run();
+        assert_eq!(run.visible_tests().count(), 3);
+        assert_eq!(run.count(TestStatus::Passed), 1);
+        assert_eq!(run.count(TestStatus::Failed), 1);
+        assert_eq!(run.count(TestStatus::Skipped), 1);
+        assert_eq!(run.success, Some(false));
+        let failed = &run.tests[&5];
+        assert_eq!(failed.duration, Some(10));
+        assert_eq!(failed.output, vec!["value: 1"]);
+        assert_eq!(failed.errors[0].message, "Expected: <0>\n  Actual: <1>\n");
+    }
+
+    #[test]
+    fn failures_rerun_by_exact_name() {
+        let target = report_run().failures().unwrap();
+        assert_eq!(
+            target.args(),
+            vec![
+                "test",
+                "--machine",
+                "--name",
+                "^(?:Counter decrements)$",
+                "/app/test/counter_test.dart"
+            ]
+        );
+        let target = TestTarget::Tests {
+            files: vec![],
+            names: vec!["Counter (resets)".to_string()],
+        };
+        assert_eq!(target.args()[3], r"^(?:Counter \(resets\))$");
+        let target = TestTarget::Groups {
+            files: vec![],
+            names: vec!["Counter".to_string()],
+        };
+        assert_eq!(target.args()[3], "^(?:Counter)( |$)");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_fake_flutter() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("lazyflutter-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let flutter = dir.join("flutter");
+        std::fs::write(
+            &flutter,
+            format!(
+                "#!/bin/sh\necho \"$@\" > args\necho 'Running \"flutter pub get\"...'\ncat <<'EOF'\n{}\nEOF\n",
+                REPORT
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&flutter, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let target = TestTarget::Files(vec!["test/counter_test.dart".to_string()]);
+        run(flutter.to_str().unwrap(), &dir, &target, tx)
+            .await
+            .unwrap();
+        let mut run = TestRun::default();
+        while let Ok(event) = rx.try_recv() {
+            run.apply(event);
+        }
+        let args = std::fs::read_to_string(dir.join("args")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(args, "test --machine test/counter_test.dart\n");
+        assert_eq!(run.count(TestStatus::Failed), 1);
+        assert_eq!(run.success, Some(false));
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Directories whose changes rerun tests.
+pub const WATCHED_DIRECTORIES: [&str; 2] = ["lib", "test"];
+
+lazy_static! {
+    // `part of` is left out, it points from a part back to its library.
+    static ref DIRECTIVE: Regex =
+        Regex::new(r#"(?m)^\s*(?:import|export|part)\s+['"]([^'"]+)['"]"#).unwrap();
+}
+
+/// Modification times of the Dart files in the watched directories.
+pub fn snapshot(project_root: &Path) -> BTreeMap<PathBuf, SystemTime> {
+    let mut files = BTreeMap::new();
+    for dir in WATCHED_DIRECTORIES {
+        collect_dart_files(&project_root.join(dir), &mut files);
+    }
+    files
+}
+
+fn collect_dart_files(dir: &Path, files: &mut BTreeMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_dart_files(&path, files);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "dart")
+        {
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                files.insert(path, modified);
+            }
+        }
+    }
+}
+
+/// Files added, modified or removed between two snapshots.
+pub fn changed_files(
+    before: &BTreeMap<PathBuf, SystemTime>,
+    after: &BTreeMap<PathBuf, SystemTime>,
+) -> Vec<PathBuf> {
+    let modified = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone());
+    let removed = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned();
+    modified.chain(removed).collect()
+}
+
+/// URIs of the imports, exports and parts of a Dart file.
+pub fn directives(content: &str) -> Vec<String> {
+    DIRECTIVE
+        .captures_iter(content)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// The file a URI refers to, if it is in this package. Imports of other packages and of the
+/// SDK are not watched.
+pub fn resolve(uri: &str, from: &Path, project_root: &Path, package: &str) -> Option<PathBuf> {
+    if let Some(path) = uri.strip_prefix(&format!("package:{}/", package)) {
+        return Some(project_root.join("lib").join(path));
+    }
+    if uri.contains(':') {
+        return None;
+    }
+    Some(normalize(&from.parent()?.join(uri)))
+}
+
+// Resolve `..` without touching the file system, which may not have the file anymore.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The files each Dart file of the package imports.
+pub fn import_graph(
+    project_root: &Path,
+    package: &str,
+    files: impl Iterator<Item = PathBuf>,
+) -> BTreeMap<PathBuf, Vec<PathBuf>> {
+    files
+        .map(|file| {
+            let imports = std::fs::read_to_string(&file)
+                .map(|content| {
+                    directives(&content)
+                        .iter()
+                        .filter_map(|uri| resolve(uri, &file, project_root, package))
+                        .collect()
+                })
+                .unwrap_or_default();
+            (file, imports)
+        })
+        .collect()
+}
+
+/// Test files depending on any of `changed`, directly or through other files.
+pub fn affected_tests(
+    graph: &BTreeMap<PathBuf, Vec<PathBuf>>,
+    changed: &[PathBuf],
+) -> Vec<PathBuf> {
+    let mut dependents = BTreeMap::<&PathBuf, Vec<&PathBuf>>::new();
+    for (file, imports) in graph {
+        for import in imports {
+            dependents.entry(import).or_default().push(file);
+        }
+    }
+    let mut visited = changed.iter().collect::<BTreeSet<_>>();
+    let mut queue = changed.iter().collect::<VecDeque<_>>();
+    while let Some(file) = queue.pop_front() {
+        for dependent in dependents.get(file).into_iter().flatten() {
+            if visited.insert(dependent) {
+                queue.push_back(dependent);
+            }
+        }
+    }
+    visited
+        .into_iter()
+        .filter(|file| is_test_file(file) && graph.contains_key(*file))
+        .cloned()
+        .collect()
+}
+
+fn is_test_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with("_test.dart"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directives_resolve_in_package() {
+        let content = "library;\nimport 'dart:io';\nimport \"package:app/src/model.dart\";\n\
+                       import 'package:http/http.dart';\nexport '../helpers.dart' show help;\n\
+                       part 'main.g.dart';\npart of 'other.dart';\n";
+        let root = Path::new("/app");
+        let from = Path::new("/app/lib/src/main.dart");
+        let resolved = directives(content)
+            .iter()
+            .filter_map(|uri| resolve(uri, from, root, "app"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            resolved,
+            vec![
+                PathBuf::from("/app/lib/src/model.dart"),
+                PathBuf::from("/app/lib/helpers.dart"),
+                PathBuf::from("/app/lib/src/main.g.dart"),
+            ]
+        );
+    }
+
+    #[test]
+    fn affected_tests_follow_imports() {
+        let file = PathBuf::from;
+        let graph = BTreeMap::from([
+            (file("/app/lib/a.dart"), vec![file("/app/lib/b.dart")]),
+            (file("/app/lib/b.dart"), vec![]),
+            (file("/app/lib/c.dart"), vec![]),
+            (file("/app/test/a_test.dart"), vec![file("/app/lib/a.dart")]),
+            (file("/app/test/c_test.dart"), vec![file("/app/lib/c.dart")]),
+        ]);
+        assert_eq!(
+            affected_tests(&graph, &[file("/app/lib/b.dart")]),
+            vec![file("/app/test/a_test.dart")]
+        );
+        assert_eq!(
+            affected_tests(&graph, &[file("/app/test/c_test.dart")]),
+            vec![file("/app/test/c_test.dart")]
+        );
+    }
+}
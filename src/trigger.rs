@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use regex::Regex;
+use tokio::process::Command;
+
+use crate::{action::Action, config::TriggerConfig};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggerEffect {
+    Action(Action),
+    Command(String),
+}
+
+struct Trigger {
+    regex: Regex,
+    action: Option<Action>,
+    command: Option<String>,
+    cooldown: Duration,
+    last_fired_at: Option<Instant>,
+}
+
+/// Log-pattern triggers evaluated over the log stream of a single session.
+pub struct Triggers {
+    triggers: Vec<Trigger>,
+}
+
+impl Triggers {
+    pub fn new(configs: &[TriggerConfig]) -> Self {
+        let triggers = configs
+            .iter()
+            .filter_map(|config| match Regex::new(&config.pattern) {
+                Ok(regex) => Some(Trigger {
+                    regex,
+                    action: config.action.clone(),
+                    command: config.command.clone(),
+                    cooldown: Duration::from_millis(config.cooldown_ms),
+                    last_fired_at: None,
+                }),
+                Err(e) => {
+                    log::error!("Invalid trigger pattern {:?}: {}", config.pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self { triggers }
+    }
+
+    pub fn evaluate(&mut self, line: &str) -> Vec<TriggerEffect> {
+        self.evaluate_at(line, Instant::now())
+    }
+
+    fn evaluate_at(&mut self, line: &str, now: Instant) -> Vec<TriggerEffect> {
+        let mut effects = vec![];
+        for trigger in self.triggers.iter_mut() {
+            if !trigger.regex.is_match(line) {
+                continue;
+            }
+            if let Some(last_fired_at) = trigger.last_fired_at {
+                if now.duration_since(last_fired_at) < trigger.cooldown {
+                    continue;
+                }
+            }
+            trigger.last_fired_at = Some(now);
+            if let Some(action) = &trigger.action {
+                effects.push(TriggerEffect::Action(action.clone()));
+            }
+            if let Some(command) = &trigger.command {
+                effects.push(TriggerEffect::Command(command.clone()));
+            }
+        }
+        effects
+    }
+}
+
+/// The triggers of every session, kept across restarts so that their cooldowns still apply.
+#[derive(Default)]
+pub struct SessionTriggers {
+    sessions: HashMap<String, Triggers>,
+}
+
+impl SessionTriggers {
+    /// Evaluate `line` with the triggers of `session_id`, created from `configs` on first use.
+    pub fn evaluate(
+        &mut self,
+        configs: &[TriggerConfig],
+        session_id: &str,
+        line: &str,
+    ) -> Vec<TriggerEffect> {
+        self.evaluate_at(configs, session_id, line, Instant::now())
+    }
+
+    fn evaluate_at(
+        &mut self,
+        configs: &[TriggerConfig],
+        session_id: &str,
+        line: &str,
+        now: Instant,
+    ) -> Vec<TriggerEffect> {
+        self.sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| Triggers::new(configs))
+            .evaluate_at(line, now)
+    }
+}
+
+/// Run a trigger's shell command in the background. The matched log line is exposed to
+/// the command as `LAZYFLUTTER_TRIGGER_LINE`.
+pub fn run_command(command: &str, line: &str) {
+    #[cfg(not(windows))]
+    let mut process = Command::new("sh");
+    #[cfg(not(windows))]
+    process.arg("-c");
+    #[cfg(windows)]
+    let mut process = Command::new("cmd");
+    #[cfg(windows)]
+    process.arg("/C");
+
+    let child = process
+        .arg(command)
+        .env("LAZYFLUTTER_TRIGGER_LINE", line)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            tokio::spawn(async move {
+                let _ = child.wait().await;
+            });
+        }
+        Err(e) => log::error!("Failed to run trigger command {:?}: {}", command, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(pattern: &str, action: Option<Action>, command: Option<&str>) -> TriggerConfig {
+        TriggerConfig {
+            pattern: pattern.to_string(),
+            action,
+            command: command.map(|c| c.to_string()),
+            cooldown_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn evaluate_matching_lines() {
+        let mut triggers = Triggers::new(&[
+            config(
+                "Lost connection to device",
+                Some(Action::RestartSession),
+                None,
+            ),
+            config("Reloaded .* libraries", Some(Action::Bell), Some("echo")),
+        ]);
+        let now = Instant::now();
+        assert_eq!(
+            triggers.evaluate_at("Lost connection to device.", now),
+            vec![TriggerEffect::Action(Action::RestartSession)]
+        );
+        assert_eq!(
+            triggers.evaluate_at("Reloaded 1 of 725 libraries in 300ms.", now),
+            vec![
+                TriggerEffect::Action(Action::Bell),
+                TriggerEffect::Command("echo".to_string())
+            ]
+        );
+        assert_eq!(triggers.evaluate_at("flutter: Hello", now), vec![]);
+    }
+
+    #[test]
+    fn evaluate_respects_cooldown() {
+        let mut triggers = Triggers::new(&[config("error", Some(Action::Bell), None)]);
+        let now = Instant::now();
+        assert_eq!(triggers.evaluate_at("error", now).len(), 1);
+        assert_eq!(
+            triggers
+                .evaluate_at("error", now + Duration::from_millis(500))
+                .len(),
+            0
+        );
+        assert_eq!(
+            triggers
+                .evaluate_at("error", now + Duration::from_millis(1000))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn restarted_sessions_keep_cooldowns() {
+        let configs = [config(
+            "Lost connection to device",
+            Some(Action::RestartSession),
+            None,
+        )];
+        let mut triggers = SessionTriggers::default();
+        let now = Instant::now();
+        let line = "Lost connection to device.";
+        assert_eq!(
+            triggers.evaluate_at(&configs, "session-1", line, now).len(),
+            1
+        );
+        // The restarted app logs under the same session id.
+        let later = now + Duration::from_millis(500);
+        assert_eq!(
+            triggers
+                .evaluate_at(&configs, "session-1", line, later)
+                .len(),
+            0
+        );
+        assert_eq!(
+            triggers
+                .evaluate_at(&configs, "session-2", line, later)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_skipped() {
+        let mut triggers = Triggers::new(&[config("(", Some(Action::Bell), None)]);
+        assert_eq!(triggers.evaluate_at("(", Instant::now()), vec![]);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod client;
+pub mod evaluate;
+pub mod frames;
+pub mod inspector;
+pub mod io;
+pub mod isolates;
+pub mod memory;
+pub mod network;
+pub mod profiler;
+pub mod rebuilds;
+pub mod timeline;
+pub mod tree_dump;
@@ -0,0 +1,317 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::{eyre::eyre, Result};
+use futures::{stream::SplitSink, SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use tokio::{
+    net::TcpStream,
+    sync::{
+        broadcast::{self, error::RecvError},
+        Mutex,
+    },
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::io::{
+    event::{StreamNotifyParams, VmServiceEvent},
+    parse_event, parse_response,
+    request::{GetIsolateParams, StreamId, StreamListenParams, VmServiceRequest},
+    response::{Isolate, Success, Vm, VmServiceResponse},
+};
+
+type WebSocketSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+
+/// The error code returned by `streamListen` when the stream is already subscribed.
+const STREAM_ALREADY_SUBSCRIBED: i64 = 103;
+// Long enough for slow requests like heap snapshots, short enough that a lost reply does not
+// freeze a panel.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A Dart VM Service Protocol client talking JSON-RPC over WebSocket.
+pub struct VmService {
+    tx: broadcast::Sender<String>,
+    sink: Arc<Mutex<WebSocketSink>>,
+    request_count: Arc<Mutex<u32>>,
+}
+
+impl VmService {
+    pub async fn connect(ws_uri: &str) -> Result<Self> {
+        let (stream, _) = connect_async(ws_uri).await?;
+        let (sink, mut stream) = stream.split();
+
+        let (tx, _) = broadcast::channel::<String>(256);
+
+        let _tx = tx.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                if let Message::Text(text) = message {
+                    let _ = _tx.send(text);
+                }
+            }
+        });
+
+        Ok(Self {
+            tx,
+            sink: Arc::new(Mutex::new(sink)),
+            request_count: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    pub async fn get_vm(&self) -> Result<Vm> {
+        self.call::<(), _>("getVM", None).await
+    }
+
+    pub async fn get_isolate(&self, isolate_id: &str) -> Result<Isolate> {
+        let params = GetIsolateParams {
+            isolate_id: isolate_id.to_string(),
+        };
+        self.call("getIsolate", Some(params)).await
+    }
+
+    pub async fn stream_listen(&self, stream_id: StreamId) -> Result<()> {
+        let request_id = self.request_id().await;
+        let request = VmServiceRequest::new(
+            request_id,
+            "streamListen",
+            Some(StreamListenParams { stream_id }),
+        )?;
+        let response: VmServiceResponse<Success> = self.request(&request).await?;
+        match response.error {
+            Some(error) if error.code != STREAM_ALREADY_SUBSCRIBED => {
+                Err(eyre!("Could not listen {:?}: {}", stream_id, error.message))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn stream_cancel(&self, stream_id: StreamId) -> Result<()> {
+        let _: Success = self
+            .call("streamCancel", Some(StreamListenParams { stream_id }))
+            .await?;
+        Ok(())
+    }
+
+    /// Invoke a service extension such as `ext.flutter.debugPaint` on the given isolate.
+    pub async fn call_service_extension(
+        &self,
+        method: &str,
+        isolate_id: &str,
+        params: Map<String, Value>,
+    ) -> Result<Value> {
+        let mut params = params;
+        params.insert("isolateId".to_string(), Value::from(isolate_id));
+        self.call(method, Some(params)).await
+    }
+
+    /// Send a request and wait for its result, turning an RPC error into an `Err`.
+    pub async fn call<P, R>(&self, method: &str, params: Option<P>) -> Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let request_id = self.request_id().await;
+        let request = VmServiceRequest::new(request_id, method, params)?;
+        let response: VmServiceResponse<R> = self.request(&request).await?;
+        if let Some(error) = response.error {
+            return Err(eyre!(
+                "{} failed with {}: {}",
+                method,
+                error.code,
+                error.message
+            ));
+        }
+        response
+            .result
+            .ok_or(eyre!("{} returned no result", method))
+    }
+
+    /// Subscribe to notifications of every stream the client listens to.
+    pub fn events(&self) -> VmServiceEvents {
+        VmServiceEvents {
+            rx: self.tx.subscribe(),
+        }
+    }
+
+    async fn request_id(&self) -> u32 {
+        let mut request_count = self.request_count.lock().await;
+        *request_count += 1;
+        *request_count
+    }
+
+    async fn request<R>(&self, request: &VmServiceRequest) -> Result<VmServiceResponse<R>>
+    where
+        R: DeserializeOwned,
+    {
+        // Subscribe before sending so that a fast response can not be missed.
+        let mut rx = self.tx.subscribe();
+        let message = serde_json::to_string(request)?;
+        self.sink.lock().await.send(Message::Text(message)).await?;
+        let response = async {
+            loop {
+                match rx.recv().await {
+                    Ok(line) => {
+                        if let Some(response) = parse_response(&line, &request.id) {
+                            return response.map_err(|e| {
+                                eyre!("Unexpected response to {}: {}", request.method, e)
+                            });
+                        }
+                    }
+                    // The response may have been one of the dropped messages.
+                    Err(RecvError::Lagged(count)) => {
+                        return Err(eyre!(
+                            "Missed {} vm service messages waiting for {}",
+                            count,
+                            request.method
+                        ))
+                    }
+                    Err(RecvError::Closed) => {
+                        return Err(eyre!("Could not receive vm service response"))
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(REQUEST_TIMEOUT, response)
+            .await
+            .map_err(|_| eyre!("{} timed out", request.method))?
+    }
+}
+
+pub struct VmServiceEvents {
+    rx: broadcast::Receiver<String>,
+}
+
+impl VmServiceEvents {
+    /// Wait for the next stream notification. Returns `None` once the connection is closed.
+    pub async fn recv(&mut self) -> Option<StreamNotifyParams> {
+        loop {
+            match self.rx.recv().await {
+                Ok(line) => {
+                    if let Some(VmServiceEvent::StreamNotify { params }) = parse_event(&line) {
+                        return Some(params);
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use super::*;
+    use crate::vm_service::io::event::EventKind;
+
+    /// Start a fake VM service that answers every request with the messages returned by
+    /// `handler`, and return its WebSocket URI.
+    pub async fn fake_vm_service<F>(handler: F) -> String
+    where
+        F: Fn(Value) -> Vec<Value> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                let Message::Text(text) = message else {
+                    continue;
+                };
+                let request: Value = serde_json::from_str(&text).unwrap();
+                for reply in handler(request) {
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                }
+            }
+        });
+        format!("ws://{}/ws", address)
+    }
+
+    #[tokio::test]
+    async fn get_vm() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "getVM");
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "VM",
+                    "name": "vm",
+                    "version": "3.2.0",
+                    "isolates": [
+                        {"type": "@Isolate", "id": "isolates/1", "name": "main", "number": "1"}
+                    ]
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let vm = vm_service.get_vm().await.unwrap();
+        assert_eq!(vm.isolates.len(), 1);
+        assert_eq!(vm.isolates[0].id, "isolates/1");
+    }
+
+    #[tokio::test]
+    async fn call_returns_rpc_error() {
+        let ws_uri = fake_vm_service(|request| {
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": {"code": 113, "message": "Method not found"}
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let result = vm_service
+            .call_service_extension("ext.app.missing", "isolates/1", Map::new())
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn unexpected_result_is_an_error() {
+        let ws_uri = fake_vm_service(|request| {
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"type": "Sentinel", "kind": "Collected", "valueAsString": "<collected>"}
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let error = vm_service.get_vm().await.unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unexpected response to getVM"));
+    }
+
+    #[tokio::test]
+    async fn stream_listen_and_receive_events() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "streamListen");
+            vec![
+                json!({"jsonrpc": "2.0", "id": request["id"], "result": {"type": "Success"}}),
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "streamNotify",
+                    "params": {
+                        "streamId": request["params"]["streamId"],
+                        "event": {"type": "Event", "kind": "GC", "timestamp": 1000}
+                    }
+                }),
+            ]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let mut events = vm_service.events();
+        vm_service.stream_listen(StreamId::Gc).await.unwrap();
+        let params = events.recv().await.unwrap();
+        assert_eq!(params.stream_id, "GC");
+        assert_eq!(params.event.kind, EventKind::Gc);
+    }
+}
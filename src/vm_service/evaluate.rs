@@ -0,0 +1,67 @@
+use color_eyre::Result;
+
+use super::{
+    client::VmService,
+    io::{
+        instance::{Instance, InstanceRef},
+        request::{EvaluateParams, GetObjectParams},
+    },
+};
+
+/// Elements fetched at most when inspecting a list or map.
+const MAX_OBJECT_ELEMENTS: i64 = 100;
+
+impl VmService {
+    /// Evaluate an expression in the scope of a library, class or instance.
+    pub async fn evaluate(
+        &self,
+        isolate_id: &str,
+        target_id: &str,
+        expression: &str,
+    ) -> Result<InstanceRef> {
+        let params = EvaluateParams {
+            isolate_id: isolate_id.to_string(),
+            target_id: target_id.to_string(),
+            expression: expression.to_string(),
+        };
+        self.call("evaluate", Some(params)).await
+    }
+
+    pub async fn get_instance(&self, isolate_id: &str, object_id: &str) -> Result<Instance> {
+        let params = GetObjectParams {
+            isolate_id: isolate_id.to_string(),
+            object_id: object_id.to_string(),
+            count: Some(MAX_OBJECT_ELEMENTS),
+        };
+        self.call("getObject", Some(params)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    #[tokio::test]
+    async fn evaluate_expression() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "evaluate");
+            assert_eq!(request["params"]["targetId"], "libraries/1");
+            assert_eq!(request["params"]["expression"], "1 + 1");
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"type": "@Instance", "id": "objects/1", "kind": "Int", "valueAsString": "2"}
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let result = vm_service
+            .evaluate("isolates/1", "libraries/1", "1 + 1")
+            .await
+            .unwrap();
+        assert_eq!(result.description(), "2");
+    }
+}
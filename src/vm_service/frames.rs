@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::io::event::{Event, EventKind};
+
+/// The timing of a single frame, reported by `Flutter.Frame` extension events in microseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameTiming {
+    pub number: u64,
+    #[serde(rename = "startTime")]
+    pub start_time: u64,
+    pub elapsed: u64,
+    pub build: u64,
+    pub raster: u64,
+    #[serde(rename = "vsyncOverhead")]
+    #[serde(default)]
+    pub vsync_overhead: u64,
+}
+
+impl FrameTiming {
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.kind != EventKind::Extension
+            || event.extension_kind.as_deref() != Some("Flutter.Frame")
+        {
+            return None;
+        }
+        serde_json::from_value(event.extension_data.clone()?).ok()
+    }
+
+    pub fn is_janky(&self, budget: Duration) -> bool {
+        let budget = budget.as_micros() as u64;
+        self.build > budget || self.raster > budget
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Percentiles {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl Percentiles {
+    pub fn of(values: impl Iterator<Item = u64>) -> Self {
+        let mut values = values.collect::<Vec<_>>();
+        values.sort_unstable();
+        // Nearest-rank percentile.
+        let percentile = |p: usize| -> u64 {
+            if values.is_empty() {
+                return 0;
+            }
+            let rank = (p * values.len()).div_ceil(100).max(1);
+            values[rank - 1]
+        };
+        Self {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+        }
+    }
+}
+
+/// Jank counts and percentiles over a set of frames.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrameSummary {
+    pub frames: usize,
+    pub janky_build: usize,
+    pub janky_raster: usize,
+    pub build: Percentiles,
+    pub raster: Percentiles,
+}
+
+impl FrameSummary {
+    pub fn new(frames: &[FrameTiming], budget: Duration) -> Self {
+        let budget_us = budget.as_micros() as u64;
+        Self {
+            frames: frames.len(),
+            janky_build: frames.iter().filter(|f| f.build > budget_us).count(),
+            janky_raster: frames.iter().filter(|f| f.raster > budget_us).count(),
+            build: Percentiles::of(frames.iter().map(|f| f.build)),
+            raster: Percentiles::of(frames.iter().map(|f| f.raster)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm_service::io::{event::VmServiceEvent, parse_event};
+
+    use super::*;
+
+    fn frame(number: u64, build: u64, raster: u64) -> FrameTiming {
+        FrameTiming {
+            number,
+            start_time: 0,
+            elapsed: build + raster,
+            build,
+            raster,
+            vsync_overhead: 0,
+        }
+    }
+
+    #[test]
+    fn parse_frame_event() {
+        let line = r#"{"jsonrpc":"2.0","method":"streamNotify","params":{"streamId":"Extension","event":{"type":"Event","kind":"Extension","isolate":{"type":"@Isolate","id":"isolates/1","name":"main","number":"1"},"timestamp":1000,"extensionKind":"Flutter.Frame","extensionData":{"number":12,"startTime":1000,"elapsed":9000,"build":3000,"raster":5000,"vsyncOverhead":200}}}}"#;
+        let Some(VmServiceEvent::StreamNotify { params }) = parse_event(line) else {
+            panic!("not a stream notification");
+        };
+        assert_eq!(
+            FrameTiming::from_event(&params.event),
+            Some(FrameTiming {
+                number: 12,
+                start_time: 1000,
+                elapsed: 9000,
+                build: 3000,
+                raster: 5000,
+                vsync_overhead: 200,
+            })
+        );
+    }
+
+    #[test]
+    fn summarize_frames() {
+        let frames = (1..=100)
+            .map(|i| frame(i, i * 200, if i % 10 == 0 { 20_000 } else { 1_000 }))
+            .collect::<Vec<_>>();
+        let summary = FrameSummary::new(&frames, Duration::from_millis(16));
+        assert_eq!(summary.frames, 100);
+        assert_eq!(summary.janky_build, 20);
+        assert_eq!(summary.janky_raster, 10);
+        assert_eq!(
+            summary.build,
+            Percentiles {
+                p50: 10_000,
+                p90: 18_000,
+                p99: 19_800,
+            }
+        );
+        assert_eq!(summary.raster.p50, 1_000);
+        assert_eq!(summary.raster.p99, 20_000);
+        assert!(frame(1, 9_000, 1_000).is_janky(Duration::from_millis(8)));
+        assert!(!frame(1, 9_000, 1_000).is_janky(Duration::from_millis(16)));
+    }
+}
@@ -0,0 +1,123 @@
+use color_eyre::{eyre::eyre, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::{client::VmService, io::diagnostics::DiagnosticsNode};
+
+/// The object group holding every inspector reference created by lazyflutter.
+pub const OBJECT_GROUP: &str = "lazyflutter-inspector";
+
+/// Helpers for the `ext.flutter.inspector.*` service extensions.
+impl VmService {
+    pub async fn get_root_widget_summary_tree(&self, isolate_id: &str) -> Result<DiagnosticsNode> {
+        self.call_inspector_extension(
+            "ext.flutter.inspector.getRootWidgetSummaryTree",
+            isolate_id,
+            [("objectGroup", OBJECT_GROUP)],
+        )
+        .await
+    }
+
+    pub async fn get_inspector_properties(
+        &self,
+        isolate_id: &str,
+        value_id: &str,
+    ) -> Result<Vec<DiagnosticsNode>> {
+        self.call_inspector_extension(
+            "ext.flutter.inspector.getProperties",
+            isolate_id,
+            [("arg", value_id), ("objectGroup", OBJECT_GROUP)],
+        )
+        .await
+    }
+
+    pub async fn get_selected_summary_widget(
+        &self,
+        isolate_id: &str,
+    ) -> Result<Option<DiagnosticsNode>> {
+        self.call_inspector_extension(
+            "ext.flutter.inspector.getSelectedSummaryWidget",
+            isolate_id,
+            [("objectGroup", OBJECT_GROUP)],
+        )
+        .await
+    }
+
+    /// Toggle "select widget mode", where tapping on the device selects a widget.
+    pub async fn set_inspector_select_mode(&self, isolate_id: &str, enabled: bool) -> Result<()> {
+        let params = Map::from_iter([("enabled".to_string(), Value::from(enabled.to_string()))]);
+        self.call_service_extension("ext.flutter.inspector.show", isolate_id, params)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn dispose_inspector_group(&self, isolate_id: &str) -> Result<()> {
+        let _: Value = self
+            .call_inspector_extension(
+                "ext.flutter.inspector.disposeGroup",
+                isolate_id,
+                [("objectGroup", OBJECT_GROUP)],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn call_inspector_extension<T, const N: usize>(
+        &self,
+        method: &str,
+        isolate_id: &str,
+        params: [(&str, &str); N],
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let params = params
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), Value::from(value)))
+            .collect();
+        let mut response = self
+            .call_service_extension(method, isolate_id, params)
+            .await?;
+        let result = response
+            .get_mut("result")
+            .map(Value::take)
+            .ok_or(eyre!("{} returned no result", method))?;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    #[tokio::test]
+    async fn get_root_widget_summary_tree() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(
+                request["method"],
+                "ext.flutter.inspector.getRootWidgetSummaryTree"
+            );
+            assert_eq!(request["params"]["isolateId"], "isolates/1");
+            assert_eq!(request["params"]["objectGroup"], OBJECT_GROUP);
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "_extensionType",
+                    "method": "ext.flutter.inspector.getRootWidgetSummaryTree",
+                    "result": {"description": "[root]", "valueId": "inspector-0", "children": []}
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let root = vm_service
+            .get_root_widget_summary_tree("isolates/1")
+            .await
+            .unwrap();
+        assert_eq!(root.value_id, Some("inspector-0".to_string()));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use self::{event::VmServiceEvent, response::VmServiceResponse};
+
+pub mod diagnostics;
+pub mod event;
+pub mod http_profile;
+pub mod instance;
+pub mod request;
+pub mod response;
+
+pub fn parse_event(s: &str) -> Option<VmServiceEvent> {
+    serde_json::from_str::<VmServiceEvent>(s).ok()
+}
+
+/// The response to the request `id`, or `None` for any other message. A response of an
+/// unexpected shape is an error rather than skipped, so the request does not wait forever.
+pub fn parse_response<T>(s: &str, id: &str) -> Option<serde_json::Result<VmServiceResponse<T>>>
+where
+    T: DeserializeOwned,
+{
+    let value = serde_json::from_str::<Value>(s).ok()?;
+    if value.get("id").and_then(|id| id.as_str()) != Some(id) {
+        return None;
+    }
+    Some(serde_json::from_value(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event() {
+        let s = r#"{"jsonrpc":"2.0","method":"streamNotify","params":{"streamId":"GC","event":{"type":"Event","kind":"GC"}}}"#;
+        assert!(parse_event(s).is_some());
+
+        let s = r#"{"jsonrpc":"2.0","id":"1","result":{"type":"Success"}}"#;
+        assert_eq!(parse_event(s), None);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let s = r#"{"jsonrpc":"2.0","id":"1","result":"1234"}"#;
+        let response = parse_response::<String>(s, "1").unwrap().ok();
+        assert_eq!(
+            response,
+            Some(VmServiceResponse {
+                id: "1".to_string(),
+                result: Some("1234".to_string()),
+                error: None,
+            })
+        );
+        assert!(parse_response::<String>(s, "2").is_none());
+        assert!(parse_response::<u32>(s, "1").unwrap().is_err());
+
+        let s = r#"some invalid message"#;
+        assert!(parse_response::<String>(s, "1").is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreationLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A node of the widget tree or of a property list returned by `ext.flutter.inspector.*`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsNode {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "valueId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_id: Option<String>,
+    #[serde(rename = "widgetRuntimeType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub widget_runtime_type: Option<String>,
+    #[serde(rename = "creationLocation")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_location: Option<CreationLocation>,
+    #[serde(rename = "createdByLocalProject")]
+    #[serde(default)]
+    pub created_by_local_project: bool,
+    #[serde(rename = "hasChildren")]
+    #[serde(default)]
+    pub has_children: bool,
+    #[serde(default)]
+    pub children: Vec<DiagnosticsNode>,
+}
+
+impl DiagnosticsNode {
+    /// `name: description` for properties, `description` for widgets.
+    pub fn label(&self) -> String {
+        match (&self.name, &self.description) {
+            (Some(name), Some(description)) if !name.is_empty() => {
+                format!("{}: {}", name, description)
+            }
+            (_, Some(description)) => description.clone(),
+            (Some(name), None) => name.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_summary_tree() {
+        let json = r#"{"description":"[root]","type":"_ElementDiagnosticableTreeNode","valueId":"inspector-0","hasChildren":true,"children":[{"description":"MyApp","valueId":"inspector-1","widgetRuntimeType":"MyApp","createdByLocalProject":true,"hasChildren":false,"creationLocation":{"file":"file:///app/lib/main.dart","line":4,"column":10,"name":"MyApp"},"children":[]}]}"#;
+        let node: DiagnosticsNode = serde_json::from_str(json).unwrap();
+        assert_eq!(node.label(), "[root]");
+        assert_eq!(node.children.len(), 1);
+        let child = &node.children[0];
+        assert!(child.created_by_local_project);
+        assert_eq!(
+            child.creation_location,
+            Some(CreationLocation {
+                file: "file:///app/lib/main.dart".to_string(),
+                line: 4,
+                column: 10,
+                name: Some("MyApp".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_property_label() {
+        let json = r#"{"description":"Color(0xff2196f3)","name":"color","type":"DiagnosticsProperty<Color>"}"#;
+        let node: DiagnosticsNode = serde_json::from_str(json).unwrap();
+        assert_eq!(node.label(), "color: Color(0xff2196f3)");
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::response::IsolateRef;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    #[serde(rename = "VMUpdate")]
+    VmUpdate,
+    IsolateStart,
+    IsolateRunnable,
+    IsolateExit,
+    IsolateUpdate,
+    IsolateReload,
+    ServiceExtensionAdded,
+    PauseStart,
+    PauseExit,
+    PauseBreakpoint,
+    PauseInterrupted,
+    PauseException,
+    PausePostRequest,
+    None,
+    Resume,
+    BreakpointAdded,
+    BreakpointResolved,
+    BreakpointRemoved,
+    Inspect,
+    #[serde(rename = "GC")]
+    Gc,
+    WriteEvent,
+    Logging,
+    Extension,
+    TimelineEvents,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceRef {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub kind: String,
+    #[serde(rename = "valueAsString")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_as_string: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub message: InstanceRef,
+    pub level: i64,
+    #[serde(rename = "loggerName")]
+    pub logger_name: InstanceRef,
+    #[serde(rename = "sequenceNumber")]
+    pub sequence_number: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: EventKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolate: Option<IsolateRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(rename = "extensionRPC")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_rpc: Option<String>,
+    #[serde(rename = "extensionKind")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_kind: Option<String>,
+    #[serde(rename = "extensionData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension_data: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<String>,
+    #[serde(rename = "logRecord")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_record: Option<LogRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StreamNotifyParams {
+    #[serde(rename = "streamId")]
+    pub stream_id: String,
+    pub event: Event,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "method")]
+pub enum VmServiceEvent {
+    #[serde(rename = "streamNotify")]
+    StreamNotify { params: StreamNotifyParams },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_extension_event() {
+        let json = r#"{"jsonrpc":"2.0","method":"streamNotify","params":{"streamId":"Extension","event":{"type":"Event","kind":"Extension","isolate":{"type":"@Isolate","id":"isolates/1","name":"main","number":"1","isSystemIsolate":false},"timestamp":1000,"extensionKind":"Flutter.Frame","extensionData":{"number":1,"elapsed":16000}}}}"#;
+        let event: VmServiceEvent = serde_json::from_str(json).unwrap();
+        let VmServiceEvent::StreamNotify { params } = event;
+        assert_eq!(params.stream_id, "Extension");
+        assert_eq!(params.event.kind, EventKind::Extension);
+        assert_eq!(
+            params.event.extension_kind,
+            Some("Flutter.Frame".to_string())
+        );
+        assert_eq!(
+            params.event.isolate.map(|isolate| isolate.id),
+            Some("isolates/1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_logging_event() {
+        let json = r#"{"jsonrpc":"2.0","method":"streamNotify","params":{"streamId":"Logging","event":{"type":"Event","kind":"Logging","timestamp":1000,"logRecord":{"type":"LogRecord","message":{"type":"@Instance","id":"objects/1","kind":"String","valueAsString":"Hello"},"level":800,"loggerName":{"type":"@Instance","kind":"String","valueAsString":"app"},"sequenceNumber":1}}}}"#;
+        let event: VmServiceEvent = serde_json::from_str(json).unwrap();
+        let VmServiceEvent::StreamNotify { params } = event;
+        let log_record = params.event.log_record.unwrap();
+        assert_eq!(
+            log_record.message.value_as_string,
+            Some("Hello".to_string())
+        );
+        assert_eq!(log_record.level, 800);
+    }
+
+    #[test]
+    fn test_deserialize_unknown_event_kind() {
+        let json = r#"{"jsonrpc":"2.0","method":"streamNotify","params":{"streamId":"Debug","event":{"type":"Event","kind":"UserTagChanged"}}}"#;
+        let event: VmServiceEvent = serde_json::from_str(json).unwrap();
+        let VmServiceEvent::StreamNotify { params } = event;
+        assert_eq!(params.event.kind, EventKind::Unknown);
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// HTTP requests recorded by `dart:io`, returned by `ext.dart.io.getHttpProfile`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpProfile {
+    /// Pass as `updatedSince` to only get the requests that changed afterwards.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub requests: Vec<HttpProfileRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpProfileRequest {
+    // Older `dart:io` versions use integer ids.
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: String,
+    pub method: String,
+    pub uri: String,
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+    #[serde(rename = "endTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<HttpProfileRequestData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<HttpProfileResponseData>,
+    /// Only set by `ext.dart.io.getHttpProfileRequest`.
+    #[serde(rename = "requestBody")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Vec<u8>>,
+    /// Only set by `ext.dart.io.getHttpProfileRequest`.
+    #[serde(rename = "responseBody")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpProfileRequestData {
+    #[serde(default)]
+    pub headers: Map<String, Value>,
+    #[serde(rename = "contentLength")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpProfileResponseData {
+    #[serde(rename = "statusCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(default)]
+    pub headers: Map<String, Value>,
+    #[serde(rename = "contentLength")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_length: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HttpProfileRequest {
+    pub fn status_code(&self) -> Option<u16> {
+        self.response.as_ref()?.status_code
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.response
+            .as_ref()
+            .and_then(|response| response.error.as_deref())
+            .or(self.request.as_ref()?.error.as_deref())
+    }
+
+    /// Duration in microseconds, or `None` while the request is in flight.
+    pub fn duration(&self) -> Option<i64> {
+        Some(self.end_time? - self.start_time)
+    }
+
+    /// Response size in bytes, from the body if it was fetched or the content length.
+    pub fn response_size(&self) -> Option<i64> {
+        match &self.response_body {
+            Some(body) => Some(body.len() as i64),
+            None => self
+                .response
+                .as_ref()?
+                .content_length
+                .filter(|length| *length >= 0),
+        }
+    }
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        value => Ok(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_http_profile() {
+        let profile: HttpProfile = serde_json::from_value(json!({
+            "type": "HttpProfile",
+            "timestamp": 2000,
+            "requests": [
+                {
+                    "type": "@HttpProfileRequest",
+                    "id": 3,
+                    "isolateId": "isolates/1",
+                    "method": "GET",
+                    "uri": "https://example.com/users",
+                    "events": [],
+                    "startTime": 1000,
+                    "endTime": 1500,
+                    "request": {"headers": {"accept": ["application/json"]}, "contentLength": 0},
+                    "response": {"statusCode": 200, "headers": {}, "contentLength": 42}
+                },
+                {
+                    "type": "@HttpProfileRequest",
+                    "id": "4",
+                    "isolateId": "isolates/1",
+                    "method": "POST",
+                    "uri": "https://example.com/login",
+                    "events": [],
+                    "startTime": 1800
+                }
+            ]
+        }))
+        .unwrap();
+        let done = &profile.requests[0];
+        assert_eq!(done.id, "3");
+        assert_eq!(done.status_code(), Some(200));
+        assert_eq!(done.duration(), Some(500));
+        assert_eq!(done.response_size(), Some(42));
+        let pending = &profile.requests[1];
+        assert_eq!(pending.id, "4");
+        assert_eq!(pending.duration(), None);
+        assert_eq!(pending.status_code(), None);
+    }
+}
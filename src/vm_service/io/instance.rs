@@ -0,0 +1,196 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::response::ClassRef;
+
+/// An `@Instance`, `@Error` or `Sentinel` returned by `evaluate` or held by another object.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstanceRef {
+    #[serde(rename = "type")]
+    pub object_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassRef>,
+    #[serde(rename = "valueAsString")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_as_string: Option<String>,
+    #[serde(rename = "valueAsStringIsTruncated")]
+    #[serde(default)]
+    pub value_as_string_is_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<i64>,
+    /// Set on `@Error`s.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl InstanceRef {
+    pub fn is_error(&self) -> bool {
+        self.object_type == "@Error" || self.object_type == "Error"
+    }
+
+    /// Whether `getObject` returns fields, elements or associations for it.
+    pub fn has_children(&self) -> bool {
+        if self.id.is_none() || self.is_error() || self.object_type == "Sentinel" {
+            return false;
+        }
+        !matches!(
+            self.kind.as_deref(),
+            Some("Null" | "Bool" | "Int" | "Double" | "String" | "Closure" | "Type")
+        )
+    }
+
+    pub fn description(&self) -> String {
+        if self.is_error() {
+            return self
+                .message
+                .clone()
+                .unwrap_or_else(|| self.kind.clone().unwrap_or_default());
+        }
+        let class_name = self.class.as_ref().map(|c| c.name.as_str()).unwrap_or("");
+        match (self.kind.as_deref(), &self.value_as_string, self.length) {
+            (Some("String"), Some(value), _) => {
+                let ellipsis = if self.value_as_string_is_truncated {
+                    "…"
+                } else {
+                    ""
+                };
+                format!("\"{}{}\"", value, ellipsis)
+            }
+            (_, Some(value), _) => value.clone(),
+            (_, None, Some(length)) => format!("{} ({} items)", class_name, length),
+            _ if !class_name.is_empty() => format!("Instance of '{}'", class_name),
+            _ => format!("<{}>", self.kind.as_deref().unwrap_or(&self.object_type)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BoundField {
+    /// The `@Field` declaration, absent for record fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decl: Option<Value>,
+    /// A name or position, set on record fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Value>,
+    pub value: InstanceRef,
+}
+
+impl BoundField {
+    pub fn name(&self) -> String {
+        match (&self.decl, &self.name) {
+            (Some(decl), _) if decl["name"].is_string() => {
+                decl["name"].as_str().unwrap_or_default().to_string()
+            }
+            (_, Some(Value::String(name))) => name.clone(),
+            (_, Some(name)) => format!("${}", name),
+            _ => "?".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MapAssociation {
+    pub key: InstanceRef,
+    pub value: InstanceRef,
+}
+
+/// The full object returned by `getObject`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+    #[serde(default)]
+    pub fields: Vec<BoundField>,
+    #[serde(default)]
+    pub elements: Vec<InstanceRef>,
+    #[serde(default)]
+    pub associations: Vec<MapAssociation>,
+}
+
+impl Instance {
+    /// Fields, list elements and map entries, labelled for display.
+    pub fn children(self) -> Vec<(String, InstanceRef)> {
+        let fields = self
+            .fields
+            .into_iter()
+            .map(|field| (field.name(), field.value));
+        let elements = self
+            .elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| (format!("[{}]", index), element));
+        let associations = self.associations.into_iter().map(|association| {
+            (
+                format!("[{}]", association.key.description()),
+                association.value,
+            )
+        });
+        fields.chain(elements).chain(associations).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn describe_instances() {
+        let instance = |value: Value| serde_json::from_value::<InstanceRef>(value).unwrap();
+        let string = instance(json!({
+            "type": "@Instance", "id": "objects/1", "kind": "String",
+            "valueAsString": "hello", "valueAsStringIsTruncated": true
+        }));
+        assert_eq!(string.description(), "\"hello…\"");
+        assert!(!string.has_children());
+        let list = instance(json!({
+            "type": "@Instance", "id": "objects/2", "kind": "List", "length": 3,
+            "class": {"type": "@Class", "id": "classes/1", "name": "_GrowableList"}
+        }));
+        assert_eq!(list.description(), "_GrowableList (3 items)");
+        assert!(list.has_children());
+        let error = instance(json!({
+            "type": "@Error", "id": "objects/3", "kind": "UnhandledException",
+            "message": "Null check operator used on a null value"
+        }));
+        assert!(error.is_error());
+        assert_eq!(
+            error.description(),
+            "Null check operator used on a null value"
+        );
+    }
+
+    #[test]
+    fn instance_children() {
+        let instance: Instance = serde_json::from_value(json!({
+            "type": "Instance",
+            "fields": [{
+                "decl": {"type": "@Field", "name": "count"},
+                "value": {"type": "@Instance", "kind": "Int", "valueAsString": "1"}
+            }],
+            "elements": [{"type": "@Instance", "kind": "Null", "valueAsString": "null"}],
+            "associations": [{
+                "key": {"type": "@Instance", "kind": "String", "valueAsString": "a"},
+                "value": {"type": "@Instance", "kind": "Bool", "valueAsString": "true"}
+            }]
+        }))
+        .unwrap();
+        let children = instance
+            .children()
+            .into_iter()
+            .map(|(name, value)| (name, value.description()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            children,
+            vec![
+                ("count".to_string(), "1".to_string()),
+                ("[0]".to_string(), "null".to_string()),
+                ("[\"a\"]".to_string(), "true".to_string()),
+            ]
+        );
+    }
+}
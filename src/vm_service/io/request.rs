@@ -0,0 +1,168 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamId {
+    #[serde(rename = "VM")]
+    Vm,
+    Isolate,
+    Debug,
+    #[serde(rename = "GC")]
+    Gc,
+    Extension,
+    Logging,
+    Stdout,
+    Stderr,
+    Timeline,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StreamListenParams {
+    #[serde(rename = "streamId")]
+    pub stream_id: StreamId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetIsolateParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+}
+
+/// Parameters of `pause`, `resume`, `kill` and `reloadSources`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IsolateParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetAllocationProfileParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gc: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetCpuSamplesParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SetFlagParams {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SetVmTimelineFlagsParams {
+    #[serde(rename = "recordedStreams")]
+    pub recorded_streams: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetVmTimelineParams {
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EvaluateParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+    #[serde(rename = "targetId")]
+    pub target_id: String,
+    pub expression: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GetObjectParams {
+    #[serde(rename = "isolateId")]
+    pub isolate_id: String,
+    #[serde(rename = "objectId")]
+    pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VmServiceRequest {
+    pub jsonrpc: String,
+    pub id: String,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Map<String, Value>>,
+}
+
+impl VmServiceRequest {
+    pub fn new<P>(id: u32, method: &str, params: Option<P>) -> serde_json::Result<Self>
+    where
+        P: Serialize,
+    {
+        let params = match params.map(serde_json::to_value).transpose()? {
+            Some(Value::Object(map)) => Some(map),
+            _ => None,
+        };
+        Ok(Self {
+            jsonrpc: "2.0".to_string(),
+            id: id.to_string(),
+            method: method.to_string(),
+            params,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_vm() {
+        let request = VmServiceRequest::new::<()>(1, "getVM", None).unwrap();
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(serialized, r#"{"jsonrpc":"2.0","id":"1","method":"getVM"}"#);
+    }
+
+    #[test]
+    fn get_isolate() {
+        let request = VmServiceRequest::new(
+            2,
+            "getIsolate",
+            Some(GetIsolateParams {
+                isolate_id: "isolates/1234".to_string(),
+            }),
+        )
+        .unwrap();
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"jsonrpc":"2.0","id":"2","method":"getIsolate","params":{"isolateId":"isolates/1234"}}"#
+        );
+    }
+
+    #[test]
+    fn stream_listen() {
+        let request = VmServiceRequest::new(
+            3,
+            "streamListen",
+            Some(StreamListenParams {
+                stream_id: StreamId::Gc,
+            }),
+        )
+        .unwrap();
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"jsonrpc":"2.0","id":"3","method":"streamListen","params":{"streamId":"GC"}}"#
+        );
+    }
+}
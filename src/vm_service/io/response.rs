@@ -0,0 +1,251 @@
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::event::Event;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmServiceError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VmServiceResponse<R> {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<R>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<VmServiceError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IsolateRef {
+    pub id: String,
+    pub name: String,
+    pub number: String,
+    #[serde(rename = "isSystemIsolate")]
+    #[serde(default)]
+    pub is_system_isolate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibraryRef {
+    pub id: String,
+    pub name: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vm {
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<i64>,
+    pub isolates: Vec<IsolateRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Isolate {
+    pub id: String,
+    pub name: String,
+    pub number: String,
+    #[serde(rename = "isSystemIsolate")]
+    #[serde(default)]
+    pub is_system_isolate: bool,
+    #[serde(default)]
+    pub runnable: bool,
+    #[serde(rename = "pauseEvent")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause_event: Option<Event>,
+    #[serde(rename = "rootLib")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_lib: Option<LibraryRef>,
+    #[serde(default)]
+    pub libraries: Vec<LibraryRef>,
+    #[serde(rename = "extensionRPCs")]
+    #[serde(default)]
+    pub extension_rpcs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Success {
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReloadReport {
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    #[serde(rename = "heapUsage")]
+    pub heap_usage: u64,
+    #[serde(rename = "heapCapacity")]
+    pub heap_capacity: u64,
+    #[serde(rename = "externalUsage")]
+    pub external_usage: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassRef {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library: Option<LibraryRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassHeapStats {
+    pub class: ClassRef,
+    #[serde(rename = "instancesCurrent")]
+    #[serde(default)]
+    pub instances_current: u64,
+    #[serde(rename = "bytesCurrent")]
+    #[serde(default)]
+    pub bytes_current: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocationProfile {
+    #[serde(default)]
+    pub members: Vec<ClassHeapStats>,
+    #[serde(rename = "memoryUsage")]
+    pub memory_usage: MemoryUsage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileFunction {
+    pub kind: String,
+    #[serde(rename = "resolvedUrl")]
+    #[serde(default)]
+    pub resolved_url: String,
+    /// A `@Function` or `NativeFunction` object.
+    pub function: Value,
+}
+
+impl ProfileFunction {
+    /// The function name, qualified with its class if it has one.
+    pub fn name(&self) -> String {
+        let name = self.function["name"].as_str().unwrap_or("<unknown>");
+        match self.function["owner"]["type"].as_str() {
+            Some("@Class") => match self.function["owner"]["name"].as_str() {
+                Some(owner) => format!("{}.{}", owner, name),
+                None => name.to_string(),
+            },
+            _ => name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuSample {
+    #[serde(default)]
+    pub tid: i64,
+    pub timestamp: i64,
+    /// Indices into `CpuSamples::functions`, starting from the top frame.
+    #[serde(default)]
+    pub stack: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuSamples {
+    #[serde(rename = "samplePeriod")]
+    pub sample_period: i64,
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+    #[serde(default)]
+    pub functions: Vec<ProfileFunction>,
+    #[serde(default)]
+    pub samples: Vec<CpuSample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timeline {
+    /// Events in the Chrome trace event format.
+    #[serde(rename = "traceEvents")]
+    #[serde(default)]
+    pub trace_events: Vec<Value>,
+    #[serde(rename = "timeOriginMicros")]
+    pub time_origin_micros: i64,
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineFlags {
+    #[serde(rename = "availableStreams")]
+    #[serde(default)]
+    pub available_streams: Vec<String>,
+    #[serde(rename = "recordedStreams")]
+    #[serde(default)]
+    pub recorded_streams: Vec<String>,
+}
+
+pub type GetVmResponse = VmServiceResponse<Vm>;
+
+pub type GetIsolateResponse = VmServiceResponse<Isolate>;
+
+pub type SuccessResponse = VmServiceResponse<Success>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm_service::io::event::EventKind;
+
+    #[test]
+    fn get_vm_response() {
+        let json = r#"{"jsonrpc":"2.0","id":"1","result":{"type":"VM","name":"vm","version":"3.2.0","pid":1234,"isolates":[{"type":"@Isolate","id":"isolates/1","name":"main","number":"1","isSystemIsolate":false}]}}"#;
+        let response: GetVmResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response,
+            GetVmResponse {
+                id: "1".to_string(),
+                result: Some(Vm {
+                    name: "vm".to_string(),
+                    version: "3.2.0".to_string(),
+                    pid: Some(1234),
+                    isolates: vec![IsolateRef {
+                        id: "isolates/1".to_string(),
+                        name: "main".to_string(),
+                        number: "1".to_string(),
+                        is_system_isolate: false,
+                    }],
+                }),
+                error: None,
+            }
+        );
+    }
+
+    #[test]
+    fn get_isolate_response() {
+        let json = r#"{"jsonrpc":"2.0","id":"2","result":{"type":"Isolate","id":"isolates/1","name":"main","number":"1","isSystemIsolate":false,"runnable":true,"pauseEvent":{"type":"Event","kind":"Resume","timestamp":1000},"rootLib":{"type":"@Library","id":"libraries/1","name":"","uri":"package:app/main.dart"},"libraries":[],"extensionRPCs":["ext.flutter.debugPaint"]}}"#;
+        let response: GetIsolateResponse = serde_json::from_str(json).unwrap();
+        let isolate = response.result.unwrap();
+        assert_eq!(isolate.pause_event.unwrap().kind, EventKind::Resume);
+        assert_eq!(isolate.root_lib.unwrap().uri, "package:app/main.dart");
+        assert_eq!(
+            isolate.extension_rpcs,
+            vec!["ext.flutter.debugPaint".to_string()]
+        );
+    }
+
+    #[test]
+    fn error_response() {
+        let json = r#"{"jsonrpc":"2.0","id":"3","error":{"code":103,"message":"Stream already subscribed","data":{"details":"The stream 'GC' is already subscribed"}}}"#;
+        let response: SuccessResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.result, None);
+        assert_eq!(response.error.unwrap().code, 103);
+    }
+}
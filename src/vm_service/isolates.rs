@@ -0,0 +1,147 @@
+use color_eyre::{eyre::eyre, Result};
+
+use super::{
+    client::VmService,
+    io::{
+        event::EventKind,
+        request::IsolateParams,
+        response::{Isolate, ReloadReport, Success},
+    },
+};
+
+/// Whether an isolate is running, as shown in the isolates panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolateStatus {
+    Starting,
+    Running,
+    Paused(PauseReason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    Start,
+    Exit,
+    Breakpoint,
+    Interrupted,
+    Exception,
+    PostRequest,
+}
+
+impl IsolateStatus {
+    /// The status an isolate is in after an event of the Isolate or Debug streams.
+    pub fn after(kind: &EventKind) -> Option<Self> {
+        let status = match kind {
+            EventKind::IsolateStart => IsolateStatus::Starting,
+            EventKind::IsolateRunnable | EventKind::Resume => IsolateStatus::Running,
+            EventKind::PauseStart => IsolateStatus::Paused(PauseReason::Start),
+            EventKind::PauseExit => IsolateStatus::Paused(PauseReason::Exit),
+            EventKind::PauseBreakpoint => IsolateStatus::Paused(PauseReason::Breakpoint),
+            EventKind::PauseInterrupted => IsolateStatus::Paused(PauseReason::Interrupted),
+            EventKind::PauseException => IsolateStatus::Paused(PauseReason::Exception),
+            EventKind::PausePostRequest => IsolateStatus::Paused(PauseReason::PostRequest),
+            _ => return None,
+        };
+        Some(status)
+    }
+
+    pub fn of(isolate: &Isolate) -> Self {
+        let status = isolate
+            .pause_event
+            .as_ref()
+            .and_then(|event| Self::after(&event.kind));
+        match status {
+            Some(status) => status,
+            None if isolate.runnable => IsolateStatus::Running,
+            None => IsolateStatus::Starting,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IsolateStatus::Starting => "starting",
+            IsolateStatus::Running => "running",
+            IsolateStatus::Paused(PauseReason::Start) => "paused at start",
+            IsolateStatus::Paused(PauseReason::Exit) => "paused at exit",
+            IsolateStatus::Paused(PauseReason::Breakpoint) => "paused at breakpoint",
+            IsolateStatus::Paused(PauseReason::Interrupted) => "paused",
+            IsolateStatus::Paused(PauseReason::Exception) => "paused at exception",
+            IsolateStatus::Paused(PauseReason::PostRequest) => "paused after reload",
+        }
+    }
+}
+
+impl VmService {
+    pub async fn pause(&self, isolate_id: &str) -> Result<()> {
+        self.isolate_request("pause", isolate_id).await
+    }
+
+    pub async fn resume(&self, isolate_id: &str) -> Result<()> {
+        self.isolate_request("resume", isolate_id).await
+    }
+
+    pub async fn kill(&self, isolate_id: &str) -> Result<()> {
+        self.isolate_request("kill", isolate_id).await
+    }
+
+    /// Reload the sources of an isolate, failing if the VM rejects the reload.
+    pub async fn reload_sources(&self, isolate_id: &str) -> Result<()> {
+        let params = IsolateParams {
+            isolate_id: isolate_id.to_string(),
+        };
+        let report: ReloadReport = self.call("reloadSources", Some(params)).await?;
+        if !report.success {
+            return Err(eyre!("The VM rejected reloading {}", isolate_id));
+        }
+        Ok(())
+    }
+
+    async fn isolate_request(&self, method: &str, isolate_id: &str) -> Result<()> {
+        let params = IsolateParams {
+            isolate_id: isolate_id.to_string(),
+        };
+        let _: Success = self.call(method, Some(params)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    #[test]
+    fn status_of_isolate() {
+        let isolate = |value| serde_json::from_value::<Isolate>(value).unwrap();
+        let paused = isolate(json!({
+            "id": "isolates/1", "name": "main", "number": "1", "runnable": true,
+            "pauseEvent": {"kind": "PauseBreakpoint"}
+        }));
+        assert_eq!(
+            IsolateStatus::of(&paused),
+            IsolateStatus::Paused(PauseReason::Breakpoint)
+        );
+        let starting = isolate(json!({
+            "id": "isolates/2", "name": "worker", "number": "2", "runnable": false,
+            "pauseEvent": {"kind": "None"}
+        }));
+        assert_eq!(IsolateStatus::of(&starting), IsolateStatus::Starting);
+    }
+
+    #[tokio::test]
+    async fn rejected_reload_is_an_error() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "reloadSources");
+            assert_eq!(request["params"]["isolateId"], "isolates/1");
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"type": "ReloadReport", "success": false}
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        assert!(vm_service.reload_sources("isolates/1").await.is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+
+use super::{
+    client::VmService,
+    io::{
+        request::{GetAllocationProfileParams, GetIsolateParams},
+        response::{AllocationProfile, MemoryUsage},
+    },
+};
+
+impl VmService {
+    pub async fn get_memory_usage(&self, isolate_id: &str) -> Result<MemoryUsage> {
+        let params = GetIsolateParams {
+            isolate_id: isolate_id.to_string(),
+        };
+        self.call("getMemoryUsage", Some(params)).await
+    }
+
+    /// Get per-class allocation stats, optionally collecting garbage first.
+    pub async fn get_allocation_profile(
+        &self,
+        isolate_id: &str,
+        gc: bool,
+    ) -> Result<AllocationProfile> {
+        let params = GetAllocationProfileParams {
+            isolate_id: isolate_id.to_string(),
+            gc: gc.then_some(true),
+        };
+        self.call("getAllocationProfile", Some(params)).await
+    }
+}
+
+/// Heap usage at a point in time, in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemorySample {
+    pub timestamp: u64,
+    pub heap_usage: u64,
+    pub heap_capacity: u64,
+    pub external_usage: u64,
+    /// Whether a garbage collection happened since the previous sample.
+    pub gc: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassAllocation {
+    pub name: String,
+    pub library: Option<String>,
+    pub instances: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocationSnapshot {
+    pub timestamp: u64,
+    pub classes: Vec<ClassAllocation>,
+}
+
+impl AllocationSnapshot {
+    pub fn new(timestamp: u64, profile: AllocationProfile) -> Self {
+        let mut classes = profile
+            .members
+            .into_iter()
+            .filter(|stats| stats.instances_current > 0)
+            .map(|stats| ClassAllocation {
+                name: stats.class.name,
+                library: stats.class.library.map(|library| library.uri),
+                instances: stats.instances_current,
+                bytes: stats.bytes_current,
+            })
+            .collect::<Vec<_>>();
+        classes.sort_by_key(|class| std::cmp::Reverse(class.bytes));
+        Self { timestamp, classes }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassAllocationDiff {
+    pub name: String,
+    pub library: Option<String>,
+    pub instances: i64,
+    pub bytes: i64,
+}
+
+/// Classes whose allocations changed between two snapshots, largest growth first.
+pub fn diff_snapshots(
+    before: &AllocationSnapshot,
+    after: &AllocationSnapshot,
+) -> Vec<ClassAllocationDiff> {
+    let key = |class: &ClassAllocation| (class.name.clone(), class.library.clone());
+    let mut diffs = HashMap::new();
+    for class in &before.classes {
+        diffs.insert(
+            key(class),
+            (-(class.instances as i64), -(class.bytes as i64)),
+        );
+    }
+    for class in &after.classes {
+        let diff = diffs.entry(key(class)).or_insert((0, 0));
+        diff.0 += class.instances as i64;
+        diff.1 += class.bytes as i64;
+    }
+    let mut diffs = diffs
+        .into_iter()
+        .filter(|(_, (instances, bytes))| *instances != 0 || *bytes != 0)
+        .map(
+            |((name, library), (instances, bytes))| ClassAllocationDiff {
+                name,
+                library,
+                instances,
+                bytes,
+            },
+        )
+        .collect::<Vec<_>>();
+    diffs.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.name.cmp(&b.name)));
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    fn class(name: &str, instances: u64, bytes: u64) -> ClassAllocation {
+        ClassAllocation {
+            name: name.to_string(),
+            library: Some("package:app/main.dart".to_string()),
+            instances,
+            bytes,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_allocation_profile() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "getAllocationProfile");
+            assert_eq!(request["params"]["gc"], true);
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "AllocationProfile",
+                    "memoryUsage": {
+                        "type": "MemoryUsage",
+                        "heapUsage": 100,
+                        "heapCapacity": 200,
+                        "externalUsage": 10
+                    },
+                    "members": [
+                        {
+                            "type": "ClassHeapStats",
+                            "class": {"type": "@Class", "id": "classes/1", "name": "Small"},
+                            "instancesCurrent": 1,
+                            "bytesCurrent": 16
+                        },
+                        {
+                            "type": "ClassHeapStats",
+                            "class": {"type": "@Class", "id": "classes/2", "name": "Unused"},
+                            "instancesCurrent": 0,
+                            "bytesCurrent": 0
+                        },
+                        {
+                            "type": "ClassHeapStats",
+                            "class": {"type": "@Class", "id": "classes/3", "name": "Large"},
+                            "instancesCurrent": 2,
+                            "bytesCurrent": 64
+                        }
+                    ]
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let profile = vm_service
+            .get_allocation_profile("isolates/1", true)
+            .await
+            .unwrap();
+        assert_eq!(profile.memory_usage.heap_usage, 100);
+        let snapshot = AllocationSnapshot::new(0, profile);
+        let names = snapshot
+            .classes
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Large", "Small"]);
+    }
+
+    #[test]
+    fn diff() {
+        let before = AllocationSnapshot {
+            timestamp: 0,
+            classes: vec![
+                class("Page", 1, 100),
+                class("Gone", 1, 10),
+                class("Same", 3, 30),
+            ],
+        };
+        let after = AllocationSnapshot {
+            timestamp: 1,
+            classes: vec![
+                class("Page", 3, 300),
+                class("New", 1, 50),
+                class("Same", 3, 30),
+            ],
+        };
+        let diffs = diff_snapshots(&before, &after);
+        let diffs = diffs
+            .iter()
+            .map(|d| (d.name.as_str(), d.instances, d.bytes))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diffs,
+            vec![("Page", 2, 200), ("New", 1, 50), ("Gone", -1, -10)]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use color_eyre::Result;
+use serde_json::{Map, Value};
+
+use super::{
+    client::VmService,
+    io::http_profile::{HttpProfile, HttpProfileRequest},
+};
+
+/// Helpers for the `ext.dart.io.*` HTTP profiling service extensions.
+impl VmService {
+    pub async fn set_http_timeline_logging(&self, isolate_id: &str, enabled: bool) -> Result<()> {
+        let params = Map::from_iter([("enabled".to_string(), Value::from(enabled.to_string()))]);
+        self.call_service_extension("ext.dart.io.httpEnableTimelineLogging", isolate_id, params)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_http_profile(
+        &self,
+        isolate_id: &str,
+        updated_since: Option<i64>,
+    ) -> Result<HttpProfile> {
+        let params = updated_since
+            .map(|since| ("updatedSince".to_string(), Value::from(since.to_string())))
+            .into_iter()
+            .collect();
+        let profile = self
+            .call_service_extension("ext.dart.io.getHttpProfile", isolate_id, params)
+            .await?;
+        Ok(serde_json::from_value(profile)?)
+    }
+
+    /// Get a request along with its request and response bodies.
+    pub async fn get_http_profile_request(
+        &self,
+        isolate_id: &str,
+        id: &str,
+    ) -> Result<HttpProfileRequest> {
+        let params = Map::from_iter([("id".to_string(), Value::from(id))]);
+        let request = self
+            .call_service_extension("ext.dart.io.getHttpProfileRequest", isolate_id, params)
+            .await?;
+        Ok(serde_json::from_value(request)?)
+    }
+
+    pub async fn clear_http_profile(&self, isolate_id: &str) -> Result<()> {
+        self.call_service_extension("ext.dart.io.clearHttpProfile", isolate_id, Map::new())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Render a body for display, pretty-printing it if it is JSON.
+pub fn format_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or_default(),
+        Err(_) => String::from_utf8_lossy(body).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    #[tokio::test]
+    async fn get_updated_http_requests() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "ext.dart.io.getHttpProfile");
+            assert_eq!(request["params"]["isolateId"], "isolates/1");
+            assert_eq!(request["params"]["updatedSince"], "1000");
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "HttpProfile",
+                    "timestamp": 2000,
+                    "requests": [{
+                        "type": "@HttpProfileRequest",
+                        "id": "1",
+                        "isolateId": "isolates/1",
+                        "method": "GET",
+                        "uri": "https://example.com",
+                        "events": [],
+                        "startTime": 1500
+                    }]
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let profile = vm_service
+            .get_http_profile("isolates/1", Some(1000))
+            .await
+            .unwrap();
+        assert_eq!(profile.timestamp, 2000);
+        assert_eq!(profile.requests[0].uri, "https://example.com");
+    }
+
+    #[test]
+    fn format_json_body() {
+        assert_eq!(format_body(br#"{"a":1}"#), "{\n  \"a\": 1\n}");
+        assert_eq!(format_body(b"plain text"), "plain text");
+    }
+}
@@ -0,0 +1,372 @@
+use color_eyre::Result;
+use serde_json::{json, Value};
+
+use super::{
+    client::VmService,
+    io::{
+        request::{GetCpuSamplesParams, GetIsolateParams, SetFlagParams},
+        response::{CpuSamples, ProfileFunction, Success, Timestamp},
+    },
+};
+
+impl VmService {
+    pub async fn get_vm_timeline_micros(&self) -> Result<i64> {
+        let timestamp: Timestamp = self.call::<(), _>("getVMTimelineMicros", None).await?;
+        Ok(timestamp.timestamp)
+    }
+
+    pub async fn set_flag(&self, name: &str, value: &str) -> Result<()> {
+        let params = SetFlagParams {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let _: Success = self.call("setFlag", Some(params)).await?;
+        Ok(())
+    }
+
+    pub async fn clear_cpu_samples(&self, isolate_id: &str) -> Result<()> {
+        let params = GetIsolateParams {
+            isolate_id: isolate_id.to_string(),
+        };
+        let _: Success = self.call("clearCpuSamples", Some(params)).await?;
+        Ok(())
+    }
+
+    pub async fn get_cpu_samples(
+        &self,
+        isolate_id: &str,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> Result<CpuSamples> {
+        let params = GetCpuSamplesParams {
+            isolate_id: isolate_id.to_string(),
+            time_origin_micros,
+            time_extent_micros,
+        };
+        self.call("getCpuSamples", Some(params)).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallTreeOrder {
+    /// Callers above callees, starting from the entry points.
+    TopDown,
+    /// Callees above callers, starting from the functions that were running.
+    BottomUp,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallTreeNode {
+    /// Index into `CpuSamples::functions`, or `None` for the root.
+    pub function: Option<usize>,
+    pub self_ticks: u64,
+    pub total_ticks: u64,
+    pub children: Vec<CallTreeNode>,
+}
+
+impl CallTreeNode {
+    fn child(&mut self, function: usize) -> &mut CallTreeNode {
+        let index = match self
+            .children
+            .iter()
+            .position(|child| child.function == Some(function))
+        {
+            Some(index) => index,
+            None => {
+                self.children.push(CallTreeNode {
+                    function: Some(function),
+                    ..CallTreeNode::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    fn sort(&mut self) {
+        self.children
+            .sort_by_key(|child| std::cmp::Reverse(child.total_ticks));
+        for child in self.children.iter_mut() {
+            child.sort();
+        }
+    }
+}
+
+/// Only keeps frames of the given packages, e.g. `my_app` or `package:my_app`.
+/// An empty filter keeps every frame.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackageFilter {
+    prefixes: Vec<String>,
+}
+
+impl PackageFilter {
+    pub fn parse(filter: &str) -> Self {
+        let prefixes = filter
+            .split([',', ' '])
+            .map(|package| package.trim().trim_start_matches("package:"))
+            .filter(|package| !package.is_empty())
+            .map(|package| format!("package:{}/", package.trim_end_matches('/')))
+            .collect();
+        Self { prefixes }
+    }
+
+    pub fn matches(&self, function: &ProfileFunction) -> bool {
+        self.prefixes.is_empty()
+            || self
+                .prefixes
+                .iter()
+                .any(|prefix| function.resolved_url.starts_with(prefix))
+    }
+}
+
+pub fn call_tree(
+    samples: &CpuSamples,
+    order: CallTreeOrder,
+    filter: &PackageFilter,
+) -> CallTreeNode {
+    let mut root = CallTreeNode::default();
+    for sample in &samples.samples {
+        let mut stack = sample
+            .stack
+            .iter()
+            .copied()
+            .filter(|index| {
+                samples
+                    .functions
+                    .get(*index)
+                    .map(|function| filter.matches(function))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        if order == CallTreeOrder::TopDown {
+            stack.reverse();
+        }
+        root.total_ticks += 1;
+        let mut node = &mut root;
+        for (depth, function) in stack.iter().enumerate() {
+            node = node.child(*function);
+            node.total_ticks += 1;
+            let is_running = match order {
+                CallTreeOrder::TopDown => depth == stack.len() - 1,
+                CallTreeOrder::BottomUp => depth == 0,
+            };
+            if is_running {
+                node.self_ticks += 1;
+            }
+        }
+    }
+    root.sort();
+    root
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlameBlock {
+    pub depth: usize,
+    pub x: u16,
+    pub width: u16,
+    pub function: usize,
+}
+
+/// Lay out a top-down call tree as a flame graph `width` columns wide, with the entry
+/// points at depth 0.
+pub fn flame_blocks(tree: &CallTreeNode, width: u16) -> Vec<FlameBlock> {
+    fn layout(
+        node: &CallTreeNode,
+        depth: usize,
+        offset_ticks: u64,
+        total_ticks: u64,
+        width: u16,
+        blocks: &mut Vec<FlameBlock>,
+    ) {
+        let column = |ticks: u64| (ticks * width as u64 / total_ticks) as u16;
+        let mut offset = offset_ticks;
+        for child in &node.children {
+            let x = column(offset);
+            let block_width = column(offset + child.total_ticks) - x;
+            if block_width > 0 {
+                if let Some(function) = child.function {
+                    blocks.push(FlameBlock {
+                        depth,
+                        x,
+                        width: block_width,
+                        function,
+                    });
+                }
+                layout(child, depth + 1, offset, total_ticks, width, blocks);
+            }
+            offset += child.total_ticks;
+        }
+    }
+
+    let mut blocks = vec![];
+    if tree.total_ticks > 0 {
+        layout(tree, 0, 0, tree.total_ticks, width, &mut blocks);
+    }
+    blocks
+}
+
+/// Convert samples to the speedscope file format, which https://www.speedscope.app opens.
+pub fn to_speedscope(samples: &CpuSamples, name: &str) -> Value {
+    let frames = samples
+        .functions
+        .iter()
+        .map(|function| json!({"name": function.name(), "file": function.resolved_url}))
+        .collect::<Vec<_>>();
+    let stacks = samples
+        .samples
+        .iter()
+        .map(|sample| sample.stack.iter().rev().copied().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let weights = vec![samples.sample_period; stacks.len()];
+    json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "shared": {"frames": frames},
+        "profiles": [{
+            "type": "sampled",
+            "name": name,
+            "unit": "microseconds",
+            "startValue": samples.time_origin_micros,
+            "endValue": samples.time_origin_micros + samples.time_extent_micros,
+            "samples": stacks,
+            "weights": weights,
+        }],
+        "exporter": "lazyflutter",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_service::io::response::CpuSample;
+
+    fn function(name: &str, url: &str) -> ProfileFunction {
+        ProfileFunction {
+            kind: "Dart".to_string(),
+            resolved_url: url.to_string(),
+            function: json!({"type": "@Function", "name": name}),
+        }
+    }
+
+    // main -> build -> layout, main -> build, main -> paint (framework)
+    fn samples() -> CpuSamples {
+        let stacks = [vec![2, 1, 0], vec![1, 0], vec![3, 0], vec![2, 1, 0]];
+        CpuSamples {
+            sample_period: 50,
+            time_origin_micros: 1000,
+            time_extent_micros: 200,
+            functions: vec![
+                function("main", "package:app/main.dart"),
+                function("build", "package:app/home.dart"),
+                function("layout", "package:app/home.dart"),
+                function("paint", "package:flutter/src/rendering/object.dart"),
+            ],
+            samples: stacks
+                .into_iter()
+                .enumerate()
+                .map(|(i, stack)| CpuSample {
+                    tid: 1,
+                    timestamp: 1000 + i as i64 * 50,
+                    stack,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn top_down_tree() {
+        let tree = call_tree(
+            &samples(),
+            CallTreeOrder::TopDown,
+            &PackageFilter::default(),
+        );
+        assert_eq!(tree.total_ticks, 4);
+        let main = &tree.children[0];
+        assert_eq!(
+            (main.function, main.total_ticks, main.self_ticks),
+            (Some(0), 4, 0)
+        );
+        let build = &main.children[0];
+        assert_eq!(
+            (build.function, build.total_ticks, build.self_ticks),
+            (Some(1), 3, 1)
+        );
+        assert_eq!(build.children[0].self_ticks, 2);
+        assert_eq!(main.children[1].function, Some(3));
+    }
+
+    #[test]
+    fn bottom_up_tree() {
+        let tree = call_tree(
+            &samples(),
+            CallTreeOrder::BottomUp,
+            &PackageFilter::default(),
+        );
+        let leaves = tree
+            .children
+            .iter()
+            .map(|node| (node.function, node.self_ticks))
+            .collect::<Vec<_>>();
+        assert_eq!(leaves, vec![(Some(2), 2), (Some(1), 1), (Some(3), 1)]);
+        assert_eq!(tree.children[0].children[0].function, Some(1));
+    }
+
+    #[test]
+    fn filter_packages() {
+        let filter = PackageFilter::parse("app");
+        assert!(filter.matches(&function("build", "package:app/home.dart")));
+        assert!(!filter.matches(&function("build", "package:application/home.dart")));
+        let tree = call_tree(&samples(), CallTreeOrder::TopDown, &filter);
+        let main = &tree.children[0];
+        // The framework frame is dropped, so `main` was running in that sample.
+        assert_eq!(main.self_ticks, 1);
+        assert_eq!(main.children.len(), 1);
+    }
+
+    #[test]
+    fn flame_graph_layout() {
+        let tree = call_tree(
+            &samples(),
+            CallTreeOrder::TopDown,
+            &PackageFilter::default(),
+        );
+        let blocks = flame_blocks(&tree, 40);
+        assert_eq!(
+            blocks,
+            vec![
+                FlameBlock {
+                    depth: 0,
+                    x: 0,
+                    width: 40,
+                    function: 0
+                },
+                FlameBlock {
+                    depth: 1,
+                    x: 0,
+                    width: 30,
+                    function: 1
+                },
+                FlameBlock {
+                    depth: 2,
+                    x: 0,
+                    width: 20,
+                    function: 2
+                },
+                FlameBlock {
+                    depth: 1,
+                    x: 30,
+                    width: 10,
+                    function: 3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn speedscope_export() {
+        let profile = to_speedscope(&samples(), "session-1");
+        assert_eq!(profile["shared"]["frames"][1]["name"], "build");
+        assert_eq!(profile["profiles"][0]["samples"][0], json!([0, 1, 2]));
+        assert_eq!(profile["profiles"][0]["weights"], json!([50, 50, 50, 50]));
+        assert_eq!(profile["profiles"][0]["endValue"], 1200);
+    }
+}
@@ -0,0 +1,198 @@
+use color_eyre::Result;
+use serde_json::{Map, Value};
+
+use super::{
+    client::VmService,
+    io::event::{Event, EventKind},
+};
+
+/// Where a widget is created in the source, as reported with `--track-widget-creation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetLocation {
+    pub file: String,
+    pub line: i64,
+    pub column: i64,
+    pub name: Option<String>,
+}
+
+/// The widgets rebuilt during a frame, from a `Flutter.RebuildDirtyWidgets` event.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RebuildStats {
+    /// Rebuild counts by location id.
+    pub counts: Vec<(i64, i64)>,
+    /// Locations seen for the first time, which later events only refer to by id.
+    pub new_locations: Vec<(i64, WidgetLocation)>,
+}
+
+impl RebuildStats {
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.kind != EventKind::Extension
+            || event.extension_kind.as_deref() != Some("Flutter.RebuildDirtyWidgets")
+        {
+            return None;
+        }
+        let data = event.extension_data.as_ref()?;
+        let events = data["events"]
+            .as_array()?
+            .iter()
+            .filter_map(|value| value.as_i64())
+            .collect::<Vec<_>>();
+        let counts = events
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        // Newer Flutter versions send `locations` with widget names along with `newLocations`.
+        let new_locations = match data["locations"].as_object() {
+            Some(locations) => parse_locations(locations),
+            None => data["newLocations"]
+                .as_object()
+                .map(parse_new_locations)
+                .unwrap_or_default(),
+        };
+        Some(Self {
+            counts,
+            new_locations,
+        })
+    }
+}
+
+/// Parse `{file: {ids: [..], lines: [..], columns: [..], names: [..]}}`.
+fn parse_locations(locations: &Map<String, Value>) -> Vec<(i64, WidgetLocation)> {
+    let mut parsed = vec![];
+    for (file, location) in locations {
+        let column = |key: &str| location[key].as_array().cloned().unwrap_or_default();
+        let (ids, lines, columns, names) = (
+            column("ids"),
+            column("lines"),
+            column("columns"),
+            column("names"),
+        );
+        for (index, id) in ids.iter().enumerate() {
+            let Some(id) = id.as_i64() else {
+                continue;
+            };
+            parsed.push((
+                id,
+                WidgetLocation {
+                    file: file.clone(),
+                    line: lines
+                        .get(index)
+                        .and_then(|l| l.as_i64())
+                        .unwrap_or_default(),
+                    column: columns
+                        .get(index)
+                        .and_then(|c| c.as_i64())
+                        .unwrap_or_default(),
+                    name: names
+                        .get(index)
+                        .and_then(|n| n.as_str())
+                        .map(|n| n.to_string()),
+                },
+            ));
+        }
+    }
+    parsed
+}
+
+/// Parse the older `{file: [id, line, column, id, line, column, ..]}` encoding.
+fn parse_new_locations(locations: &Map<String, Value>) -> Vec<(i64, WidgetLocation)> {
+    let mut parsed = vec![];
+    for (file, values) in locations {
+        let values = values
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|value| value.as_i64())
+            .collect::<Vec<_>>();
+        for triple in values.chunks_exact(3) {
+            parsed.push((
+                triple[0],
+                WidgetLocation {
+                    file: file.clone(),
+                    line: triple[1],
+                    column: triple[2],
+                    name: None,
+                },
+            ));
+        }
+    }
+    parsed
+}
+
+impl VmService {
+    /// Post a `Flutter.RebuildDirtyWidgets` event after each frame while enabled.
+    pub async fn set_track_rebuild_dirty_widgets(
+        &self,
+        isolate_id: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let params = Map::from_iter([("enabled".to_string(), Value::from(enabled.to_string()))]);
+        self.call_service_extension(
+            "ext.flutter.inspector.trackRebuildDirtyWidgets",
+            isolate_id,
+            params,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn event(data: Value) -> Event {
+        serde_json::from_value(json!({
+            "kind": "Extension",
+            "extensionKind": "Flutter.RebuildDirtyWidgets",
+            "extensionData": data,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_rebuild_stats() {
+        let stats = RebuildStats::from_event(&event(json!({
+            "startTime": 1000,
+            "events": [1, 3, 2, 1],
+            "newLocations": {"file:///app/lib/main.dart": [1, 10, 5, 2, 20, 7]},
+            "locations": {
+                "file:///app/lib/main.dart": {
+                    "ids": [1, 2],
+                    "lines": [10, 20],
+                    "columns": [5, 7],
+                    "names": ["Text", "Padding"]
+                }
+            }
+        })))
+        .unwrap();
+        assert_eq!(stats.counts, vec![(1, 3), (2, 1)]);
+        assert_eq!(stats.new_locations.len(), 2);
+        assert_eq!(
+            stats.new_locations[1],
+            (
+                2,
+                WidgetLocation {
+                    file: "file:///app/lib/main.dart".to_string(),
+                    line: 20,
+                    column: 7,
+                    name: Some("Padding".to_string()),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn parse_older_location_encoding() {
+        let stats = RebuildStats::from_event(&event(json!({
+            "events": [1, 2],
+            "newLocations": {"file:///app/lib/main.dart": [1, 10, 5]}
+        })))
+        .unwrap();
+        assert_eq!(stats.counts, vec![(1, 2)]);
+        assert_eq!(stats.new_locations[0].1.line, 10);
+        assert_eq!(stats.new_locations[0].1.name, None);
+    }
+}
@@ -0,0 +1,111 @@
+use color_eyre::Result;
+use serde_json::{json, Value};
+
+use super::{
+    client::VmService,
+    io::{
+        request::{GetVmTimelineParams, SetVmTimelineFlagsParams},
+        response::{Success, Timeline, TimelineFlags},
+    },
+};
+
+/// Timeline streams recorded while a recording is running.
+pub const RECORDED_STREAMS: [&str; 4] = ["Dart", "Embedder", "GC", "API"];
+
+impl VmService {
+    pub async fn get_vm_timeline_flags(&self) -> Result<TimelineFlags> {
+        self.call::<(), _>("getVMTimelineFlags", None).await
+    }
+
+    pub async fn set_vm_timeline_flags(&self, recorded_streams: &[&str]) -> Result<()> {
+        let params = SetVmTimelineFlagsParams {
+            recorded_streams: recorded_streams.iter().map(|s| s.to_string()).collect(),
+        };
+        let _: Success = self.call("setVMTimelineFlags", Some(params)).await?;
+        Ok(())
+    }
+
+    pub async fn clear_vm_timeline(&self) -> Result<()> {
+        let _: Success = self.call::<(), _>("clearVMTimeline", None).await?;
+        Ok(())
+    }
+
+    pub async fn get_vm_timeline(
+        &self,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> Result<Timeline> {
+        let params = GetVmTimelineParams {
+            time_origin_micros,
+            time_extent_micros,
+        };
+        self.call("getVMTimeline", Some(params)).await
+    }
+}
+
+/// Wrap the timeline in the Chrome trace JSON object format, which Perfetto and
+/// chrome://tracing load.
+pub fn to_chrome_trace(timeline: &Timeline) -> Value {
+    json!({
+        "traceEvents": timeline.trace_events,
+        "displayTimeUnit": "ms",
+        "otherData": {
+            "timeOriginMicros": timeline.time_origin_micros,
+            "timeExtentMicros": timeline.time_extent_micros,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm_service::client::test::fake_vm_service;
+
+    #[tokio::test]
+    async fn get_vm_timeline() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "getVMTimeline");
+            assert_eq!(request["params"]["timeOriginMicros"], 100);
+            assert_eq!(request["params"]["timeExtentMicros"], 50);
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "Timeline",
+                    "traceEvents": [
+                        {"name": "Frame", "cat": "Embedder", "ph": "X", "ts": 120, "dur": 10, "pid": 1, "tid": 2}
+                    ],
+                    "timeOriginMicros": 100,
+                    "timeExtentMicros": 50
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let timeline = vm_service.get_vm_timeline(100, 50).await.unwrap();
+        let trace = to_chrome_trace(&timeline);
+        assert_eq!(trace["traceEvents"][0]["name"], "Frame");
+        assert_eq!(trace["otherData"]["timeExtentMicros"], 50);
+    }
+
+    #[tokio::test]
+    async fn get_vm_timeline_flags() {
+        let ws_uri = fake_vm_service(|request| {
+            assert_eq!(request["method"], "getVMTimelineFlags");
+            vec![json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "type": "TimelineFlags",
+                    "recorderName": "ring",
+                    "availableStreams": ["API", "Dart", "GC"],
+                    "recordedStreams": ["Dart"]
+                }
+            })]
+        })
+        .await;
+        let vm_service = VmService::connect(&ws_uri).await.unwrap();
+        let flags = vm_service.get_vm_timeline_flags().await.unwrap();
+        assert_eq!(flags.recorded_streams, vec!["Dart"]);
+    }
+}
@@ -0,0 +1,167 @@
+use color_eyre::{eyre::eyre, Result};
+use serde_json::Map;
+use strum::{Display, EnumIter};
+
+use super::client::VmService;
+
+/// The trees a Flutter app can dump through its `ext.flutter.debugDump*` service extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display)]
+pub enum TreeDump {
+    #[strum(to_string = "Widget tree")]
+    App,
+    #[strum(to_string = "Render tree")]
+    RenderTree,
+    #[strum(to_string = "Layer tree")]
+    LayerTree,
+    #[strum(to_string = "Semantics tree")]
+    SemanticsTree,
+    #[strum(to_string = "Focus tree")]
+    FocusTree,
+}
+
+impl TreeDump {
+    pub fn method(&self) -> &'static str {
+        match self {
+            TreeDump::App => "ext.flutter.debugDumpApp",
+            TreeDump::RenderTree => "ext.flutter.debugDumpRenderTree",
+            TreeDump::LayerTree => "ext.flutter.debugDumpLayerTree",
+            TreeDump::SemanticsTree => "ext.flutter.debugDumpSemanticsTreeInTraversalOrder",
+            TreeDump::FocusTree => "ext.flutter.debugDumpFocusTree",
+        }
+    }
+}
+
+/// A node of a dumped tree, listed in pre-order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpNode {
+    pub depth: usize,
+    pub text: String,
+    /// The lines describing the node below its header.
+    pub properties: Vec<String>,
+}
+
+impl VmService {
+    /// Dump a tree of the app, returned as text instead of being printed to the app log.
+    pub async fn debug_dump(&self, isolate_id: &str, dump: TreeDump) -> Result<String> {
+        let result = self
+            .call_service_extension(dump.method(), isolate_id, Map::new())
+            .await?;
+        result["data"]
+            .as_str()
+            .map(|data| data.to_string())
+            .ok_or(eyre!("{} returned no data", dump.method()))
+    }
+}
+
+// Characters drawing the branches of a dump, which are not part of its text.
+const GUIDES: &[char] = &[' ', '│', '╎', '┊', '┆', '║', '┃', '╏'];
+const CONNECTORS: &[char] = &['├', '└', '╘', '╞', '┣', '┗', '╚', '╠'];
+const LINKS: &[char] = &['─', '═', '╦', '╤', '━', '╸'];
+
+/// Split the text of a `debugDump*` into nodes, nesting them by the column of their connector.
+pub fn parse_tree_dump(text: &str) -> Vec<DumpNode> {
+    let mut nodes: Vec<DumpNode> = vec![];
+    // Connector columns and indices of the nodes enclosing the current line.
+    let mut ancestors: Vec<(isize, usize)> = vec![];
+    for line in text.lines() {
+        let chars = line.chars().collect::<Vec<_>>();
+        let Some(column) = chars.iter().position(|c| !GUIDES.contains(c)) else {
+            continue;
+        };
+        let has_connector = CONNECTORS.contains(&chars[column]);
+        if !has_connector && !nodes.is_empty() {
+            if let Some(&(_, index)) = ancestors.last() {
+                nodes[index].properties.push(
+                    chars[column..]
+                        .iter()
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string(),
+                );
+            }
+            continue;
+        }
+        // A root without a connector encloses children connected at its own column.
+        let column = if has_connector {
+            column as isize
+        } else {
+            column as isize - 1
+        };
+        while ancestors.last().is_some_and(|&(c, _)| c >= column) {
+            ancestors.pop();
+        }
+        let text = chars[column.max(0) as usize..]
+            .iter()
+            .skip_while(|c| CONNECTORS.contains(c) || LINKS.contains(c))
+            .collect::<String>();
+        nodes.push(DumpNode {
+            depth: ancestors.len(),
+            text: text.trim().to_string(),
+            properties: vec![],
+        });
+        ancestors.push((column, nodes.len() - 1));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(nodes: &[DumpNode]) -> Vec<(usize, &str)> {
+        nodes
+            .iter()
+            .map(|node| (node.depth, node.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_render_tree() {
+        let dump = "\
+RenderView#1a2b3
+ │ debug mode enabled - linux
+ │
+ └─child: RenderSemanticsAnnotations#c4d5e
+   │ size: Size(800.0, 600.0)
+   │
+   ├─child 1: RenderParagraph#00001
+   │   text: \"a\"
+   └─child 2: RenderPadding#00002
+     └─child: RenderParagraph#00003
+";
+        let nodes = parse_tree_dump(dump);
+        assert_eq!(
+            outline(&nodes),
+            vec![
+                (0, "RenderView#1a2b3"),
+                (1, "child: RenderSemanticsAnnotations#c4d5e"),
+                (2, "child 1: RenderParagraph#00001"),
+                (2, "child 2: RenderPadding#00002"),
+                (3, "child: RenderParagraph#00003"),
+            ]
+        );
+        assert_eq!(nodes[0].properties, vec!["debug mode enabled - linux"]);
+        assert_eq!(nodes[2].properties, vec!["text: \"a\""]);
+    }
+
+    #[test]
+    fn parse_widget_tree() {
+        let dump = "\
+[root](renderObject: RenderView#1a2b3)
+└MyApp
+ └MaterialApp(state: _MaterialAppState#3f2a1)
+  ├Text(\"a\")
+  └Text(\"b\")
+";
+        assert_eq!(
+            outline(&parse_tree_dump(dump)),
+            vec![
+                (0, "[root](renderObject: RenderView#1a2b3)"),
+                (1, "MyApp"),
+                (2, "MaterialApp(state: _MaterialAppState#3f2a1)"),
+                (3, "Text(\"a\")"),
+                (3, "Text(\"b\")"),
+            ]
+        );
+    }
+}